@@ -17,10 +17,9 @@ const BENCH_KEY_ID: yubihsm::ObjectId = 999;
 fn clear_key_slot(hsm: &mut yubihsm::Client) {
     let _ = hsm.delete_object(BENCH_KEY_ID, yubihsm::ObjectType::AsymmetricKey);
 
-    assert!(
-        hsm.get_object_info(BENCH_KEY_ID, yubihsm::ObjectType::AsymmetricKey)
-            .is_err()
-    );
+    assert!(hsm
+        .get_object_info(BENCH_KEY_ID, yubihsm::ObjectType::AsymmetricKey)
+        .is_err());
 }
 
 /// Create a public key for use in a test
@@ -34,7 +33,8 @@ fn generate_key(hsm: &mut yubihsm::Client) {
             yubihsm::Domain::DOM1,
             yubihsm::Capability::ASYMMETRIC_SIGN_EDDSA,
             yubihsm::AsymmetricAlg::Ed25519,
-        ).unwrap_or_else(|e| panic!("error generating asymmetric key: {}", e));
+        )
+        .unwrap_or_else(|e| panic!("error generating asymmetric key: {}", e));
 
     assert_eq!(key_id, BENCH_KEY_ID);
 }
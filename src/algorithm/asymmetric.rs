@@ -1,4 +1,6 @@
-use super::{Algorithm, AlgorithmError, AlgorithmErrorKind::TagInvalid};
+use std::str::FromStr;
+
+use super::{Algorithm, AlgorithmError, AlgorithmErrorKind::TagInvalid, EcdsaAlg};
 
 /// Asymmetric algorithms (RSA or ECC)
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -84,6 +86,46 @@ impl AsymmetricAlg {
             AsymmetricAlg::EC_BP512 => 64,
         }
     }
+
+    /// Return the ECDSA algorithm (i.e. digest) to use with keys on this
+    /// curve, or `None` if this isn't an elliptic curve algorithm
+    pub fn ecdsa_alg(self) -> Option<EcdsaAlg> {
+        match self {
+            AsymmetricAlg::EC_P224
+            | AsymmetricAlg::EC_P256
+            | AsymmetricAlg::EC_K256
+            | AsymmetricAlg::EC_BP256 => Some(EcdsaAlg::SHA256),
+            AsymmetricAlg::EC_P384 | AsymmetricAlg::EC_BP384 => Some(EcdsaAlg::SHA384),
+            AsymmetricAlg::EC_P521 | AsymmetricAlg::EC_BP512 => Some(EcdsaAlg::SHA512),
+            AsymmetricAlg::RSA_2048
+            | AsymmetricAlg::RSA_3072
+            | AsymmetricAlg::RSA_4096
+            | AsymmetricAlg::Ed25519 => None,
+        }
+    }
+}
+
+impl FromStr for AsymmetricAlg {
+    type Err = AlgorithmError;
+
+    /// Parse an asymmetric algorithm from its name as used by `yubihsm-shell`
+    fn from_str(s: &str) -> Result<Self, AlgorithmError> {
+        Ok(match s.to_lowercase().as_ref() {
+            "rsa2048" => AsymmetricAlg::RSA_2048,
+            "rsa3072" => AsymmetricAlg::RSA_3072,
+            "rsa4096" => AsymmetricAlg::RSA_4096,
+            "ed25519" => AsymmetricAlg::Ed25519,
+            "ecp224" => AsymmetricAlg::EC_P224,
+            "ecp256" => AsymmetricAlg::EC_P256,
+            "ecp384" => AsymmetricAlg::EC_P384,
+            "ecp521" => AsymmetricAlg::EC_P521,
+            "eck256" => AsymmetricAlg::EC_K256,
+            "ecbp256" => AsymmetricAlg::EC_BP256,
+            "ecbp384" => AsymmetricAlg::EC_BP384,
+            "ecbp512" => AsymmetricAlg::EC_BP512,
+            _ => fail!(TagInvalid, "unknown asymmetric algorithm: {}", s),
+        })
+    }
 }
 
 impl From<AsymmetricAlg> for Algorithm {
@@ -7,6 +7,9 @@ use super::{Algorithm, AlgorithmError, AlgorithmErrorKind::TagInvalid};
 pub enum AuthAlg {
     /// YubiHSM AES PSK authentication
     YUBICO_AES = 0x26,
+
+    /// YubiHSM EC P-256 asymmetric authentication
+    EC_P256_YUBICO = 0x31,
 }
 
 impl AuthAlg {
@@ -14,6 +17,7 @@ impl AuthAlg {
     pub fn from_u8(tag: u8) -> Result<Self, AlgorithmError> {
         Ok(match tag {
             0x26 => AuthAlg::YUBICO_AES,
+            0x31 => AuthAlg::EC_P256_YUBICO,
             _ => fail!(TagInvalid, "unknown auth algorithm ID: 0x{:02x}", tag),
         })
     }
@@ -27,6 +31,7 @@ impl AuthAlg {
     pub fn key_len(self) -> usize {
         match self {
             AuthAlg::YUBICO_AES => 32,
+            AuthAlg::EC_P256_YUBICO => 64,
         }
     }
 }
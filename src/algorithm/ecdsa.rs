@@ -1,3 +1,6 @@
+#[cfg(feature = "sha2")]
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
 use super::{Algorithm, AlgorithmError, AlgorithmErrorKind::TagInvalid};
 
 /// Valid algorithms for asymmetric keys
@@ -34,6 +37,18 @@ impl EcdsaAlg {
     pub fn to_u8(self) -> u8 {
         self as u8
     }
+
+    /// Hash the given message with this algorithm's digest, or return `None`
+    /// for SHA-1 (which isn't supported)
+    #[cfg(feature = "sha2")]
+    pub(crate) fn digest(self, message: &[u8]) -> Option<Vec<u8>> {
+        Some(match self {
+            EcdsaAlg::SHA256 => Sha256::digest(message).to_vec(),
+            EcdsaAlg::SHA384 => Sha384::digest(message).to_vec(),
+            EcdsaAlg::SHA512 => Sha512::digest(message).to_vec(),
+            EcdsaAlg::SHA1 => return None,
+        })
+    }
 }
 
 impl From<EcdsaAlg> for Algorithm {
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use super::{Algorithm, AlgorithmError, AlgorithmErrorKind::TagInvalid};
 
 /// Valid algorithms for HMAC keys
@@ -56,6 +58,21 @@ impl HmacAlg {
     }
 }
 
+impl FromStr for HmacAlg {
+    type Err = AlgorithmError;
+
+    /// Parse an HMAC algorithm from its name as used by `yubihsm-shell`
+    fn from_str(s: &str) -> Result<Self, AlgorithmError> {
+        Ok(match s.to_lowercase().as_ref() {
+            "hmac-sha1" => HmacAlg::SHA1,
+            "hmac-sha256" => HmacAlg::SHA256,
+            "hmac-sha384" => HmacAlg::SHA384,
+            "hmac-sha512" => HmacAlg::SHA512,
+            _ => fail!(TagInvalid, "unknown HMAC algorithm: {}", s),
+        })
+    }
+}
+
 impl From<HmacAlg> for Algorithm {
     fn from(alg: HmacAlg) -> Algorithm {
         Algorithm::Hmac(alg)
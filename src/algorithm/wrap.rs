@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use super::{Algorithm, AlgorithmError, AlgorithmErrorKind::TagInvalid};
 
 /// Valid algorithms for "wrap" (symmetric encryption/key wrapping) keys
@@ -41,6 +43,20 @@ impl WrapAlg {
     }
 }
 
+impl FromStr for WrapAlg {
+    type Err = AlgorithmError;
+
+    /// Parse a wrap algorithm from its name as used by `yubihsm-shell`
+    fn from_str(s: &str) -> Result<Self, AlgorithmError> {
+        Ok(match s.to_lowercase().as_ref() {
+            "aes128-ccm-wrap" => WrapAlg::AES128_CCM,
+            "aes192-ccm-wrap" => WrapAlg::AES192_CCM,
+            "aes256-ccm-wrap" => WrapAlg::AES256_CCM,
+            _ => fail!(TagInvalid, "unknown wrap algorithm: {}", s),
+        })
+    }
+}
+
 impl From<WrapAlg> for Algorithm {
     fn from(alg: WrapAlg) -> Algorithm {
         Algorithm::Wrap(alg)
@@ -0,0 +1,119 @@
+//! EC P-256 keys for asymmetric authentication to the `YubiHSM2`
+//!
+//! The HSM stores only the public key of an asymmetric auth key (as an auth
+//! key object with `AuthAlg::EC_P256_YUBICO`), so no shared secret needs to
+//! be kept on the client host.
+
+use p256::ecdh::diffie_hellman;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::{PublicKey, SecretKey};
+use rand::{OsRng, RngCore};
+use std::fmt::{self, Debug};
+use zeroize::Zeroize;
+
+use auth_key::{AuthKeyError, AuthKeyErrorKind::SizeInvalid};
+
+/// Size of an asymmetric auth key's secret scalar
+pub const ASYMMETRIC_AUTH_KEY_SIZE: usize = 32;
+
+/// Size of an asymmetric auth key's public key as stored in the HSM
+/// (i.e. the affine X and Y coordinates, without a SEC1 tag byte)
+pub const ASYMMETRIC_AUTH_PUBLIC_KEY_SIZE: usize = 64;
+
+/// Size of an uncompressed SEC1-encoded P-256 point (as sent during the
+/// asymmetric session handshake)
+pub(crate) const UNCOMPRESSED_POINT_SIZE: usize = 65;
+
+/// Size of an ECDH shared secret (i.e. the X coordinate of the shared point)
+pub(crate) const SHARED_SECRET_SIZE: usize = 32;
+
+/// EC P-256 secret key used to authenticate to the HSM
+#[derive(Clone)]
+pub struct AsymmetricAuthKey(SecretKey);
+
+impl AsymmetricAuthKey {
+    /// Generate a random `AsymmetricAuthKey` using `OsRng`
+    pub fn generate() -> Self {
+        let mut rng = OsRng::new().expect("RNG failure!");
+        let mut bytes = [0u8; ASYMMETRIC_AUTH_KEY_SIZE];
+
+        // Retry in the (astronomically unlikely) event the random bytes
+        // aren't a valid scalar
+        loop {
+            rng.fill_bytes(&mut bytes);
+
+            if let Ok(key) = Self::from_slice(&bytes) {
+                bytes.zeroize();
+                return key;
+            }
+        }
+    }
+
+    /// Create an `AsymmetricAuthKey` from a 32-byte big endian secret scalar
+    pub fn from_slice(key_slice: &[u8]) -> Result<Self, AuthKeyError> {
+        ensure!(
+            key_slice.len() == ASYMMETRIC_AUTH_KEY_SIZE,
+            SizeInvalid,
+            "expected {}-byte key, got {}",
+            ASYMMETRIC_AUTH_KEY_SIZE,
+            key_slice.len()
+        );
+
+        SecretKey::from_slice(key_slice)
+            .map(AsymmetricAuthKey)
+            .map_err(|_| err!(SizeInvalid, "invalid P-256 secret scalar"))
+    }
+
+    /// Get the secret scalar (big endian). Take care not to leak it!
+    pub fn secret_bytes(&self) -> [u8; ASYMMETRIC_AUTH_KEY_SIZE] {
+        let mut bytes = [0u8; ASYMMETRIC_AUTH_KEY_SIZE];
+        bytes.copy_from_slice(&self.0.to_bytes());
+        bytes
+    }
+
+    /// Get the public key to store in the HSM with `put_asymmetric_auth_key`
+    pub fn public_key(&self) -> [u8; ASYMMETRIC_AUTH_PUBLIC_KEY_SIZE] {
+        let mut bytes = [0u8; ASYMMETRIC_AUTH_PUBLIC_KEY_SIZE];
+        bytes.copy_from_slice(&self.public_key_point()[1..]);
+        bytes
+    }
+
+    /// Get the public key as an uncompressed SEC1 point
+    pub(crate) fn public_key_point(&self) -> Vec<u8> {
+        self.0
+            .public_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Compute an ECDH shared secret with the given public key, which may be
+    /// an uncompressed SEC1 point or a bare X and Y coordinate pair
+    pub(crate) fn diffie_hellman(
+        &self,
+        public_key: &[u8],
+    ) -> Result<[u8; SHARED_SECRET_SIZE], AuthKeyError> {
+        let public_key = if public_key.len() == ASYMMETRIC_AUTH_PUBLIC_KEY_SIZE {
+            let mut point = Vec::with_capacity(UNCOMPRESSED_POINT_SIZE);
+            point.push(0x04);
+            point.extend_from_slice(public_key);
+            PublicKey::from_sec1_bytes(&point)
+        } else {
+            PublicKey::from_sec1_bytes(public_key)
+        }
+        .map_err(|_| err!(SizeInvalid, "invalid P-256 public key"))?;
+
+        let shared_secret = diffie_hellman(self.0.to_nonzero_scalar(), public_key.as_affine());
+
+        let mut result = [0u8; SHARED_SECRET_SIZE];
+        result.copy_from_slice(shared_secret.raw_secret_bytes());
+        Ok(result)
+    }
+}
+
+impl Debug for AsymmetricAuthKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Avoid leaking secrets in debug messages
+        write!(f, "yubihsm::AsymmetricAuthKey(...)")
+    }
+}
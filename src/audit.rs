@@ -2,6 +2,7 @@ use failure::Error;
 use serde::de::{self, Deserialize, Deserializer, Visitor};
 use serde::ser::{Serialize, Serializer};
 use std::fmt;
+use std::str::FromStr;
 
 use command::CommandCode;
 
@@ -52,6 +53,20 @@ impl AuditOption {
     }
 }
 
+impl FromStr for AuditOption {
+    type Err = Error;
+
+    /// Parse an audit option from `off`, `on`, or `fix`
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s.to_lowercase().as_ref() {
+            "off" => AuditOption::Off,
+            "on" => AuditOption::On,
+            "fix" => AuditOption::Fix,
+            _ => bail!("invalid audit option: {} (expected off, on, or fix)", s),
+        })
+    }
+}
+
 impl Serialize for AuditOption {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serializer.serialize_u8(self.to_u8())
@@ -70,12 +85,12 @@ impl<'de> Deserialize<'de> for AuditOption {
             }
 
             fn visit_u8<E: de::Error>(self, value: u8) -> Result<AuditOption, E> {
-                AuditOption::from_u8(value).or_else(|e| Err(E::custom(format!("{}", e))))
+                AuditOption::from_u8(value).map_err(|e| E::custom(format!("{}", e)))
             }
 
             fn visit_u64<E: de::Error>(self, value: u64) -> Result<AuditOption, E> {
                 assert!(value < 255);
-                AuditOption::from_u8(value as u8).or_else(|e| Err(E::custom(format!("{}", e))))
+                AuditOption::from_u8(value as u8).map_err(|e| E::custom(format!("{}", e)))
             }
         }
 
@@ -125,12 +140,12 @@ impl<'de> Deserialize<'de> for AuditTag {
             }
 
             fn visit_u8<E: de::Error>(self, value: u8) -> Result<AuditTag, E> {
-                AuditTag::from_u8(value).or_else(|e| Err(E::custom(format!("{}", e))))
+                AuditTag::from_u8(value).map_err(|e| E::custom(format!("{}", e)))
             }
 
             fn visit_u64<E: de::Error>(self, value: u64) -> Result<AuditTag, E> {
                 assert!(value < 255);
-                AuditTag::from_u8(value as u8).or_else(|e| Err(E::custom(format!("{}", e))))
+                AuditTag::from_u8(value as u8).map_err(|e| E::custom(format!("{}", e)))
             }
         }
 
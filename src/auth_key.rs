@@ -44,6 +44,9 @@ impl AuthKey {
     /// This method is designed to be compatible with yubihsm-shell. Ensure
     /// you use a long, random password when using this method as the key
     /// derivation algorithm used does little to prevent brute force attacks.
+    ///
+    /// To derive stronger keys (e.g. with a random salt or scrypt), use a
+    /// `PasswordKdf` instead.
     #[cfg(feature = "passwords")]
     pub fn derive_from_password(password: &[u8]) -> Self {
         let mut kdf_output = [0u8; AUTH_KEY_SIZE];
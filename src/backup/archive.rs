@@ -0,0 +1,314 @@
+//! Versioned, line-based backup archive format
+//!
+//! ```text
+//! # yubihsm.rs backup archive v1
+//! wrap_key=0x0010
+//! object type=0x03 id=0x0064 sequence=1 label=<hex> algorithm=0x2e capabilities=0x0000000100000080 delegated=0x0000000000000000 domains=0x0001 pubkey=<hex> sha256=<hex> blob=<hex>
+//! ```
+//!
+//! Every object line is a manifest entry for the wrapped blob at its end.
+//! `pubkey` is only present for asymmetric keys.
+//! Blank lines and other lines starting with `#` are ignored.
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{BackupError, BackupErrorKind::*};
+use hex;
+use {
+    Algorithm, Capability, Domain, ObjectId, ObjectInfo, ObjectLabel, ObjectType, SequenceId,
+    WrapMessage,
+};
+
+/// First line of every archive
+pub const ARCHIVE_HEADER: &str = "# yubihsm.rs backup archive v1";
+
+/// Size of a SHA-256 digest
+const SHA256_SIZE: usize = 32;
+
+/// Objects exported under a wrap key, along with a manifest of what they are
+#[derive(Clone, Debug)]
+pub struct Archive {
+    /// ID of the wrap key the objects were exported under
+    pub wrap_key_id: ObjectId,
+
+    /// Exported objects
+    pub entries: Vec<ArchiveEntry>,
+}
+
+/// An object in an `Archive`
+#[derive(Clone, Debug)]
+pub struct ArchiveEntry {
+    /// ID of the object
+    pub object_id: ObjectId,
+
+    /// Type of the object
+    pub object_type: ObjectType,
+
+    /// Sequence number of the object when it was exported
+    pub sequence: SequenceId,
+
+    /// Label of the object when it was exported
+    pub label: ObjectLabel,
+
+    /// Algorithm of the object
+    pub algorithm: Algorithm,
+
+    /// Capabilities of the object
+    pub capabilities: Capability,
+
+    /// Delegated capabilities of the object
+    pub delegated_capabilities: Capability,
+
+    /// Domains the object is accessible from
+    pub domains: Domain,
+
+    /// Public key of the object (asymmetric keys only)
+    pub public_key: Option<Vec<u8>>,
+
+    /// SHA-256 digest of `wrapped`
+    pub digest: [u8; SHA256_SIZE],
+
+    /// The object encrypted under the wrap key (i.e. `WrapMessage::into_vec`)
+    pub wrapped: Vec<u8>,
+}
+
+impl Archive {
+    /// Create an empty archive for objects exported under the given wrap key
+    pub fn new(wrap_key_id: ObjectId) -> Self {
+        Self {
+            wrap_key_id,
+            entries: vec![],
+        }
+    }
+
+    /// Load an archive from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BackupError> {
+        let mut archive = String::new();
+
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut archive))
+            .map_err(|e| err!(IoError, "{}", e))?;
+
+        archive.parse()
+    }
+
+    /// Save this archive to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BackupError> {
+        File::create(path)
+            .and_then(|mut file| file.write_all(self.to_string().as_bytes()))
+            .map_err(|e| err!(IoError, "{}", e))
+    }
+}
+
+impl fmt::Display for Archive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", ARCHIVE_HEADER)?;
+        writeln!(f, "wrap_key=0x{:04x}", self.wrap_key_id)?;
+
+        for entry in &self.entries {
+            write!(
+                f,
+                "object type=0x{:02x} id=0x{:04x} sequence={} label={} algorithm=0x{:02x} \
+                 capabilities=0x{:016x} delegated=0x{:016x} domains=0x{:04x}",
+                entry.object_type.to_u8(),
+                entry.object_id,
+                entry.sequence,
+                hex::encode(entry.label.as_ref()),
+                entry.algorithm.to_u8(),
+                entry.capabilities.bits(),
+                entry.delegated_capabilities.bits(),
+                entry.domains.bits()
+            )?;
+
+            if let Some(ref public_key) = entry.public_key {
+                write!(f, " pubkey={}", hex::encode(public_key))?;
+            }
+
+            writeln!(
+                f,
+                " sha256={} blob={}",
+                hex::encode(&entry.digest),
+                hex::encode(&entry.wrapped)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Archive {
+    type Err = BackupError;
+
+    fn from_str(s: &str) -> Result<Self, BackupError> {
+        let mut lines = s.lines().enumerate().filter(|&(_, line)| !line.is_empty());
+
+        match lines.next() {
+            Some((_, ARCHIVE_HEADER)) => (),
+            Some((_, line)) if line.starts_with("# yubihsm.rs backup archive") => {
+                fail!(ArchiveInvalid, "unsupported archive version: {}", line)
+            }
+            _ => fail!(ArchiveInvalid, "missing archive header"),
+        }
+
+        let mut lines = lines.filter(|&(_, line)| !line.starts_with('#'));
+
+        let wrap_key_id = match lines.next() {
+            Some((_, line)) if line.starts_with("wrap_key=") => {
+                parse_id(&line["wrap_key=".len()..])
+            }
+            _ => None,
+        }
+        .ok_or_else(|| err!(ArchiveInvalid, "missing wrap key ID"))?;
+
+        let mut archive = Archive::new(wrap_key_id);
+
+        for (line_num, line) in lines {
+            let entry = parse_entry(line)
+                .ok_or_else(|| err!(ArchiveInvalid, "malformed archive line {}", line_num + 1))?;
+
+            archive.entries.push(entry);
+        }
+
+        Ok(archive)
+    }
+}
+
+impl ArchiveEntry {
+    /// Create an entry for a wrapped object from its info (and public key,
+    /// for asymmetric keys), computing the digest of the blob
+    pub fn new(info: ObjectInfo, public_key: Option<Vec<u8>>, wrapped: WrapMessage) -> Self {
+        let wrapped = wrapped.into_vec();
+
+        Self {
+            object_id: info.object_id,
+            object_type: info.object_type,
+            sequence: info.sequence,
+            label: info.label,
+            algorithm: info.algorithm,
+            capabilities: info.capabilities,
+            delegated_capabilities: info.delegated_capabilities,
+            domains: info.domains,
+            public_key,
+            digest: sha256(&wrapped),
+            wrapped,
+        }
+    }
+
+    /// Does the wrapped blob match its recorded digest?
+    pub fn verify_digest(&self) -> bool {
+        sha256(&self.wrapped) == self.digest
+    }
+}
+
+/// Parse an `object` line
+fn parse_entry(line: &str) -> Option<ArchiveEntry> {
+    let mut fields = line.split_whitespace();
+
+    if fields.next() != Some("object") {
+        return None;
+    }
+
+    let mut object_type = None;
+    let mut object_id = None;
+    let mut sequence = None;
+    let mut label = None;
+    let mut algorithm = None;
+    let mut capabilities = None;
+    let mut delegated_capabilities = None;
+    let mut domains = None;
+    let mut public_key = None;
+    let mut digest = None;
+    let mut wrapped = None;
+
+    for field in fields {
+        let mut parts = field.splitn(2, '=');
+        let (key, value) = (parts.next()?, parts.next()?);
+
+        match key {
+            "type" => object_type = Some(ObjectType::from_u8(parse_hex_u8(value)?).ok()?),
+            "id" => object_id = Some(parse_id(value)?),
+            "sequence" => sequence = Some(value.parse().ok()?),
+            "label" => label = Some(ObjectLabel::new(&hex::decode(value)?).ok()?),
+            "algorithm" => algorithm = Some(Algorithm::from_u8(parse_hex_u8(value)?).ok()?),
+            "capabilities" => capabilities = Some(parse_capabilities(value)?),
+            "delegated" => delegated_capabilities = Some(parse_capabilities(value)?),
+            "domains" => domains = Some(parse_domains(value)?),
+            "pubkey" => public_key = Some(hex::decode(value)?),
+            "sha256" => {
+                let bytes = hex::decode(value)?;
+
+                if bytes.len() != SHA256_SIZE {
+                    return None;
+                }
+
+                let mut d = [0u8; SHA256_SIZE];
+                d.copy_from_slice(&bytes);
+                digest = Some(d);
+            }
+            "blob" => wrapped = Some(hex::decode(value)?),
+            _ => return None,
+        }
+    }
+
+    Some(ArchiveEntry {
+        object_id: object_id?,
+        object_type: object_type?,
+        sequence: sequence?,
+        label: label?,
+        algorithm: algorithm?,
+        capabilities: capabilities?,
+        delegated_capabilities: delegated_capabilities?,
+        domains: domains?,
+        public_key,
+        digest: digest?,
+        wrapped: wrapped?,
+    })
+}
+
+/// Parse a `0x`-prefixed object ID
+fn parse_id(value: &str) -> Option<ObjectId> {
+    if !value.starts_with("0x") {
+        return None;
+    }
+
+    ObjectId::from_str_radix(&value[2..], 16).ok()
+}
+
+/// Parse a `0x`-prefixed byte
+fn parse_hex_u8(value: &str) -> Option<u8> {
+    if !value.starts_with("0x") {
+        return None;
+    }
+
+    u8::from_str_radix(&value[2..], 16).ok()
+}
+
+/// Parse `0x`-prefixed capability bitflags
+fn parse_capabilities(value: &str) -> Option<Capability> {
+    if !value.starts_with("0x") {
+        return None;
+    }
+
+    Capability::from_bits(u64::from_str_radix(&value[2..], 16).ok()?)
+}
+
+/// Parse `0x`-prefixed domain bitflags
+fn parse_domains(value: &str) -> Option<Domain> {
+    if !value.starts_with("0x") {
+        return None;
+    }
+
+    Domain::from_bits(u16::from_str_radix(&value[2..], 16).ok()?)
+}
+
+/// Compute the SHA-256 digest of the given data
+fn sha256(data: &[u8]) -> [u8; SHA256_SIZE] {
+    let mut digest = [0u8; SHA256_SIZE];
+    digest.copy_from_slice(Sha256::digest(data).as_slice());
+    digest
+}
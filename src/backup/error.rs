@@ -0,0 +1,34 @@
+//! Errors that occur while backing up or restoring an HSM
+
+use client::{ClientError, ClientErrorKind};
+use error::Error;
+
+/// Backup/restore errors
+pub type BackupError = Error<BackupErrorKind>;
+
+/// Kinds of backup/restore errors
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum BackupErrorKind {
+    /// The archive is malformed or has an unsupported version
+    #[fail(display = "invalid backup archive")]
+    ArchiveInvalid,
+
+    /// Couldn't read or write the archive
+    #[fail(display = "I/O error")]
+    IoError,
+
+    /// Errors communicating with the HSM
+    #[fail(display = "client error: {}", kind)]
+    ClientError {
+        /// Client error kind
+        kind: ClientErrorKind,
+    },
+}
+
+// TODO: capture causes?
+impl From<ClientError> for BackupError {
+    fn from(err: ClientError) -> Self {
+        let kind = BackupErrorKind::ClientError { kind: err.kind() };
+        Error::new(kind, err.message().map(str::to_owned))
+    }
+}
@@ -0,0 +1,204 @@
+//! Whole-device backup and restore: export every object which can be
+//! exported under a wrap key into an `Archive`, and import them back (e.g.
+//! onto a replacement HSM which has the same wrap key).
+//!
+//! Objects without the `EXPORT_UNDER_WRAP` capability can't leave the HSM,
+//! so they're reported as skipped rather than failing the whole backup.
+
+mod archive;
+mod error;
+
+pub use self::archive::{Archive, ArchiveEntry, ARCHIVE_HEADER};
+pub use self::error::{BackupError, BackupErrorKind};
+
+use {Capability, Client, ObjectId, ObjectType, WrapMessage};
+
+/// An object which couldn't be backed up or restored
+#[derive(Clone, Debug)]
+pub struct ObjectError {
+    /// Type of the object
+    pub object_type: ObjectType,
+
+    /// ID of the object
+    pub object_id: ObjectId,
+
+    /// Why the object couldn't be backed up or restored
+    pub reason: String,
+}
+
+/// Result of `export_all`
+#[derive(Clone, Debug)]
+pub struct ExportReport {
+    /// Objects which were exported
+    pub archive: Archive,
+
+    /// Objects which couldn't be exported
+    pub skipped: Vec<ObjectError>,
+}
+
+/// Result of `restore`
+#[derive(Clone, Debug, Default)]
+pub struct RestoreReport {
+    /// Objects which were imported and verified
+    pub restored: Vec<(ObjectType, ObjectId)>,
+
+    /// Objects which couldn't be imported or didn't match the archive
+    pub failed: Vec<ObjectError>,
+}
+
+impl RestoreReport {
+    /// Was every object in the archive restored?
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Export every object with the `EXPORT_UNDER_WRAP` capability under the
+/// given wrap key.
+///
+/// Errors exporting individual objects are recorded in the report's
+/// `skipped` list. Only errors listing the objects in the HSM are returned.
+pub fn export_all(client: &mut Client, wrap_key_id: ObjectId) -> Result<ExportReport, BackupError> {
+    let mut archive = Archive::new(wrap_key_id);
+    let mut skipped = vec![];
+
+    for object in client.list_objects()? {
+        let object_type = object.object_type;
+        let object_id = object.object_id;
+
+        let skip = |reason: String| ObjectError {
+            object_type,
+            object_id,
+            reason,
+        };
+
+        if object_type == ObjectType::WrapKey && object_id == wrap_key_id {
+            skipped.push(skip("wrap key used for the backup".to_owned()));
+            continue;
+        }
+
+        let info = match client.get_object_info(object_id, object_type) {
+            Ok(info) => info,
+            Err(e) => {
+                skipped.push(skip(format!("couldn't get object info: {}", e)));
+                continue;
+            }
+        };
+
+        if !info.capabilities.contains(Capability::EXPORT_UNDER_WRAP) {
+            skipped.push(skip("missing export_under_wrap capability".to_owned()));
+            continue;
+        }
+
+        // Record the public key of asymmetric keys so restores can check it
+        let public_key = if object_type == ObjectType::AsymmetricKey {
+            match client.get_pubkey(object_id) {
+                Ok(public_key) => Some(public_key.bytes),
+                Err(e) => {
+                    skipped.push(skip(format!("couldn't get public key: {}", e)));
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        match client.export_wrapped(wrap_key_id, object_type, object_id) {
+            Ok(wrapped) => archive
+                .entries
+                .push(ArchiveEntry::new(info, public_key, wrapped)),
+            Err(e) => skipped.push(skip(format!("export failed: {}", e))),
+        }
+    }
+
+    Ok(ExportReport { archive, skipped })
+}
+
+/// Import every object in the archive under its wrap key, checking each
+/// blob against its digest first and each imported object against the
+/// archive's manifest afterward (label, algorithm, capabilities, domains,
+/// and the public key of asymmetric keys).
+///
+/// Errors restoring individual objects are recorded in the report's
+/// `failed` list, so one bad entry doesn't stop the rest of the restore.
+pub fn restore(client: &mut Client, archive: &Archive) -> Result<RestoreReport, BackupError> {
+    let mut report = RestoreReport::default();
+
+    for entry in &archive.entries {
+        match restore_entry(client, archive.wrap_key_id, entry) {
+            Ok(()) => report.restored.push((entry.object_type, entry.object_id)),
+            Err(reason) => report.failed.push(ObjectError {
+                object_type: entry.object_type,
+                object_id: entry.object_id,
+                reason,
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Import and verify a single archive entry
+fn restore_entry(
+    client: &mut Client,
+    wrap_key_id: ObjectId,
+    entry: &ArchiveEntry,
+) -> Result<(), String> {
+    if !entry.verify_digest() {
+        return Err("SHA-256 digest mismatch".to_owned());
+    }
+
+    let wrapped = WrapMessage::from_vec(entry.wrapped.clone())
+        .map_err(|e| format!("malformed wrapped object: {}", e))?;
+
+    let imported = client
+        .import_wrapped(wrap_key_id, wrapped)
+        .map_err(|e| format!("import failed: {}", e))?;
+
+    if imported.object_type != entry.object_type || imported.object_id != entry.object_id {
+        return Err(format!(
+            "imported unexpected object: {:?} 0x{:04x}",
+            imported.object_type, imported.object_id
+        ));
+    }
+
+    let info = client
+        .get_object_info(entry.object_id, entry.object_type)
+        .map_err(|e| format!("couldn't verify imported object: {}", e))?;
+
+    if info.label != entry.label {
+        return Err(format!("imported object has label {:?}", info.label));
+    }
+
+    if info.algorithm != entry.algorithm {
+        return Err(format!(
+            "imported object has algorithm {:?}",
+            info.algorithm
+        ));
+    }
+
+    if info.capabilities != entry.capabilities
+        || info.delegated_capabilities != entry.delegated_capabilities
+    {
+        return Err(format!(
+            "imported object has capabilities {:?} (delegated: {:?})",
+            info.capabilities, info.delegated_capabilities
+        ));
+    }
+
+    if info.domains != entry.domains {
+        return Err(format!("imported object has domains {:?}", info.domains));
+    }
+
+    if let Some(ref public_key) = entry.public_key {
+        let imported_key = client
+            .get_pubkey(entry.object_id)
+            .map_err(|e| format!("couldn't get imported public key: {}", e))?;
+
+        if &imported_key.bytes != public_key {
+            return Err("imported object has a different public key".to_owned());
+        }
+    }
+
+    Ok(())
+}
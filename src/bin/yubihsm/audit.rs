@@ -0,0 +1,144 @@
+//! Audit log and audit settings
+
+use failure::Error;
+use yubihsm::{AuditOption, Client, CommandCode};
+
+/// Options for `yubihsm audit-logs`
+#[derive(Debug, Options)]
+pub struct AuditLogsOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, help = "check the digest chain of the returned entries")]
+    verify: bool,
+}
+
+impl AuditLogsOpts {
+    /// Print the audit log, optionally verifying it
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let logs = client.get_audit_logs()?;
+
+        println!(
+            "unlogged boot events: {}  unlogged auth events: {}",
+            logs.unlogged_boot_events, logs.unlogged_auth_events
+        );
+
+        for entry in &logs.entries {
+            println!(
+                "#{:<5} {:?} len={} session_key=0x{:04x} target=0x{:04x} second=0x{:04x} result={:?} tick={} digest={:?}",
+                entry.item,
+                entry.cmd,
+                entry.length,
+                entry.session_key,
+                entry.target_key,
+                entry.second_key,
+                entry.result,
+                entry.tick,
+                entry.digest
+            );
+        }
+
+        if self.verify {
+            if let Some(entry) = logs.first_invalid_entry() {
+                bail!("audit log digest mismatch at entry #{}", entry.item);
+            }
+
+            println!("audit log digest chain verified");
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm get-audit`
+#[derive(Debug, Options)]
+pub struct GetAuditOpts {
+    #[options(help = "print help message")]
+    help: bool,
+}
+
+impl GetAuditOpts {
+    /// Print the forced auditing option and per-command audit settings
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        println!("force audit: {:?}", client.get_force_audit_option()?);
+
+        for setting in client.get_commands_audit_options()? {
+            println!(
+                "{:?} (0x{:02x}): {:?}",
+                setting.command_type(),
+                setting.command_type().to_u8(),
+                setting.audit_option()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm put-audit`
+#[derive(Debug, Options)]
+pub struct PutAuditOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(
+        no_short,
+        meta = "OPTION",
+        help = "set forced auditing: off, on, or fix"
+    )]
+    force: Option<AuditOption>,
+
+    #[options(
+        no_short,
+        meta = "CODE",
+        help = "command code to change the audit setting of (e.g. 0x56)"
+    )]
+    command: Option<String>,
+
+    #[options(
+        no_short,
+        meta = "OPTION",
+        help = "audit setting for --command: off, on, or fix"
+    )]
+    option: Option<AuditOption>,
+}
+
+impl PutAuditOpts {
+    /// Change the forced auditing option and/or a per-command audit setting
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        ensure!(
+            self.force.is_some() || self.command.is_some(),
+            "nothing to do: specify --force and/or --command"
+        );
+
+        if let Some(option) = self.force {
+            client.put_force_audit_option(option)?;
+            println!("force audit: {:?}", option);
+        }
+
+        if let Some(ref code) = self.command {
+            let command = parse_command_code(code)?;
+
+            let option = self
+                .option
+                .ok_or_else(|| format_err!("--command requires --option"))?;
+
+            client.put_command_audit_option(command, option)?;
+            println!("{:?}: {:?}", command, option);
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a command code given as hex (`0x56`) or decimal
+fn parse_command_code(code: &str) -> Result<CommandCode, Error> {
+    let byte = if let Some(hex) = code.strip_prefix("0x") {
+        u8::from_str_radix(hex, 16)
+    } else {
+        code.parse()
+    };
+
+    let byte = byte.map_err(|_| format_err!("invalid command code: {}", code))?;
+    CommandCode::from_u8(byte)
+}
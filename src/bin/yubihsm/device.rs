@@ -0,0 +1,60 @@
+//! Device-level commands
+
+use failure::Error;
+use yubihsm::Client;
+
+/// Options for `yubihsm device-info`
+#[derive(Debug, Options)]
+pub struct DeviceInfoOpts {
+    #[options(help = "print help message")]
+    help: bool,
+}
+
+impl DeviceInfoOpts {
+    /// Print information about the HSM
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let info = client.device_info()?;
+
+        println!(
+            "firmware version: {}.{}.{}",
+            info.major_version, info.minor_version, info.build_version
+        );
+        println!("serial number:    {:010}", info.serial_number);
+        println!(
+            "log store:        {}/{} entries used",
+            info.log_store_used, info.log_store_capacity
+        );
+        println!("algorithms:");
+
+        for algorithm in &info.algorithms {
+            println!("  {:?}", algorithm);
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm storage-status`
+#[derive(Debug, Options)]
+pub struct StorageStatusOpts {
+    #[options(help = "print help message")]
+    help: bool,
+}
+
+impl StorageStatusOpts {
+    /// Print the HSM's storage status
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let status = client.storage_status()?;
+
+        println!(
+            "records: {}/{} free",
+            status.free_records, status.total_records
+        );
+        println!(
+            "pages:   {}/{} free ({} bytes each)",
+            status.free_pages, status.total_pages, status.page_size
+        );
+
+        Ok(())
+    }
+}
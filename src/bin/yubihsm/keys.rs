@@ -0,0 +1,403 @@
+//! Generating, importing, and using keys
+
+use base64;
+use failure::Error;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::fs;
+use std::io::{self, Write};
+use std::str::FromStr;
+use yubihsm::pem;
+use yubihsm::{AsymmetricAlg, Capability, Client, Domain, HmacAlg, ObjectId, ObjectLabel, WrapAlg};
+
+use super::required;
+
+/// Options for `yubihsm generate-asymmetric`
+#[derive(Debug, Options)]
+pub struct GenerateAsymmetricOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "key ID (default: chosen by the HSM)")]
+    id: ObjectId,
+
+    #[options(no_short, help = "key label")]
+    label: String,
+
+    #[options(no_short, help = "domains, e.g. 1,2,3 or all (default: 1)")]
+    domains: Option<Domain>,
+
+    #[options(no_short, help = "comma-separated capabilities")]
+    capabilities: Option<Capability>,
+
+    #[options(no_short, meta = "ALG", help = "algorithm, e.g. ed25519 or ecp256")]
+    algorithm: Option<AsymmetricAlg>,
+}
+
+impl GenerateAsymmetricOpts {
+    /// Generate the key
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let key_id = client.generate_asymmetric_key(
+            self.id,
+            ObjectLabel::new(self.label.as_bytes())?,
+            self.domains.unwrap_or(Domain::DOM1),
+            required(&self.capabilities, "capabilities")?,
+            required(&self.algorithm, "algorithm")?,
+        )?;
+
+        println!("generated asymmetric key 0x{:04x}", key_id);
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm generate-hmac`
+#[derive(Debug, Options)]
+pub struct GenerateHmacOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "key ID (default: chosen by the HSM)")]
+    id: ObjectId,
+
+    #[options(no_short, help = "key label")]
+    label: String,
+
+    #[options(no_short, help = "domains, e.g. 1,2,3 or all (default: 1)")]
+    domains: Option<Domain>,
+
+    #[options(no_short, help = "comma-separated capabilities")]
+    capabilities: Option<Capability>,
+
+    #[options(
+        no_short,
+        meta = "ALG",
+        help = "algorithm, e.g. hmac-sha256 (default: hmac-sha256)"
+    )]
+    algorithm: Option<HmacAlg>,
+}
+
+impl GenerateHmacOpts {
+    /// Generate the key
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let key_id = client.generate_hmac_key(
+            self.id,
+            ObjectLabel::new(self.label.as_bytes())?,
+            self.domains.unwrap_or(Domain::DOM1),
+            required(&self.capabilities, "capabilities")?,
+            self.algorithm.unwrap_or(HmacAlg::SHA256),
+        )?;
+
+        println!("generated HMAC key 0x{:04x}", key_id);
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm generate-wrap`
+#[derive(Debug, Options)]
+pub struct GenerateWrapOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "key ID (default: chosen by the HSM)")]
+    id: ObjectId,
+
+    #[options(no_short, help = "key label")]
+    label: String,
+
+    #[options(no_short, help = "domains, e.g. 1,2,3 or all (default: 1)")]
+    domains: Option<Domain>,
+
+    #[options(no_short, help = "comma-separated capabilities")]
+    capabilities: Option<Capability>,
+
+    #[options(
+        no_short,
+        meta = "CAPABILITIES",
+        help = "capabilities wrapped objects may have (default: none)"
+    )]
+    delegated: Option<Capability>,
+
+    #[options(
+        no_short,
+        meta = "ALG",
+        help = "algorithm, e.g. aes256-ccm-wrap (default: aes256-ccm-wrap)"
+    )]
+    algorithm: Option<WrapAlg>,
+}
+
+impl GenerateWrapOpts {
+    /// Generate the key
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let key_id = client.generate_wrap_key(
+            self.id,
+            ObjectLabel::new(self.label.as_bytes())?,
+            self.domains.unwrap_or(Domain::DOM1),
+            required(&self.capabilities, "capabilities")?,
+            self.delegated.unwrap_or_else(Capability::empty),
+            self.algorithm.unwrap_or(WrapAlg::AES256_CCM),
+        )?;
+
+        println!("generated wrap key 0x{:04x}", key_id);
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm put-asymmetric`
+#[derive(Debug, Options)]
+pub struct PutAsymmetricOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "key ID (default: chosen by the HSM)")]
+    id: ObjectId,
+
+    #[options(no_short, help = "key label")]
+    label: String,
+
+    #[options(no_short, help = "domains, e.g. 1,2,3 or all (default: 1)")]
+    domains: Option<Domain>,
+
+    #[options(no_short, help = "comma-separated capabilities")]
+    capabilities: Option<Capability>,
+
+    #[options(no_short, meta = "ALG", help = "algorithm, e.g. ed25519 or ecp256")]
+    algorithm: Option<AsymmetricAlg>,
+
+    #[options(
+        no_short,
+        meta = "FILE",
+        help = "file containing the raw private key (e.g. a 32-byte Ed25519 seed)"
+    )]
+    key_file: Option<String>,
+}
+
+impl PutAsymmetricOpts {
+    /// Import the key
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let key_bytes = fs::read(required(&self.key_file, "key-file")?)?;
+
+        let key_id = client.put_asymmetric_key(
+            self.id,
+            ObjectLabel::new(self.label.as_bytes())?,
+            self.domains.unwrap_or(Domain::DOM1),
+            required(&self.capabilities, "capabilities")?,
+            required(&self.algorithm, "algorithm")?,
+            key_bytes,
+        )?;
+
+        println!("imported asymmetric key 0x{:04x}", key_id);
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm put-wrap`
+#[derive(Debug, Options)]
+pub struct PutWrapOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "key ID (default: chosen by the HSM)")]
+    id: ObjectId,
+
+    #[options(no_short, help = "key label")]
+    label: String,
+
+    #[options(no_short, help = "domains, e.g. 1,2,3 or all (default: 1)")]
+    domains: Option<Domain>,
+
+    #[options(no_short, help = "comma-separated capabilities")]
+    capabilities: Option<Capability>,
+
+    #[options(
+        no_short,
+        meta = "CAPABILITIES",
+        help = "capabilities wrapped objects may have (default: none)"
+    )]
+    delegated: Option<Capability>,
+
+    #[options(
+        no_short,
+        meta = "ALG",
+        help = "algorithm, e.g. aes256-ccm-wrap (default: aes256-ccm-wrap)"
+    )]
+    algorithm: Option<WrapAlg>,
+
+    #[options(no_short, meta = "FILE", help = "file containing the raw AES key")]
+    key_file: Option<String>,
+}
+
+impl PutWrapOpts {
+    /// Import the key
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let key_bytes = fs::read(required(&self.key_file, "key-file")?)?;
+
+        let key_id = client.put_wrap_key(
+            self.id,
+            ObjectLabel::new(self.label.as_bytes())?,
+            self.domains.unwrap_or(Domain::DOM1),
+            required(&self.capabilities, "capabilities")?,
+            self.delegated.unwrap_or_else(Capability::empty),
+            self.algorithm.unwrap_or(WrapAlg::AES256_CCM),
+            key_bytes,
+        )?;
+
+        println!("imported wrap key 0x{:04x}", key_id);
+        Ok(())
+    }
+}
+
+/// Output formats for public keys
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PublicKeyFormat {
+    /// PEM-encoded `SubjectPublicKeyInfo`
+    Pem,
+
+    /// DER-encoded `SubjectPublicKeyInfo`
+    Der,
+
+    /// Raw key bytes as returned by the HSM
+    Raw,
+}
+
+impl FromStr for PublicKeyFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s {
+            "pem" => PublicKeyFormat::Pem,
+            "der" => PublicKeyFormat::Der,
+            "raw" => PublicKeyFormat::Raw,
+            _ => bail!("invalid format: {} (expected pem, der, or raw)", s),
+        })
+    }
+}
+
+/// Options for `yubihsm get-pubkey`
+#[derive(Debug, Options)]
+pub struct GetPubkeyOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "key ID")]
+    id: Option<ObjectId>,
+
+    #[options(no_short, help = "output format: pem, der, or raw (default: pem)")]
+    format: Option<PublicKeyFormat>,
+
+    #[options(no_short, meta = "FILE", help = "write to a file instead of stdout")]
+    output: Option<String>,
+}
+
+impl GetPubkeyOpts {
+    /// Fetch the public key and write it in the requested format
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let public_key = client.get_pubkey(required(&self.id, "id")?)?;
+
+        let output = match self.format.unwrap_or(PublicKeyFormat::Pem) {
+            PublicKeyFormat::Pem => {
+                pem::encode("PUBLIC KEY", &public_key.to_spki_der()).into_bytes()
+            }
+            PublicKeyFormat::Der => public_key.to_spki_der(),
+            PublicKeyFormat::Raw => public_key.into_vec(),
+        };
+
+        write_output(self.output.as_ref(), &output)
+    }
+}
+
+/// Signature algorithms supported by `yubihsm sign`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SignAlg {
+    /// Ed25519
+    Ed25519,
+
+    /// ECDSA over a SHA-256 digest
+    EcdsaSha256,
+
+    /// ECDSA over a SHA-384 digest
+    EcdsaSha384,
+
+    /// ECDSA over a SHA-512 digest
+    EcdsaSha512,
+}
+
+impl FromStr for SignAlg {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s {
+            "ed25519" => SignAlg::Ed25519,
+            "ecdsa-sha256" => SignAlg::EcdsaSha256,
+            "ecdsa-sha384" => SignAlg::EcdsaSha384,
+            "ecdsa-sha512" => SignAlg::EcdsaSha512,
+            _ => bail!(
+                "invalid signature algorithm: {} (expected ed25519 or ecdsa-sha256/384/512)",
+                s
+            ),
+        })
+    }
+}
+
+/// Options for `yubihsm sign`
+#[derive(Debug, Options)]
+pub struct SignOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "key ID")]
+    id: Option<ObjectId>,
+
+    #[options(
+        no_short,
+        meta = "ALG",
+        help = "ed25519, ecdsa-sha256, ecdsa-sha384, or ecdsa-sha512"
+    )]
+    algorithm: Option<SignAlg>,
+
+    #[options(no_short, meta = "FILE", help = "file to sign")]
+    input: Option<String>,
+
+    #[options(
+        no_short,
+        meta = "FILE",
+        help = "write the raw signature to a file (default: base64 to stdout)"
+    )]
+    output: Option<String>,
+}
+
+impl SignOpts {
+    /// Sign the input file
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let key_id = required(&self.id, "id")?;
+        let data = fs::read(required(&self.input, "input")?)?;
+
+        let signature = match required(&self.algorithm, "algorithm")? {
+            SignAlg::Ed25519 => client.sign_ed25519(key_id, data)?.as_ref().to_vec(),
+            SignAlg::EcdsaSha256 => client
+                .sign_ecdsa(key_id, Sha256::digest(&data).as_slice())?
+                .into_vec(),
+            SignAlg::EcdsaSha384 => client
+                .sign_ecdsa(key_id, Sha384::digest(&data).as_slice())?
+                .into_vec(),
+            SignAlg::EcdsaSha512 => client
+                .sign_ecdsa(key_id, Sha512::digest(&data).as_slice())?
+                .into_vec(),
+        };
+
+        match self.output {
+            Some(ref path) => fs::write(path, &signature)?,
+            None => println!("{}", base64::encode(&signature)),
+        }
+
+        Ok(())
+    }
+}
+
+/// Write output to the given file, or stdout if none was given
+fn write_output(path: Option<&String>, output: &[u8]) -> Result<(), Error> {
+    match path {
+        Some(path) => fs::write(path, output)?,
+        None => io::stdout().write_all(output)?,
+    }
+
+    Ok(())
+}
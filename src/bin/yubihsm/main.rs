@@ -0,0 +1,219 @@
+//! `yubihsm`: command-line tool for day-to-day administration of YubiHSM2 devices
+//!
+//! The connector is selected with `--connector` (or the `YUBIHSM_CONNECTOR`
+//! environment variable) using the same URLs as `yubihsm::connector::from_url`,
+//! e.g. `http://127.0.0.1:12345` or `usb://?serial=0123456789`.
+//!
+//! Sessions are authenticated with `--auth-key` (default 1) and a password
+//! which is either typed in at a prompt (with `--prompt-password`) or taken
+//! from the `YUBIHSM_PASSWORD` environment variable. Passwords can't be given
+//! on the command line, where they'd be visible in the process list. When
+//! neither is given, the default (factory) credentials are used.
+
+#![deny(warnings, missing_docs, trivial_casts, trivial_numeric_casts)]
+#![deny(unsafe_code, unused_import_braces)]
+
+extern crate base64;
+#[macro_use]
+extern crate failure;
+extern crate gumdrop;
+#[macro_use]
+extern crate gumdrop_derive;
+extern crate sha2;
+extern crate yubihsm;
+
+mod audit;
+mod device;
+mod keys;
+mod objects;
+mod provision;
+mod wrap;
+
+use failure::Error;
+use gumdrop::Options;
+use std::{env, process};
+use yubihsm::credentials::{CredentialSource, PromptSource, DEFAULT_AUTH_KEY_ID};
+use yubihsm::{connector, AuthKey, Client, Credentials, ObjectId};
+
+/// Connector URL used if none is given
+const DEFAULT_CONNECTOR_URL: &str = "http://127.0.0.1:12345";
+
+/// Environment variable containing the connector URL
+const CONNECTOR_ENV_VAR: &str = "YUBIHSM_CONNECTOR";
+
+/// Environment variable containing the auth key password
+const PASSWORD_ENV_VAR: &str = "YUBIHSM_PASSWORD";
+
+/// Global options
+#[derive(Debug, Options)]
+struct YubihsmOpts {
+    /// Print help message
+    #[options(help = "print help message")]
+    help: bool,
+
+    /// Connector URL
+    #[options(
+        no_short,
+        meta = "URL",
+        help = "connector URL (default: $YUBIHSM_CONNECTOR or http://127.0.0.1:12345)"
+    )]
+    connector: Option<String>,
+
+    /// Auth key to open the session with
+    #[options(no_short, meta = "ID", help = "auth key ID (default: 1)")]
+    auth_key: Option<ObjectId>,
+
+    /// Prompt for the auth key password
+    #[options(
+        no_short,
+        help = "prompt for the auth key password (default: $YUBIHSM_PASSWORD or the factory default)"
+    )]
+    prompt_password: bool,
+
+    /// Subcommand to run
+    #[options(command)]
+    command: Option<Command>,
+}
+
+/// Subcommands
+#[derive(Debug, Options)]
+enum Command {
+    #[options(help = "show the HSM's firmware version, serial number, and algorithms")]
+    DeviceInfo(device::DeviceInfoOpts),
+
+    #[options(help = "show how much storage is available on the HSM")]
+    StorageStatus(device::StorageStatusOpts),
+
+    #[options(help = "list objects visible to the auth key")]
+    List(objects::ListOpts),
+
+    #[options(help = "show information about an object")]
+    Info(objects::InfoOpts),
+
+    #[options(help = "delete an object")]
+    Delete(objects::DeleteOpts),
+
+    #[options(help = "generate an asymmetric key")]
+    GenerateAsymmetric(keys::GenerateAsymmetricOpts),
+
+    #[options(help = "generate an HMAC key")]
+    GenerateHmac(keys::GenerateHmacOpts),
+
+    #[options(help = "generate a wrap key")]
+    GenerateWrap(keys::GenerateWrapOpts),
+
+    #[options(help = "import an existing asymmetric private key")]
+    PutAsymmetric(keys::PutAsymmetricOpts),
+
+    #[options(help = "import an existing wrap key")]
+    PutWrap(keys::PutWrapOpts),
+
+    #[options(help = "get the public key for an asymmetric key")]
+    GetPubkey(keys::GetPubkeyOpts),
+
+    #[options(help = "sign a file with an asymmetric key")]
+    Sign(keys::SignOpts),
+
+    #[options(help = "export an object encrypted under a wrap key")]
+    Export(wrap::ExportOpts),
+
+    #[options(help = "import an object encrypted under a wrap key")]
+    Import(wrap::ImportOpts),
+
+    #[options(help = "fetch (and optionally verify) the audit log")]
+    AuditLogs(audit::AuditLogsOpts),
+
+    #[options(help = "show audit settings")]
+    GetAudit(audit::GetAuditOpts),
+
+    #[options(help = "change audit settings")]
+    PutAudit(audit::PutAuditOpts),
+
+    #[options(help = "bring the HSM in line with a manifest")]
+    Provision(provision::ProvisionOpts),
+}
+
+impl YubihsmOpts {
+    /// Open a client using the connector and credentials from the options
+    fn client(&self) -> Result<Client, Error> {
+        let url = match self.connector {
+            Some(ref url) => url.clone(),
+            None => {
+                env::var(CONNECTOR_ENV_VAR).unwrap_or_else(|_| DEFAULT_CONNECTOR_URL.to_owned())
+            }
+        };
+
+        let auth_key_id = self.auth_key.unwrap_or(DEFAULT_AUTH_KEY_ID);
+
+        let credentials = if self.prompt_password {
+            PromptSource::new(auth_key_id).credentials()?
+        } else {
+            match env::var(PASSWORD_ENV_VAR) {
+                Ok(password) => Credentials::from_password(auth_key_id, password.as_bytes()),
+                Err(_) => Credentials::new(auth_key_id, AuthKey::default()),
+            }
+        };
+
+        Ok(Client::open(
+            connector::from_url(&url)?,
+            credentials,
+            false,
+        )?)
+    }
+}
+
+impl Command {
+    /// Run this command with the given client
+    fn run(&self, client: &mut Client) -> Result<(), Error> {
+        match *self {
+            Command::DeviceInfo(ref opts) => opts.run(client),
+            Command::StorageStatus(ref opts) => opts.run(client),
+            Command::List(ref opts) => opts.run(client),
+            Command::Info(ref opts) => opts.run(client),
+            Command::Delete(ref opts) => opts.run(client),
+            Command::GenerateAsymmetric(ref opts) => opts.run(client),
+            Command::GenerateHmac(ref opts) => opts.run(client),
+            Command::GenerateWrap(ref opts) => opts.run(client),
+            Command::PutAsymmetric(ref opts) => opts.run(client),
+            Command::PutWrap(ref opts) => opts.run(client),
+            Command::GetPubkey(ref opts) => opts.run(client),
+            Command::Sign(ref opts) => opts.run(client),
+            Command::Export(ref opts) => opts.run(client),
+            Command::Import(ref opts) => opts.run(client),
+            Command::AuditLogs(ref opts) => opts.run(client),
+            Command::GetAudit(ref opts) => opts.run(client),
+            Command::PutAudit(ref opts) => opts.run(client),
+            Command::Provision(ref opts) => opts.run(client),
+        }
+    }
+}
+
+fn main() {
+    let opts = YubihsmOpts::parse_args_default_or_exit();
+
+    let command = match opts.command {
+        Some(ref command) => command,
+        None => {
+            eprintln!("Usage: yubihsm [OPTIONS] COMMAND [COMMAND OPTIONS]\n");
+            eprintln!("{}\n", YubihsmOpts::usage());
+            eprintln!("Commands:\n{}", Command::usage());
+            process::exit(2);
+        }
+    };
+
+    let result = opts
+        .client()
+        .and_then(|mut client| command.run(&mut client));
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Parse a required option, returning an error naming the option if missing
+fn required<T: Clone>(value: &Option<T>, name: &str) -> Result<T, Error> {
+    value
+        .clone()
+        .ok_or_else(|| format_err!("missing required option: --{}", name))
+}
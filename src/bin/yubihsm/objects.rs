@@ -0,0 +1,100 @@
+//! Listing, inspecting, and deleting objects
+
+use failure::Error;
+use yubihsm::{Client, ObjectId, ObjectType};
+
+use super::required;
+
+/// Options for `yubihsm list`
+#[derive(Debug, Options)]
+pub struct ListOpts {
+    #[options(help = "print help message")]
+    help: bool,
+}
+
+impl ListOpts {
+    /// Print the ID, type, and sequence of every visible object
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let objects = client.list_objects()?;
+
+        for entry in &objects {
+            println!(
+                "id: 0x{:04x}  type: {:?}  sequence: {}",
+                entry.object_id, entry.object_type, entry.sequence
+            );
+        }
+
+        println!("{} object(s)", objects.len());
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm info`
+#[derive(Debug, Options)]
+pub struct InfoOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "object ID")]
+    id: Option<ObjectId>,
+
+    #[options(
+        no_short,
+        long = "type",
+        meta = "TYPE",
+        help = "object type (e.g. asymmetric-key, wrap-key)"
+    )]
+    object_type: Option<ObjectType>,
+}
+
+impl InfoOpts {
+    /// Print everything the HSM reports about an object
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let info = client.get_object_info(
+            required(&self.id, "id")?,
+            required(&self.object_type, "type")?,
+        )?;
+
+        println!("id:                       0x{:04x}", info.object_id);
+        println!("type:                     {:?}", info.object_type);
+        println!("algorithm:                {:?}", info.algorithm);
+        println!("label:                    {:?}", info.label);
+        println!("length:                   {}", info.length);
+        println!("domains:                  {:?}", info.domains);
+        println!("sequence:                 {}", info.sequence);
+        println!("origin:                   {:?}", info.origin);
+        println!("capabilities:             {:?}", info.capabilities);
+        println!(
+            "delegated capabilities:   {:?}",
+            info.delegated_capabilities
+        );
+
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm delete`
+#[derive(Debug, Options)]
+pub struct DeleteOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "object ID")]
+    id: Option<ObjectId>,
+
+    #[options(no_short, long = "type", meta = "TYPE", help = "object type")]
+    object_type: Option<ObjectType>,
+}
+
+impl DeleteOpts {
+    /// Delete the object
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let object_id = required(&self.id, "id")?;
+        let object_type = required(&self.object_type, "type")?;
+
+        client.delete_object(object_id, object_type)?;
+        println!("deleted {:?} 0x{:04x}", object_type, object_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+//! Provisioning an HSM from a manifest
+
+use failure::Error;
+use yubihsm::provision::{Manifest, Plan};
+use yubihsm::Client;
+
+use super::required;
+
+/// Options for `yubihsm provision`
+#[derive(Debug, Options)]
+pub struct ProvisionOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(
+        no_short,
+        meta = "FILE",
+        help = "TOML manifest describing the desired objects"
+    )]
+    manifest: Option<String>,
+
+    #[options(
+        no_short,
+        help = "make the planned changes (default: only print the plan)"
+    )]
+    apply: bool,
+
+    #[options(
+        no_short,
+        help = "replace keys which differ if the manifest sets allow_replace (destroys them)"
+    )]
+    force: bool,
+}
+
+impl ProvisionOpts {
+    /// Print the plan for the manifest, and apply it if requested
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let mut manifest = Manifest::load_toml(required(&self.manifest, "manifest")?)?;
+        manifest.allow_replace &= self.force;
+
+        let plan = Plan::new(client, &manifest)?;
+
+        print!("{}", plan);
+
+        if plan.is_empty() {
+            println!("nothing to do");
+        } else if self.apply {
+            plan.apply(client)?;
+            println!("applied {} change(s)", plan.changes().len());
+        } else {
+            println!("run again with --apply to make these changes");
+        }
+
+        if !plan.drifted().is_empty() {
+            println!(
+                "objects marked with ~ won't be replaced (replacing keys requires \
+                 allow_replace in the manifest and --force)"
+            );
+        }
+
+        Ok(())
+    }
+}
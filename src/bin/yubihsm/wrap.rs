@@ -0,0 +1,89 @@
+//! Exporting and importing objects encrypted under a wrap key
+//!
+//! Wrapped objects are stored as base64 (the same format as `yubihsm-shell`
+//! uses), so files can be exchanged between the two tools.
+
+use base64;
+use failure::Error;
+use std::fs;
+use yubihsm::{Client, ObjectId, ObjectType, WrapMessage};
+
+use super::required;
+
+/// Options for `yubihsm export`
+#[derive(Debug, Options)]
+pub struct ExportOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(no_short, meta = "ID", help = "ID of the wrap key to export under")]
+    wrap_key: Option<ObjectId>,
+
+    #[options(no_short, meta = "ID", help = "ID of the object to export")]
+    id: Option<ObjectId>,
+
+    #[options(
+        no_short,
+        long = "type",
+        meta = "TYPE",
+        help = "type of the object to export"
+    )]
+    object_type: Option<ObjectType>,
+
+    #[options(no_short, meta = "FILE", help = "file to write the wrapped object to")]
+    output: Option<String>,
+}
+
+impl ExportOpts {
+    /// Export the object to a file
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let object_id = required(&self.id, "id")?;
+        let object_type = required(&self.object_type, "type")?;
+        let path = required(&self.output, "output")?;
+
+        let wrapped = client.export_wrapped(
+            required(&self.wrap_key, "wrap-key")?,
+            object_type,
+            object_id,
+        )?;
+
+        fs::write(&path, format!("{}\n", base64::encode(&wrapped.into_vec())))?;
+        println!("exported {:?} 0x{:04x} to {}", object_type, object_id, path);
+
+        Ok(())
+    }
+}
+
+/// Options for `yubihsm import`
+#[derive(Debug, Options)]
+pub struct ImportOpts {
+    #[options(help = "print help message")]
+    help: bool,
+
+    #[options(
+        no_short,
+        meta = "ID",
+        help = "ID of the wrap key the object was exported under"
+    )]
+    wrap_key: Option<ObjectId>,
+
+    #[options(no_short, meta = "FILE", help = "file containing the wrapped object")]
+    input: Option<String>,
+}
+
+impl ImportOpts {
+    /// Import the object from a file
+    pub fn run(&self, client: &mut Client) -> Result<(), Error> {
+        let encoded = fs::read_to_string(required(&self.input, "input")?)?;
+        let wrapped = WrapMessage::from_vec(base64::decode(encoded.trim())?)?;
+
+        let imported = client.import_wrapped(required(&self.wrap_key, "wrap-key")?, wrapped)?;
+
+        println!(
+            "imported {:?} 0x{:04x}",
+            imported.object_type, imported.object_id
+        );
+
+        Ok(())
+    }
+}
@@ -1,11 +1,17 @@
 // Apparently bitflags isn't clippy-safe
-#![cfg_attr(clippy, allow(clippy::redundant_field_names, clippy::suspicious_arithmetic_impl))]
+#![cfg_attr(
+    clippy,
+    allow(clippy::redundant_field_names, clippy::suspicious_arithmetic_impl)
+)]
 
 use std::fmt;
+use std::str::FromStr;
 
 use serde::de::{self, Deserialize, Deserializer, Visitor};
 use serde::ser::{Serialize, Serializer};
 
+use error::Error;
+
 bitflags! {
     /// Object attributes specifying which operations are allowed to be performed
     ///
@@ -41,6 +47,9 @@ bitflags! {
         /// audit: read the log store
         const AUDIT = 0x100_0000;
 
+        /// change_auth_key: change the secret of the session's own AuthKey
+        const CHANGE_AUTHKEY = 0x4000_0000_0000;
+
         /// delete_asymmetric: delete asymmetric key objects
         const DELETE_ASYMMETRIC = 0x200_0000_0000;
 
@@ -149,9 +158,6 @@ bitflags! {
         /// wrap_data: wrap user-provided data
         const WRAP_DATA = 0x20_0000_0000;
 
-        /// Unknown Capability (Bit 46)
-        const CAP46 = 0x4000_0000_0000;
-
         /// Unknown Capability (Bit 47)
         const CAP47 = 0x8000_0000_0000;
 
@@ -205,12 +211,112 @@ bitflags! {
     }
 }
 
+impl Capability {
+    /// Unknown Capability (Bit 46), now known to be `CHANGE_AUTHKEY`
+    #[deprecated(note = "use Capability::CHANGE_AUTHKEY")]
+    pub const CAP46: Capability = Capability::CHANGE_AUTHKEY;
+}
+
 impl Default for Capability {
     fn default() -> Self {
         Capability::empty()
     }
 }
 
+/// Errors parsing capabilities
+pub type CapabilityError = Error<CapabilityErrorKind>;
+
+/// Kinds of errors parsing capabilities
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum CapabilityErrorKind {
+    /// Unknown capability name
+    #[fail(display = "unknown capability")]
+    NameInvalid,
+}
+
+/// Names of capabilities, as used by `yubihsm-shell`
+const CAPABILITY_NAMES: &[(&str, Capability)] = &[
+    ("change-authentication-key", Capability::CHANGE_AUTHKEY),
+    ("create-otp-aead", Capability::OTP_AEAD_CREATE),
+    ("decrypt-oaep", Capability::ASYMMETRIC_DECRYPT_OAEP),
+    ("decrypt-otp", Capability::OTP_DECRYPT),
+    ("decrypt-pkcs", Capability::ASYMMETRIC_DECRYPT_PKCS),
+    ("delete-asymmetric-key", Capability::DELETE_ASYMMETRIC),
+    ("delete-authentication-key", Capability::DELETE_AUTHKEY),
+    ("delete-hmac-key", Capability::DELETE_HMACKEY),
+    ("delete-opaque", Capability::DELETE_OPAQUE),
+    ("delete-otp-aead-key", Capability::DELETE_OTP_AEAD_KEY),
+    ("delete-template", Capability::DELETE_TEMPLATE),
+    ("delete-wrap-key", Capability::DELETE_WRAPKEY),
+    ("derive-ecdh", Capability::ASYMMETRIC_DECRYPT_ECDH),
+    ("export-wrapped", Capability::EXPORT_WRAPPED),
+    ("exportable-under-wrap", Capability::EXPORT_UNDER_WRAP),
+    ("generate-asymmetric-key", Capability::ASYMMETRIC_GEN),
+    ("generate-hmac-key", Capability::HMACKEY_GENERATE),
+    ("generate-otp-aead-key", Capability::GENERATE_OTP_AEAD_KEY),
+    ("generate-wrap-key", Capability::GENERATE_WRAPKEY),
+    ("get-log-entries", Capability::AUDIT),
+    ("get-opaque", Capability::GET_OPAQUE),
+    ("get-option", Capability::GET_OPTION),
+    ("get-pseudo-random", Capability::GET_RANDOMNESS),
+    ("get-template", Capability::GET_TEMPLATE),
+    ("import-wrapped", Capability::IMPORT_WRAPPED),
+    ("put-asymmetric-key", Capability::PUT_ASYMMETRIC),
+    ("put-authentication-key", Capability::PUT_AUTHKEY),
+    ("put-mac-key", Capability::PUT_HMACKEY),
+    ("put-opaque", Capability::PUT_OPAQUE),
+    ("put-option", Capability::PUT_OPTION),
+    ("put-otp-aead-key", Capability::PUT_OTP_AEAD_KEY),
+    ("put-template", Capability::PUT_TEMPLATE),
+    ("put-wrap-key", Capability::PUT_WRAPKEY),
+    ("randomize-otp-aead", Capability::OTP_AEAD_RANDOM),
+    ("reset-device", Capability::RESET),
+    ("rewrap-from-otp-aead-key", Capability::OTP_AEAD_REWRAP_FROM),
+    ("rewrap-to-otp-aead-key", Capability::OTP_AEAD_REWRAP_TO),
+    ("sign-attestation-certificate", Capability::ATTEST),
+    ("sign-ecdsa", Capability::ASYMMETRIC_SIGN_ECDSA),
+    ("sign-eddsa", Capability::ASYMMETRIC_SIGN_EDDSA),
+    ("sign-hmac", Capability::HMAC_DATA),
+    ("sign-pkcs", Capability::ASYMMETRIC_SIGN_PKCS),
+    ("sign-pss", Capability::ASYMMETRIC_SIGN_PSS),
+    ("sign-ssh-certificate", Capability::SSH_CERTIFY),
+    ("unwrap-data", Capability::UNWRAP_DATA),
+    ("verify-hmac", Capability::HMAC_VERIFY),
+    ("wrap-data", Capability::WRAP_DATA),
+];
+
+impl FromStr for Capability {
+    type Err = CapabilityError;
+
+    /// Parse a comma-separated list of capability names as used by
+    /// `yubihsm-shell` (e.g. `sign-eddsa,exportable-under-wrap`), `all`, or
+    /// `none`. Underscores may be used in place of hyphens.
+    fn from_str(s: &str) -> Result<Self, CapabilityError> {
+        match s.trim() {
+            "all" => return Ok(Capability::all()),
+            "" | "none" => return Ok(Capability::empty()),
+            _ => (),
+        }
+
+        let mut capabilities = Capability::empty();
+
+        for name in s.split(',').map(str::trim) {
+            let normalized = name.to_lowercase().replace('_', "-");
+
+            match CAPABILITY_NAMES.iter().find(|&&(n, _)| n == normalized) {
+                Some(&(_, capability)) => capabilities |= capability,
+                None => fail!(
+                    CapabilityErrorKind::NameInvalid,
+                    "unknown capability: {}",
+                    name
+                ),
+            }
+        }
+
+        Ok(capabilities)
+    }
+}
+
 impl Serialize for Capability {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
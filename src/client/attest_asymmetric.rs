@@ -29,11 +29,7 @@ impl Response for AttestationCertificate {
 }
 
 // TODO: use clippy's scoped lints once they work on stable
-#[allow(
-    unknown_lints,
-    renamed_and_removed_lints,
-    len_without_is_empty
-)]
+#[allow(unknown_lints, renamed_and_removed_lints, len_without_is_empty)]
 impl AttestationCertificate {
     /// Unwrap inner byte vector
     pub fn into_vec(self) -> Vec<u8> {
@@ -57,8 +53,8 @@ impl AsRef<[u8]> for AttestationCertificate {
     }
 }
 
-impl Into<Vec<u8>> for AttestationCertificate {
-    fn into(self) -> Vec<u8> {
-        self.0
+impl From<AttestationCertificate> for Vec<u8> {
+    fn from(certificate: AttestationCertificate) -> Vec<u8> {
+        certificate.0
     }
 }
@@ -0,0 +1,246 @@
+//! Builder for `Client` instances
+
+use super::{Client, ClientError, ClientErrorKind::ConfigError, RetryPolicy};
+use connector::{Connector, ConnectorConfig};
+use credentials::{CredentialSource, Credentials};
+use observer::Observer;
+use session::{SessionTimeout, SESSION_INACTIVITY_TIMEOUT};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default target for log messages emitted by `Client`
+pub const DEFAULT_LOG_TARGET: &str = "yubihsm::client";
+
+/// Builder for `Client` instances.
+///
+/// The credentials can't be deserialized and must always be set via
+/// `credentials()` or `credential_source()`, but all other options can be
+/// loaded from a configuration file, e.g. in TOML:
+///
+/// ```toml
+/// session_timeout_ms = 20000
+/// reconnect = true
+/// healthcheck_on_open = false
+/// log_target = "myapp::hsm"
+///
+/// [retry_policy]
+/// max_attempts = 3
+/// backoff_ms = 100
+/// max_backoff_ms = 5000
+/// retryable_errors = ["ConnectionFailed", "IoError"]
+///
+/// [connector]
+/// type = "usb"
+/// serial = "0123456789"
+/// timeout_ms = 1000
+/// ```
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ClientBuilder {
+    /// Method for connecting to the HSM
+    #[serde(skip)]
+    connector: Option<Box<dyn Connector>>,
+
+    /// Configuration for creating a connector (if one wasn't given explicitly)
+    #[serde(rename = "connector")]
+    connector_config: Option<ConnectorConfig>,
+
+    /// Source of the credentials to authenticate with
+    #[serde(skip)]
+    credentials: Option<Box<dyn CredentialSource>>,
+
+    /// Session inactivity timeout in milliseconds
+    session_timeout_ms: u64,
+
+    /// Reopen sessions which have timed out or been closed
+    reconnect: bool,
+
+    /// Policy for retrying idempotent commands after connection errors
+    retry_policy: RetryPolicy,
+
+    /// Target for log messages emitted by the `Client`
+    log_target: String,
+
+    /// Check the connector is healthy before opening each session
+    healthcheck_on_open: bool,
+
+    /// Observer to notify about commands and sessions
+    #[serde(skip)]
+    observer: Option<Arc<dyn Observer>>,
+}
+
+impl ClientBuilder {
+    /// Create a new `ClientBuilder` with the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `Connector` used to communicate with the HSM
+    pub fn connector<C>(mut self, connector: C) -> Self
+    where
+        C: Into<Box<dyn Connector>>,
+    {
+        self.connector = Some(connector.into());
+        self
+    }
+
+    /// Set the `ConnectorConfig` used to create a `Connector`, if one isn't
+    /// set explicitly via `connector()`
+    pub fn connector_config(mut self, connector_config: ConnectorConfig) -> Self {
+        self.connector_config = Some(connector_config);
+        self
+    }
+
+    /// Set the `Credentials` used to authenticate sessions
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(Box::new(credentials));
+        self
+    }
+
+    /// Set a `CredentialSource` to load credentials from each time a
+    /// session is opened, instead of keeping `Credentials` in memory
+    pub fn credential_source<S>(mut self, source: S) -> Self
+    where
+        S: CredentialSource + 'static,
+    {
+        self.credentials = Some(Box::new(source));
+        self
+    }
+
+    /// Set the session inactivity timeout (default 30 seconds).
+    ///
+    /// This can't be longer than the HSM's own 30 second inactivity timeout.
+    pub fn session_timeout(mut self, timeout: SessionTimeout) -> Self {
+        let duration = timeout.duration();
+        self.session_timeout_ms = duration.as_secs() * 1000 + u64::from(duration.subsec_millis());
+        self
+    }
+
+    /// Reopen sessions which have timed out or been closed (default true).
+    ///
+    /// If disabled, the credential source is discarded after the first session
+    /// is opened, and the `Client` can no longer be used once it is closed.
+    pub fn reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Set the `RetryPolicy` (default: never retry)
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the target for log messages emitted by the `Client`.
+    ///
+    /// This only applies to the `Client`'s own messages: sessions and
+    /// connectors log under their module paths (e.g. `yubihsm::session`).
+    pub fn log_target<S: Into<String>>(mut self, log_target: S) -> Self {
+        self.log_target = log_target.into();
+        self
+    }
+
+    /// Check the connector is healthy before opening each session (default true)
+    pub fn healthcheck_on_open(mut self, healthcheck_on_open: bool) -> Self {
+        self.healthcheck_on_open = healthcheck_on_open;
+        self
+    }
+
+    /// Set an `Observer` to notify about every command sent to the HSM and
+    /// every session opened
+    pub fn observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Build a `Client`, deferring connecting until the first command
+    pub fn build(self) -> Result<Client, ClientError> {
+        let max_timeout_ms = SESSION_INACTIVITY_TIMEOUT.as_secs() * 1000;
+
+        ensure!(
+            self.session_timeout_ms > 0 && self.session_timeout_ms <= max_timeout_ms,
+            ConfigError,
+            "invalid session timeout: {}ms (must be 1-{}ms)",
+            self.session_timeout_ms,
+            max_timeout_ms
+        );
+
+        let connector = match (self.connector, self.connector_config) {
+            (Some(connector), _) => connector,
+            (None, Some(config)) => config.new_connector()?,
+            (None, None) => fail!(ConfigError, "no connector configured"),
+        };
+
+        let credentials = self
+            .credentials
+            .ok_or_else(|| err!(ConfigError, "no credentials configured"))?;
+
+        Ok(Client {
+            connector,
+            session: None,
+            credentials: Some(credentials),
+            session_timeout: SessionTimeout::new(Duration::from_millis(self.session_timeout_ms)),
+            reconnect: self.reconnect,
+            retry_policy: self.retry_policy,
+            log_target: self.log_target,
+            healthcheck_on_open: self.healthcheck_on_open,
+            observer: self.observer,
+            has_connected: false,
+        })
+    }
+
+    /// Build a `Client` and open a session with the HSM
+    pub fn open(self) -> Result<Client, ClientError> {
+        let mut client = self.build()?;
+        client.connect()?;
+        Ok(client)
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        let session_timeout = SessionTimeout::default().duration();
+
+        Self {
+            connector: None,
+            connector_config: None,
+            credentials: None,
+            session_timeout_ms: session_timeout.as_secs() * 1000,
+            reconnect: true,
+            retry_policy: RetryPolicy::default(),
+            log_target: DEFAULT_LOG_TARGET.to_owned(),
+            healthcheck_on_open: true,
+            observer: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_with_timeout(timeout_ms: u64) -> Result<Client, ClientError> {
+        ClientBuilder::new()
+            .session_timeout(SessionTimeout::new(Duration::from_millis(timeout_ms)))
+            .build()
+    }
+
+    #[test]
+    fn rejects_invalid_session_timeouts() {
+        for &timeout_ms in &[0, 30_001, 60_000] {
+            let err = build_with_timeout(timeout_ms).err().unwrap();
+            assert_eq!(err.kind(), ConfigError);
+            assert!(err.to_string().contains("invalid session timeout"));
+        }
+    }
+
+    #[test]
+    fn accepts_device_session_timeout() {
+        // Fails later, for want of a connector
+        let err = build_with_timeout(30_000).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "configuration error: no connector configured"
+        );
+    }
+}
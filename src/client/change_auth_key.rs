@@ -0,0 +1,37 @@
+//! Change the secret of the auth key used to authenticate the current session
+//!
+//! <https://developers.yubico.com/YubiHSM2/Commands/Change_Authentication_Key.html>
+
+use algorithm::Algorithm;
+use auth_key::AuthKey;
+use command::{Command, CommandCode};
+use object::ObjectId;
+use response::Response;
+
+/// Request parameters for `command::change_auth_key`
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct ChangeAuthKeyCommand {
+    /// ID of the auth key (must be the one the session is authenticated with)
+    pub key_id: ObjectId,
+
+    /// Algorithm of the new auth key
+    pub algorithm: Algorithm,
+
+    /// New authentication key
+    pub auth_key: AuthKey,
+}
+
+impl Command for ChangeAuthKeyCommand {
+    type ResponseType = ChangeAuthKeyResponse;
+}
+
+/// Response from `command::change_auth_key`
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct ChangeAuthKeyResponse {
+    /// ID of the key
+    pub key_id: ObjectId,
+}
+
+impl Response for ChangeAuthKeyResponse {
+    const COMMAND_CODE: CommandCode = CommandCode::ChangeAuthKey;
+}
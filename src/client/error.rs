@@ -1,10 +1,10 @@
 //! Errors that occur during sessions
 
 use connector::{ConnectionError, ConnectionErrorKind};
+use credentials::{CredentialsError, CredentialsErrorKind};
 use error::{Error, HsmErrorKind};
 use serialization::SerializationError;
 use session::{SessionError, SessionErrorKind};
-use std::error::Error as StdError;
 
 /// Session errors
 pub type ClientError = Error<ClientErrorKind>;
@@ -20,6 +20,10 @@ pub enum ClientErrorKind {
     #[fail(display = "session closed")]
     ClosedSessionError,
 
+    /// Client configuration is invalid or incomplete
+    #[fail(display = "configuration error")]
+    ConfigError,
+
     /// Errors with the connection to the HSM
     #[fail(display = "connection error")]
     ConnectionError {
@@ -31,6 +35,13 @@ pub enum ClientErrorKind {
     #[fail(display = "couldn't create session")]
     CreateFailed,
 
+    /// Couldn't load credentials from the `CredentialSource`
+    #[fail(display = "credentials error: {}", kind)]
+    CredentialsError {
+        /// Credentials error kind
+        kind: CredentialsErrorKind,
+    },
+
     /// Errors originating in the HSM device
     #[fail(display = "HSM error: {}", kind)]
     DeviceError {
@@ -38,6 +49,10 @@ pub enum ClientErrorKind {
         kind: HsmErrorKind,
     },
 
+    /// Invalid argument given (e.g. a key of the wrong type)
+    #[fail(display = "invalid argument")]
+    InvalidArgument,
+
     /// Protocol error occurred
     #[fail(display = "protocol error")]
     ProtocolError,
@@ -51,7 +66,15 @@ pub enum ClientErrorKind {
 impl From<ConnectionError> for ClientError {
     fn from(err: ConnectionError) -> Self {
         let kind = ClientErrorKind::ConnectionError { kind: err.kind() };
-        err!(kind, err.description())
+        Error::new(kind, err.message().map(str::to_owned))
+    }
+}
+
+// TODO: capture causes?
+impl From<CredentialsError> for ClientError {
+    fn from(err: CredentialsError) -> Self {
+        let kind = ClientErrorKind::CredentialsError { kind: err.kind() };
+        Error::new(kind, err.message().map(str::to_owned))
     }
 }
 
@@ -61,6 +84,7 @@ impl From<SessionError> for ClientError {
         let kind = match err.kind() {
             SessionErrorKind::AuthFail => ClientErrorKind::AuthFail,
             SessionErrorKind::ClosedSessionError => ClientErrorKind::ClosedSessionError,
+            SessionErrorKind::ConnectionError { kind } => ClientErrorKind::ConnectionError { kind },
             SessionErrorKind::CreateFailed => ClientErrorKind::CreateFailed,
             SessionErrorKind::DeviceError { kind } => ClientErrorKind::DeviceError { kind },
             SessionErrorKind::ProtocolError
@@ -70,13 +94,16 @@ impl From<SessionError> for ClientError {
             SessionErrorKind::ResponseError => ClientErrorKind::ResponseError,
         };
 
-        err!(kind, err.description())
+        Error::new(kind, err.message().map(str::to_owned))
     }
 }
 
 // TODO: capture causes?
 impl From<SerializationError> for ClientError {
     fn from(err: SerializationError) -> Self {
-        err!(ClientErrorKind::ProtocolError, err.description())
+        Error::new(
+            ClientErrorKind::ProtocolError,
+            err.message().map(str::to_owned),
+        )
     }
 }
@@ -2,6 +2,10 @@
 //!
 //! <https://developers.yubico.com/YubiHSM2/Commands/Get_Logs.html>
 
+#[cfg(feature = "sha2")]
+use byteorder::{BigEndian, WriteBytesExt};
+#[cfg(feature = "sha2")]
+use sha2::{Digest, Sha256};
 use std::fmt::{self, Debug};
 
 use command::{Command, CommandCode};
@@ -36,6 +40,22 @@ impl Response for AuditLogs {
     const COMMAND_CODE: CommandCode = CommandCode::GetLogs;
 }
 
+#[cfg(feature = "sha2")]
+impl AuditLogs {
+    /// Check the digest chain of the returned entries, returning the first
+    /// entry whose digest doesn't match its contents and the previous
+    /// entry's digest (or `None` if the chain is intact).
+    ///
+    /// The first entry is only used as the starting point of the chain, since
+    /// the digest of the entry before it isn't available.
+    pub fn first_invalid_entry(&self) -> Option<&LogEntry> {
+        self.entries
+            .windows(2)
+            .find(|pair| !pair[1].follows(&pair[0]))
+            .map(|pair| &pair[1])
+    }
+}
+
 /// Entry in the log response
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LogEntry {
@@ -67,6 +87,34 @@ pub struct LogEntry {
     pub digest: LogDigest,
 }
 
+#[cfg(feature = "sha2")]
+impl LogEntry {
+    /// Does this entry's digest match its contents chained with the digest
+    /// of the given previous entry?
+    pub fn follows(&self, previous: &LogEntry) -> bool {
+        let mut hasher = Sha256::new();
+        hasher.input(&self.serialize_fields());
+        hasher.input(previous.digest.as_ref());
+
+        let digest = hasher.result();
+        digest[..LOG_DIGEST_SIZE] == self.digest.0[..]
+    }
+
+    /// Serialize the fields covered by the digest
+    fn serialize_fields(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16);
+        bytes.write_u16::<BigEndian>(self.item).unwrap();
+        bytes.push(self.cmd.to_u8());
+        bytes.write_u16::<BigEndian>(self.length).unwrap();
+        bytes.write_u16::<BigEndian>(self.session_key).unwrap();
+        bytes.write_u16::<BigEndian>(self.target_key).unwrap();
+        bytes.write_u16::<BigEndian>(self.second_key).unwrap();
+        bytes.push(self.result.to_u8());
+        bytes.write_u32::<BigEndian>(self.tick).unwrap();
+        bytes
+    }
+}
+
 /// Size of a truncated digest in the log
 pub const LOG_DIGEST_SIZE: usize = 16;
 
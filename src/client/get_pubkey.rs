@@ -3,6 +3,7 @@
 //! <https://developers.yubico.com/YubiHSM2/Commands/Get_Pubkey.html>
 
 use command::{Command, CommandCode};
+use der;
 use response::Response;
 use {AsymmetricAlg, ObjectId};
 
@@ -43,11 +44,7 @@ impl Response for PublicKey {
 }
 
 // TODO: use clippy's scoped lints once they work on stable
-#[allow(
-    unknown_lints,
-    renamed_and_removed_lints,
-    len_without_is_empty
-)]
+#[allow(unknown_lints, renamed_and_removed_lints, len_without_is_empty)]
 impl PublicKey {
     /// Unwrap inner byte vector
     pub fn into_vec(self) -> Vec<u8> {
@@ -63,6 +60,71 @@ impl PublicKey {
     pub fn as_slice(&self) -> &[u8] {
         self.as_ref()
     }
+
+    /// Serialize this public key as a DER-encoded X.509 `SubjectPublicKeyInfo`
+    /// (i.e. the contents of a `-----BEGIN PUBLIC KEY-----` PEM file).
+    ///
+    /// RSA keys are assumed to have the public exponent 65537, which is the
+    /// only exponent the `YubiHSM2` uses.
+    pub fn to_spki_der(&self) -> Vec<u8> {
+        let (algorithm_id, subject_public_key) = match (self.algorithm, curve_oid(self.algorithm)) {
+            (_, Some(curve)) => {
+                let mut point = Vec::with_capacity(self.bytes.len() + 1);
+                point.push(0x04);
+                point.extend_from_slice(&self.bytes);
+
+                (
+                    der::sequence(&[&der::oid(OID_EC_PUBLIC_KEY), &der::oid(curve)]),
+                    point,
+                )
+            }
+            (AsymmetricAlg::Ed25519, None) => {
+                (der::sequence(&[&der::oid(OID_ED25519)]), self.bytes.clone())
+            }
+            // Every other algorithm is RSA
+            (_, None) => {
+                let rsa_public_key = der::sequence(&[
+                    &der::unsigned_integer(&self.bytes),
+                    &der::unsigned_integer(&RSA_PUBLIC_EXPONENT),
+                ]);
+
+                (
+                    der::sequence(&[&der::oid(OID_RSA_ENCRYPTION), &der::null()]),
+                    rsa_public_key,
+                )
+            }
+        };
+
+        der::sequence(&[&algorithm_id, &der::bit_string(&subject_public_key)])
+    }
+}
+
+/// Public exponent of RSA keys generated by the `YubiHSM2` (65537)
+const RSA_PUBLIC_EXPONENT: [u8; 3] = [0x01, 0x00, 0x01];
+
+/// rsaEncryption (RFC 3279)
+const OID_RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113_549, 1, 1, 1];
+
+/// id-Ed25519 (RFC 8410)
+const OID_ED25519: &[u64] = &[1, 3, 101, 112];
+
+/// id-ecPublicKey (RFC 5480)
+pub(crate) const OID_EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10_045, 2, 1];
+
+/// Get the named curve OID for an elliptic curve algorithm, or `None` if the
+/// algorithm isn't an elliptic curve
+pub(crate) fn curve_oid(alg: AsymmetricAlg) -> Option<&'static [u64]> {
+    Some(match alg {
+        AsymmetricAlg::EC_P224 => &[1, 3, 132, 0, 33],
+        AsymmetricAlg::EC_P256 => &[1, 2, 840, 10_045, 3, 1, 7],
+        AsymmetricAlg::EC_P384 => &[1, 3, 132, 0, 34],
+        AsymmetricAlg::EC_P521 => &[1, 3, 132, 0, 35],
+        AsymmetricAlg::EC_K256 => &[1, 3, 132, 0, 10],
+        AsymmetricAlg::EC_BP256 => &[1, 3, 36, 3, 3, 2, 8, 1, 1, 7],
+        AsymmetricAlg::EC_BP384 => &[1, 3, 36, 3, 3, 2, 8, 1, 1, 11],
+        AsymmetricAlg::EC_BP512 => &[1, 3, 36, 3, 3, 2, 8, 1, 1, 13],
+        _ => return None,
+    })
 }
 
 impl AsRef<[u8]> for PublicKey {
@@ -71,8 +133,8 @@ impl AsRef<[u8]> for PublicKey {
     }
 }
 
-impl Into<Vec<u8>> for PublicKey {
-    fn into(self) -> Vec<u8> {
-        self.bytes
+impl From<PublicKey> for Vec<u8> {
+    fn from(public_key: PublicKey) -> Vec<u8> {
+        public_key.bytes
     }
 }
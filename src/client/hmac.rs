@@ -29,11 +29,7 @@ impl Response for HMACTag {
 }
 
 // TODO: use clippy's scoped lints once they work on stable
-#[allow(
-    unknown_lints,
-    renamed_and_removed_lints,
-    len_without_is_empty
-)]
+#[allow(unknown_lints, renamed_and_removed_lints, len_without_is_empty)]
 impl HMACTag {
     /// Create a new HMAC tag
     pub fn new<V: Into<Vec<u8>>>(vec: V) -> HMACTag {
@@ -74,8 +70,8 @@ impl<'a> From<&'a [u8]> for HMACTag {
     }
 }
 
-impl Into<Vec<u8>> for HMACTag {
-    fn into(self) -> Vec<u8> {
-        self.0
+impl From<HMACTag> for Vec<u8> {
+    fn from(tag: HMACTag) -> Vec<u8> {
+        tag.0
     }
 }
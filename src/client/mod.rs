@@ -10,6 +10,8 @@ mod error;
 
 pub mod attest_asymmetric;
 pub mod blink;
+mod builder;
+pub mod change_auth_key;
 pub mod delete_object;
 pub mod device_info;
 pub mod echo;
@@ -36,6 +38,7 @@ pub mod put_option;
 pub mod put_otp_aead_key;
 pub mod put_wrap_key;
 pub mod reset;
+mod retry;
 pub mod set_log_index;
 pub mod sign_ecdsa;
 pub mod sign_eddsa;
@@ -43,6 +46,8 @@ pub mod sign_eddsa;
 pub mod sign_rsa_pkcs1v15;
 #[cfg(feature = "rsa")]
 pub mod sign_rsa_pss;
+#[cfg(feature = "secp256k1")]
+pub mod sign_secp256k1;
 pub mod storage_status;
 pub mod unwrap_data;
 pub mod verify_hmac;
@@ -50,35 +55,45 @@ pub mod wrap_data;
 
 #[cfg(feature = "rsa")]
 use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::thread;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+pub use self::builder::ClientBuilder;
 pub use self::error::{ClientError, ClientErrorKind};
+pub use self::retry::RetryPolicy;
 
 use self::error::ClientErrorKind::*;
+#[cfg(feature = "secp256k1")]
+use self::sign_secp256k1::*;
 use self::{
-    attest_asymmetric::*, blink::*, delete_object::*, device_info::*, echo::*, export_wrapped::*,
-    generate_asymmetric_key::*, generate_hmac_key::*, generate_key::*, generate_wrap_key::*,
-    get_logs::*, get_object_info::*, get_opaque::*, get_option::*, get_pseudo_random::*,
-    get_pubkey::*, hmac::*, import_wrapped::*, list_objects::*, put_asymmetric_key::*,
-    put_auth_key::*, put_hmac_key::*, put_object::*, put_opaque::*, put_option::*,
-    put_otp_aead_key::*, put_wrap_key::*, reset::*, set_log_index::*, sign_ecdsa::*, sign_eddsa::*,
-    storage_status::*, unwrap_data::*, verify_hmac::*, wrap_data::*,
+    attest_asymmetric::*, blink::*, change_auth_key::*, delete_object::*, device_info::*, echo::*,
+    export_wrapped::*, generate_asymmetric_key::*, generate_hmac_key::*, generate_key::*,
+    generate_wrap_key::*, get_logs::*, get_object_info::*, get_opaque::*, get_option::*,
+    get_pseudo_random::*, get_pubkey::*, hmac::*, import_wrapped::*, list_objects::*,
+    put_asymmetric_key::*, put_auth_key::*, put_hmac_key::*, put_object::*, put_opaque::*,
+    put_option::*, put_otp_aead_key::*, put_wrap_key::*, reset::*, set_log_index::*, sign_ecdsa::*,
+    sign_eddsa::*, storage_status::*, unwrap_data::*, verify_hmac::*, wrap_data::*,
 };
 #[cfg(feature = "rsa")]
 use self::{sign_rsa_pkcs1v15::*, sign_rsa_pss::*};
 use algorithm::*;
 use audit::*;
 use auth_key::AuthKey;
-#[cfg(feature = "rsa")]
-use byteorder::{BigEndian, ByteOrder};
 use capability::Capability;
-use command::{Command, CommandCode};
+use command::{Command, CommandCode, CommandMessage};
 use connector::Connector;
-use credentials::Credentials;
+#[cfg(feature = "passwords")]
+use credentials::DEFAULT_AUTH_KEY_ID;
+use credentials::{AuthMethod, CredentialSource, Credentials};
 use domain::Domain;
 use object::{ObjectHandle, ObjectId, ObjectInfo, ObjectLabel, ObjectType};
+use observer::{ConnectEvent, Observer};
+use serial_number::SerialNumber;
 use serialization::{deserialize, serialize};
+#[cfg(feature = "passwords")]
+use session::SessionErrorKind;
 use session::{Session, SessionId, SessionTimeout};
 use wrap::WrapMessage;
 
@@ -86,13 +101,34 @@ use wrap::WrapMessage;
 /// HSM hardware device.
 pub struct Client {
     /// Method for connecting to the HSM
-    connector: Box<Connector>,
+    connector: Box<dyn Connector>,
 
     /// Encrypted session with the HSM (if we have one open)
     session: Option<Session>,
 
-    /// Cached `Credentials` for reconnecting closed sessions
-    credentials: Option<Credentials>,
+    /// Source of `Credentials` for opening (and reopening) sessions
+    credentials: Option<Box<dyn CredentialSource>>,
+
+    /// Inactivity timeout for new sessions
+    session_timeout: SessionTimeout,
+
+    /// Keep the credential source after opening a session, so it can be reopened
+    reconnect: bool,
+
+    /// Policy for retrying idempotent commands after connection errors
+    retry_policy: RetryPolicy,
+
+    /// Target for log messages
+    log_target: String,
+
+    /// Check the connector is healthy before opening each session
+    healthcheck_on_open: bool,
+
+    /// Observer to notify about commands and sessions
+    observer: Option<Arc<dyn Observer>>,
+
+    /// Has a session been opened successfully yet?
+    has_connected: bool,
 }
 
 impl Client {
@@ -109,31 +145,26 @@ impl Client {
         reconnect: bool,
     ) -> Result<Self, ClientError>
     where
-        C: Into<Box<Connector>>,
+        C: Into<Box<dyn Connector>>,
     {
-        let mut client = Self::new(connector, credentials)?;
-        client.connect()?;
-
-        // Clear credentials if reconnecting has been disabled
-        if !reconnect {
-            client.credentials = None;
-        }
-
-        Ok(client)
+        ClientBuilder::new()
+            .connector(connector)
+            .credentials(credentials)
+            .reconnect(reconnect)
+            .open()
     }
 
     /// Create a `yubihsm::Client`, but defer connecting until `connect()` is called.
+    ///
+    /// Use `ClientBuilder` to configure options besides the connector and credentials.
     pub fn new<C>(connector: C, credentials: Credentials) -> Result<Self, ClientError>
     where
-        C: Into<Box<Connector>>,
+        C: Into<Box<dyn Connector>>,
     {
-        let client = Self {
-            connector: connector.into(),
-            session: None,
-            credentials: Some(credentials),
-        };
-
-        Ok(client)
+        ClientBuilder::new()
+            .connector(connector)
+            .credentials(credentials)
+            .build()
     }
 
     /// Connect to the HSM (idempotently, i.e. returns success if we have
@@ -150,7 +181,47 @@ impl Client {
 
     /// Get the current session ID (if we have an open session).
     pub fn session_id(&self) -> Option<SessionId> {
-        self.session.as_ref().and_then(|s| Some(s.id()))
+        self.session.as_ref().map(|s| s.id())
+    }
+
+    /// Get the serial number of the HSM, as reported by the `Connector`
+    pub fn serial_number(&self) -> Result<SerialNumber, ClientError> {
+        Ok(self.connector.serial_number()?)
+    }
+
+    /// Get the public key (`x || y`) of the HSM's device key, which it
+    /// authenticates itself with when opening asymmetric sessions.
+    ///
+    /// This is fetched without a session, so it should be obtained over a
+    /// trusted connection and pinned with `Credentials::asymmetric`.
+    ///
+    /// <https://developers.yubico.com/YubiHSM2/Commands/Get_Device_Public_Key.html>
+    #[cfg(feature = "asymmetric-auth")]
+    pub fn device_public_key(&self) -> Result<Vec<u8>, ClientError> {
+        Ok(::session::device_public_key(&*self.connector)?)
+    }
+
+    /// Get the policy for retrying commands after connection errors
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Set the policy for retrying commands after connection errors.
+    ///
+    /// Retrying requires reconnecting, so it has no effect if reconnecting
+    /// has been disabled.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Set an `Observer` to notify about every command sent to the HSM and
+    /// every session opened (replacing any previously set observer)
+    pub fn set_observer(&mut self, observer: Arc<dyn Observer>) {
+        if let Some(ref mut session) = self.session {
+            session.set_observer(Some(Arc::clone(&observer)));
+        }
+
+        self.observer = Some(observer);
     }
 
     /// Get current `Session` (either opening a new one or returning an already
@@ -160,18 +231,153 @@ impl Client {
             return Ok(self.session.as_mut().unwrap());
         }
 
-        let session = Session::open(
-            &*self.connector,
-            self.credentials
-                .as_ref()
-                .ok_or_else(|| err!(AuthFail, "session reconnection disabled"))?,
-            SessionTimeout::default(),
-        )?;
+        let started_at = Instant::now();
+        let result = self.open_session();
+
+        if let Some(ref observer) = self.observer {
+            observer.on_connect(&ConnectEvent {
+                session_id: result.as_ref().ok().map(Session::id),
+                reconnect: self.has_connected,
+                duration: started_at.elapsed(),
+                error: result.as_ref().err().map(ClientError::kind),
+            });
+        }
 
+        let mut session = result?;
+        session.set_observer(self.observer.clone());
         self.session = Some(session);
+        self.has_connected = true;
+
+        // Discard the credential source if reconnecting has been disabled
+        if !self.reconnect {
+            self.credentials = None;
+        }
+
         Ok(self.session.as_mut().unwrap())
     }
 
+    /// Open a new `Session` with the HSM
+    fn open_session(&self) -> Result<Session, ClientError> {
+        if self.healthcheck_on_open {
+            self.connector.healthcheck()?;
+        }
+
+        let credentials = self
+            .credentials
+            .as_ref()
+            .ok_or_else(|| err!(AuthFail, "session reconnection disabled"))?
+            .credentials()?;
+
+        Ok(Session::open(
+            &*self.connector,
+            &credentials,
+            self.session_timeout,
+        )?)
+    }
+
+    /// Replace the auth key with the given ID with a new one, which gets the
+    /// same domains, capabilities, and delegated capabilities.
+    ///
+    /// The new key is put into the HSM and a second session is opened with
+    /// `new_credentials` to prove it works before the old key is deleted.
+    /// If either step fails, the new key is deleted again, leaving the old
+    /// key in place.
+    ///
+    /// On success, the client switches over to the session opened with the
+    /// new credentials (and uses them for reconnecting, if enabled, in place
+    /// of any `CredentialSource` it was configured with).
+    pub fn rotate_auth_key(
+        &mut self,
+        old_key_id: ObjectId,
+        new_key_label: ObjectLabel,
+        new_credentials: Credentials,
+    ) -> Result<(), ClientError> {
+        let new_key_id = new_credentials.auth_key_id;
+
+        ensure!(
+            new_key_id != old_key_id,
+            ProtocolError,
+            "new auth key must have a different ID from the old one (0x{:04x})",
+            old_key_id
+        );
+
+        let old_key_info = self.get_object_info(old_key_id, ObjectType::AuthKey)?;
+
+        match new_credentials.auth_method {
+            AuthMethod::Symmetric(ref auth_key) => self.put_auth_key(
+                new_key_id,
+                new_key_label,
+                old_key_info.domains,
+                old_key_info.capabilities,
+                old_key_info.delegated_capabilities,
+                AuthAlg::YUBICO_AES,
+                auth_key.clone(),
+            )?,
+            #[cfg(feature = "asymmetric-auth")]
+            AuthMethod::Asymmetric { ref auth_key, .. } => self.put_asymmetric_auth_key(
+                new_key_id,
+                new_key_label,
+                old_key_info.domains,
+                old_key_info.capabilities,
+                old_key_info.delegated_capabilities,
+                &auth_key.public_key()[..],
+            )?,
+        };
+
+        let result = Session::open(&*self.connector, &new_credentials, self.session_timeout)
+            .map_err(ClientError::from)
+            .and_then(|session| {
+                self.delete_object(old_key_id, ObjectType::AuthKey)?;
+                Ok(session)
+            });
+
+        let mut session = match result {
+            Ok(session) => session,
+            Err(e) => {
+                if let Err(rollback_err) = self.delete_object(new_key_id, ObjectType::AuthKey) {
+                    warn!(
+                        target: &self.log_target,
+                        "couldn't roll back auth key rotation: {}", rollback_err
+                    );
+                }
+
+                return Err(e);
+            }
+        };
+
+        session.set_observer(self.observer.clone());
+        self.session = Some(session);
+
+        if self.reconnect {
+            self.credentials = Some(Box::new(new_credentials));
+        }
+
+        Ok(())
+    }
+
+    /// Is the default auth key (which every new YubiHSM2 ships with in slot 1,
+    /// derived from the password "password") still present and usable?
+    #[cfg(feature = "passwords")]
+    pub fn has_default_auth_key(&mut self) -> Result<bool, ClientError> {
+        let present = self.list_objects()?.iter().any(|entry| {
+            entry.object_type == ObjectType::AuthKey && entry.object_id == DEFAULT_AUTH_KEY_ID
+        });
+
+        if !present {
+            return Ok(false);
+        }
+
+        match Session::open(
+            &*self.connector,
+            &Credentials::default(),
+            self.session_timeout,
+        ) {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.kind() == SessionErrorKind::AuthFail => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Ping the HSM, ensuring we have a live connection and returning the
     /// end-to-end latency.
     pub fn ping(&mut self) -> Result<Duration, ClientError> {
@@ -191,8 +397,47 @@ impl Client {
     }
 
     /// Encrypt a command, send it to the HSM, then read and decrypt the response.
+    ///
+    /// Idempotent commands which fail due to connection errors are retried
+    /// on a new session according to the `RetryPolicy`.
     fn send_command<T: Command>(&mut self, command: T) -> Result<T::ResponseType, ClientError> {
-        Ok(self.session()?.send_command(command)?)
+        let command_data = serialize(&command)?;
+        let mut attempt = 1;
+
+        loop {
+            let message = CommandMessage::new(T::COMMAND_CODE, command_data.clone())?;
+
+            let err = match self.session() {
+                Ok(session) => match session.send_command_message::<T::ResponseType>(message) {
+                    Ok(response) => return Ok(response),
+                    Err(e) => ClientError::from(e),
+                },
+                Err(e) => e,
+            };
+
+            if !self
+                .retry_policy
+                .should_retry(T::COMMAND_CODE, attempt, &err)
+            {
+                return Err(err);
+            }
+
+            let backoff = self.retry_policy.backoff(attempt);
+
+            debug!(
+                target: &self.log_target,
+                "cmd={:?} attempt={} failed ({}), retrying in {:?}",
+                T::COMMAND_CODE,
+                attempt,
+                err,
+                backoff
+            );
+
+            // Discard the broken session so a new one is opened on retry
+            self.session = None;
+            thread::sleep(backoff);
+            attempt += 1;
+        }
     }
 
     //
@@ -217,10 +462,10 @@ impl Client {
         key_id: ObjectId,
         attestation_key_id: Option<ObjectId>,
     ) -> Result<AttestationCertificate, ClientError> {
-        Ok(self.send_command(AttestAsymmetricCommand {
+        self.send_command(AttestAsymmetricCommand {
             key_id,
             attestation_key_id: attestation_key_id.unwrap_or(0),
-        })?)
+        })
     }
 
     /// Blink the HSM's LEDs (to identify it) for the given number of seconds.
@@ -231,6 +476,48 @@ impl Client {
         Ok(())
     }
 
+    /// Change the secret of the auth key the current session is authenticated
+    /// with, keeping its ID, label, capabilities, and domains. Requires the
+    /// auth key to have the `CHANGE_AUTHKEY` capability (and recent firmware).
+    ///
+    /// If reconnecting is enabled, the client reconnects using the new key
+    /// (in place of any `CredentialSource` it was configured with).
+    ///
+    /// Only symmetric auth keys can be changed: this fails for sessions
+    /// authenticated with an asymmetric auth key.
+    ///
+    /// <https://developers.yubico.com/YubiHSM2/Commands/Change_Authentication_Key.html>
+    pub fn change_auth_key<K>(&mut self, new_key: K) -> Result<(), ClientError>
+    where
+        K: Into<AuthKey>,
+    {
+        let auth_key = new_key.into();
+        let (key_id, auth_alg) = {
+            let session = self.session()?;
+            (session.auth_key_id(), session.auth_alg())
+        };
+
+        ensure!(
+            auth_alg == AuthAlg::YUBICO_AES,
+            InvalidArgument,
+            "auth key 0x{:04x} is asymmetric ({:?}): only symmetric auth keys can be changed",
+            key_id,
+            auth_alg
+        );
+
+        self.send_command(ChangeAuthKeyCommand {
+            key_id,
+            algorithm: AuthAlg::YUBICO_AES.into(),
+            auth_key: auth_key.clone(),
+        })?;
+
+        if self.credentials.is_some() {
+            self.credentials = Some(Box::new(Credentials::new(key_id, auth_key)));
+        }
+
+        Ok(())
+    }
+
     /// Delete an object of the given ID and type.
     ///
     /// <https://developers.yubico.com/YubiHSM2/Commands/Delete_Object.html>
@@ -250,7 +537,7 @@ impl Client {
     ///
     /// <https://developers.yubico.com/YubiHSM2/Commands/Device_Info.html>
     pub fn device_info(&mut self) -> Result<DeviceInfoResponse, ClientError> {
-        Ok(self.send_command(DeviceInfoCommand {})?)
+        self.send_command(DeviceInfoCommand {})
     }
 
     /// Echo a message sent to the HSM.
@@ -263,7 +550,8 @@ impl Client {
         Ok(self
             .send_command(EchoCommand {
                 message: msg.into(),
-            })?.0)
+            })?
+            .0)
     }
 
     /// Export an encrypted object from the HSM using the given key-wrapping key.
@@ -280,7 +568,8 @@ impl Client {
                 wrap_key_id,
                 object_type,
                 object_id,
-            })?.0)
+            })?
+            .0)
     }
 
     /// Generate a new asymmetric key within the HSM.
@@ -301,7 +590,8 @@ impl Client {
                 domains,
                 capabilities,
                 algorithm: algorithm.into(),
-            }))?.key_id)
+            }))?
+            .key_id)
     }
 
     /// Generate a new HMAC key within the HSM.
@@ -322,7 +612,8 @@ impl Client {
                 domains,
                 capabilities,
                 algorithm: algorithm.into(),
-            }))?.key_id)
+            }))?
+            .key_id)
     }
 
     /// Generate a new wrap key within the HSM.
@@ -350,14 +641,15 @@ impl Client {
                     algorithm: algorithm.into(),
                 },
                 delegated_capabilities,
-            })?.key_id)
+            })?
+            .key_id)
     }
 
     /// Get audit logs from the HSM device.
     ///
     /// <https://developers.yubico.com/YubiHSM2/Commands/Get_Logs.html>
     pub fn get_audit_logs(&mut self) -> Result<AuditLogs, ClientError> {
-        Ok(self.send_command(GetLogsCommand {})?)
+        self.send_command(GetLogsCommand {})
     }
 
     /// Get information about an object.
@@ -372,7 +664,8 @@ impl Client {
             .send_command(GetObjectInfoCommand(ObjectHandle::new(
                 object_id,
                 object_type,
-            )))?.0)
+            )))?
+            .0)
     }
 
     /// Get an opaque object stored in the HSM.
@@ -443,14 +736,15 @@ impl Client {
         Ok(self
             .send_command(GetPseudoRandomCommand {
                 bytes: bytes as u16,
-            })?.bytes)
+            })?
+            .bytes)
     }
 
     /// Get the public key for an asymmetric key stored on the device.
     ///
     /// <https://developers.yubico.com/YubiHSM2/Commands/Get_Pubkey.html>
     pub fn get_pubkey(&mut self, key_id: ObjectId) -> Result<PublicKey, ClientError> {
-        Ok(self.send_command(GetPubKeyCommand { key_id })?)
+        self.send_command(GetPubKeyCommand { key_id })
     }
 
     /// Compute an HMAC tag of the given data with the given key ID.
@@ -460,10 +754,10 @@ impl Client {
     where
         M: Into<Vec<u8>>,
     {
-        Ok(self.send_command(HMACDataCommand {
+        self.send_command(HMACDataCommand {
             key_id,
             data: msg.into(),
-        })?)
+        })
     }
 
     /// Import an encrypted object from the HSM using the given key-wrapping key.
@@ -479,11 +773,11 @@ impl Client {
     {
         let WrapMessage { nonce, ciphertext } = wrap_message.into();
 
-        Ok(self.send_command(ImportWrappedCommand {
+        self.send_command(ImportWrappedCommand {
             wrap_key_id,
             nonce,
             ciphertext,
-        })?)
+        })
     }
 
     /// List objects visible from the current session.
@@ -531,7 +825,8 @@ impl Client {
                     algorithm: algorithm.into(),
                 },
                 data,
-            })?.key_id)
+            })?
+            .key_id)
     }
 
     /// Put an existing `AuthKey` into the HSM.
@@ -563,7 +858,52 @@ impl Client {
                 },
                 delegated_capabilities,
                 auth_key: auth_key.into(),
-            })?.key_id)
+            })?
+            .key_id)
+    }
+
+    /// Put the public key of an asymmetric (EC P-256) auth key into the HSM,
+    /// i.e. the X and Y coordinates returned by `AsymmetricAuthKey::public_key`.
+    /// Sessions can then be opened with the corresponding `AsymmetricAuthKey`
+    /// using `Credentials::asymmetric`.
+    ///
+    /// <https://developers.yubico.com/YubiHSM2/Commands/Put_Authentication_Key.html>
+    #[cfg(feature = "asymmetric-auth")]
+    pub fn put_asymmetric_auth_key<K>(
+        &mut self,
+        key_id: ObjectId,
+        label: ObjectLabel,
+        domains: Domain,
+        capabilities: Capability,
+        delegated_capabilities: Capability,
+        public_key: K,
+    ) -> Result<ObjectId, ClientError>
+    where
+        K: Into<Vec<u8>>,
+    {
+        let public_key = public_key.into();
+
+        ensure!(
+            public_key.len() == AuthAlg::EC_P256_YUBICO.key_len(),
+            ProtocolError,
+            "invalid asymmetric auth key length: {} (expected {})",
+            public_key.len(),
+            AuthAlg::EC_P256_YUBICO.key_len()
+        );
+
+        Ok(self
+            .send_command(PutAsymmetricAuthKeyCommand {
+                params: PutObjectParams {
+                    id: key_id,
+                    label,
+                    domains,
+                    capabilities,
+                    algorithm: AuthAlg::EC_P256_YUBICO.into(),
+                },
+                delegated_capabilities,
+                public_key,
+            })?
+            .key_id)
     }
 
     /// Put an existing HMAC key into the HSM.
@@ -604,7 +944,8 @@ impl Client {
                     algorithm: algorithm.into(),
                 },
                 hmac_key,
-            })?.key_id)
+            })?
+            .key_id)
     }
 
     /// Put an opaque object (X.509 certificate or other bytestring) into the HSM.
@@ -632,7 +973,8 @@ impl Client {
                     algorithm: algorithm.into(),
                 },
                 data: opaque_data.into(),
-            })?.object_id)
+            })?
+            .object_id)
     }
 
     /// Configure the audit policy settings for a particular command, e.g. auditing
@@ -707,7 +1049,8 @@ impl Client {
                     algorithm: algorithm.into(),
                 },
                 data,
-            })?.key_id)
+            })?
+            .key_id)
     }
 
     /// Put an existing wrap key into the HSM.
@@ -751,7 +1094,8 @@ impl Client {
                 },
                 delegated_capabilities,
                 data,
-            })?.key_id)
+            })?
+            .key_id)
     }
 
     /// Reset the HSM to a factory default state and reboot, clearing all
@@ -764,7 +1108,7 @@ impl Client {
     pub fn reset(&mut self) -> Result<(), ClientError> {
         // TODO: handle potential errors that occur when resetting
         if let Err(e) = self.send_command(ResetCommand {}) {
-            debug!("error sending reset command: {}", e);
+            debug!(target: &self.log_target, "error sending reset command: {}", e);
         }
 
         // Resetting the HSM invalidates our session
@@ -789,16 +1133,9 @@ impl Client {
     /// The YubiHSM2 does not produce signatures in "low S" form, which is expected
     /// for most cryptocurrency applications (the typical use case for secp256k1).
     ///
-    /// If your application demands this (e.g. Bitcoin), you'll need to normalize
-    /// the signatures. One option for this is the `secp256k1` crate's
-    /// [Signature::normalize_s] function.
-    ///
-    /// The [signatory-yubihsm] crate automatically normalizes secp256k1 ECDSA
-    /// signatures to "low S" form. Consider using that if you'd like a ready-made
-    /// solution for cryptocurrency applications.
-    ///
-    /// [Signature::normalize_s]: https://docs.rs/secp256k1/latest/secp256k1/struct.Signature.html#method.normalize_s
-    /// [signatory-yubihsm]: https://docs.rs/signatory-yubihsm/latest/signatory_yubihsm/ecdsa/struct.ECDSASigner.html
+    /// If your application demands this (e.g. Bitcoin), use `sign_secp256k1`
+    /// or `sign_secp256k1_recoverable` (with the `secp256k1` cargo feature),
+    /// which normalize the signatures.
     pub fn sign_ecdsa<T>(
         &mut self,
         key_id: ObjectId,
@@ -807,10 +1144,108 @@ impl Client {
     where
         T: Into<Vec<u8>>,
     {
-        Ok(self.send_command(SignDataECDSACommand {
+        self.send_command(SignDataECDSACommand {
             key_id,
             digest: digest.into(),
-        })?)
+        })
+    }
+
+    /// Compute an ECDSA signature of the given message, hashing it with the
+    /// digest for the key's curve (see `AsymmetricAlg::ecdsa_alg`), i.e.
+    /// SHA-256 for P-224 and 256-bit curves, SHA-384 for 384-bit curves, and
+    /// SHA-512 for P-521 and brainpoolP512r1.
+    ///
+    /// <https://developers.yubico.com/YubiHSM2/Commands/Sign_Data_Ecdsa.html>
+    #[cfg(feature = "sha2")]
+    pub fn sign_ecdsa_message(
+        &mut self,
+        key_id: ObjectId,
+        message: &[u8],
+    ) -> Result<ECDSASignature, ClientError> {
+        let algorithm = self
+            .get_object_info(key_id, ObjectType::AsymmetricKey)?
+            .algorithm;
+
+        let ecdsa_alg = match algorithm {
+            Algorithm::Asymmetric(alg) => alg.ecdsa_alg(),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            err!(
+                InvalidArgument,
+                "key 0x{:04x} isn't an ECDSA key (algorithm: {:?})",
+                key_id,
+                algorithm
+            )
+        })?;
+
+        let digest = ecdsa_alg
+            .digest(message)
+            .ok_or_else(|| err!(ProtocolError, "ECDSA with SHA-1 is unsupported"))?;
+
+        self.sign_ecdsa(key_id, digest)
+    }
+
+    /// Compute a secp256k1 ECDSA signature of the given digest, normalized to
+    /// "low S" form and encoded as compact `r || s`.
+    ///
+    /// Fails with `InvalidArgument` if the key isn't a secp256k1 key.
+    ///
+    /// <https://developers.yubico.com/YubiHSM2/Commands/Sign_Data_Ecdsa.html>
+    #[cfg(feature = "secp256k1")]
+    pub fn sign_secp256k1(
+        &mut self,
+        key_id: ObjectId,
+        digest: &[u8],
+    ) -> Result<Secp256k1Signature, ClientError> {
+        self.get_secp256k1_pubkey(key_id)?;
+        let signature = self.sign_ecdsa(key_id, digest)?;
+
+        normalize_signature(signature.as_slice())
+            .map(|signature| compact_signature(&signature))
+            .ok_or_else(|| err!(ProtocolError, "malformed ECDSA signature from HSM"))
+    }
+
+    /// Compute a recoverable secp256k1 ECDSA signature of the given digest
+    /// (i.e. Ethereum-style `r || s || v`), normalized to "low S" form.
+    ///
+    /// The recovery ID is found by recovering public keys from the signature
+    /// until one matches the key's public key (from `get_pubkey`).
+    ///
+    /// Fails with `InvalidArgument` if the key isn't a secp256k1 key.
+    ///
+    /// <https://developers.yubico.com/YubiHSM2/Commands/Sign_Data_Ecdsa.html>
+    #[cfg(feature = "secp256k1")]
+    pub fn sign_secp256k1_recoverable(
+        &mut self,
+        key_id: ObjectId,
+        digest: &[u8],
+    ) -> Result<Secp256k1RecoverableSignature, ClientError> {
+        let public_key = self.get_secp256k1_pubkey(key_id)?;
+
+        let signature = normalize_signature(self.sign_ecdsa(key_id, digest)?.as_slice())
+            .ok_or_else(|| err!(ProtocolError, "malformed ECDSA signature from HSM"))?;
+
+        let recovery_id = recovery_id(&public_key.bytes, digest, &signature)
+            .ok_or_else(|| err!(ProtocolError, "couldn't compute recovery ID for signature"))?;
+
+        Ok(recoverable_signature(&signature, recovery_id))
+    }
+
+    /// Get the public key for the given key, ensuring it's a secp256k1 key
+    #[cfg(feature = "secp256k1")]
+    fn get_secp256k1_pubkey(&mut self, key_id: ObjectId) -> Result<PublicKey, ClientError> {
+        let public_key = self.get_pubkey(key_id)?;
+
+        ensure!(
+            public_key.algorithm == AsymmetricAlg::EC_K256,
+            InvalidArgument,
+            "key 0x{:04x} isn't a secp256k1 key (algorithm: {:?})",
+            key_id,
+            public_key.algorithm
+        );
+
+        Ok(public_key)
     }
 
     /// Compute an Ed25519 signature with the given key ID.
@@ -824,10 +1259,10 @@ impl Client {
     where
         T: Into<Vec<u8>>,
     {
-        Ok(self.send_command(SignDataEdDSACommand {
+        self.send_command(SignDataEdDSACommand {
             key_id,
             data: data.into(),
-        })?)
+        })
     }
 
     /// Compute an RSASSA-PKCS#1v1.5 signature of the SHA-256 hash of the given data.
@@ -842,16 +1277,14 @@ impl Client {
         key_id: ObjectId,
         data: &[u8],
     ) -> Result<RSAPKCS1Signature, ClientError> {
-        Ok(self.send_command(SignDataPKCS1Command {
+        self.send_command(SignDataPKCS1Command {
             key_id,
             digest: Sha256::digest(data).as_slice().into(),
-        })?)
+        })
     }
 
-    /// Compute an RSASSA-PSS signature of the SHA-256 hash of the given data with the given key ID.
-    ///
-    /// **WARNING**: This method has not been tested and is not confirmed to actually work! Use at your
-    /// own risk!
+    /// Compute an RSASSA-PSS signature of the SHA-256 hash of the given data with the given key ID,
+    /// using MGF1 with SHA-256 and a 32-byte salt (i.e. `PS256`).
     ///
     /// <https://developers.yubico.com/YubiHSM2/Commands/Sign_Data_Pss.html>
     #[cfg(feature = "rsa")]
@@ -860,34 +1293,21 @@ impl Client {
         key_id: ObjectId,
         data: &[u8],
     ) -> Result<RSAPSSSignature, ClientError> {
-        ensure!(
-            data.len() > RSA_PSS_MAX_MESSAGE_SIZE,
-            ProtocolError,
-            "message too large to be signed (max: {})",
-            RSA_PSS_MAX_MESSAGE_SIZE
-        );
-
-        let mut hasher = Sha256::default();
-
-        let mut length = [0u8; 2];
-        BigEndian::write_u16(&mut length, data.len() as u16);
-        hasher.input(&length);
-        hasher.input(data);
-        let digest = hasher.result();
+        let digest = Sha256::digest(data);
 
-        Ok(self.send_command(SignDataPSSCommand {
+        self.send_command(SignDataPSSCommand {
             key_id,
             mgf1_hash_alg: Algorithm::Mgf(MgfAlg::SHA256),
             salt_len: digest.as_slice().len() as u16,
             digest: digest.as_slice().into(),
-        })?)
+        })
     }
 
     /// Get storage status (i.e. currently free storage) from the HSM device.
     ///
     /// <https://developers.yubico.com/YubiHSM2/Commands/Storage_Status.html>
     pub fn storage_status(&mut self) -> Result<StorageStatusResponse, ClientError> {
-        Ok(self.send_command(StorageStatusCommand {})?)
+        self.send_command(StorageStatusCommand {})
     }
 
     /// Decrypt data which was encrypted (using AES-CCM) under a wrap key.
@@ -908,7 +1328,8 @@ impl Client {
                 wrap_key_id,
                 nonce,
                 ciphertext,
-            })?.0)
+            })?
+            .0)
     }
 
     /// Verify an HMAC tag of the given data with the given key ID.
@@ -944,6 +1365,7 @@ impl Client {
             .send_command(WrapDataCommand {
                 wrap_key_id,
                 plaintext,
-            })?.0)
+            })?
+            .0)
     }
 }
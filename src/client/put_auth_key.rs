@@ -36,3 +36,22 @@ pub(crate) struct PutAuthKeyResponse {
 impl Response for PutAuthKeyResponse {
     const COMMAND_CODE: CommandCode = CommandCode::PutAuthKey;
 }
+
+/// Request parameters for `command::put_asymmetric_auth_key`
+#[cfg(any(feature = "asymmetric-auth", feature = "mockhsm"))]
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct PutAsymmetricAuthKeyCommand {
+    /// Common parameters to all put object command
+    pub params: PutObjectParams,
+
+    /// Delegated capabilities
+    pub delegated_capabilities: Capability,
+
+    /// Public key (X and Y coordinates of a P-256 point)
+    pub public_key: Vec<u8>,
+}
+
+#[cfg(any(feature = "asymmetric-auth", feature = "mockhsm"))]
+impl Command for PutAsymmetricAuthKeyCommand {
+    type ResponseType = PutAuthKeyResponse;
+}
@@ -0,0 +1,140 @@
+//! Policy for retrying commands which failed due to connection errors
+
+use std::time::Duration;
+
+use super::{ClientError, ClientErrorKind};
+use command::CommandCode;
+use connector::ConnectionErrorKind;
+
+/// Default maximum number of attempts per command (i.e. no retries)
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 1;
+
+/// Default delay before the first retry (100 milliseconds)
+pub const DEFAULT_BACKOFF_MILLIS: u64 = 100;
+
+/// Default upper bound on the delay between retries (5 seconds)
+pub const DEFAULT_MAX_BACKOFF_MILLIS: u64 = 5000;
+
+/// Policy for transparently retrying commands on a new session.
+///
+/// When a command fails with one of the `retryable_errors`, the `Client`
+/// discards its current session, waits for the backoff period, opens a
+/// new session using its cached `Credentials`, and resends the command.
+/// The backoff period doubles after each failed attempt, up to
+/// `max_backoff_ms`.
+///
+/// Only idempotent commands (see `CommandCode::is_idempotent`) are ever
+/// retried. Commands which create, modify, or delete objects are never
+/// replayed, since the HSM may have executed them before the connection
+/// failed.
+///
+/// The default policy makes a single attempt, i.e. it never retries.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts per command, including the first one
+    pub max_attempts: u32,
+
+    /// Delay before the first retry in milliseconds
+    pub backoff_ms: u64,
+
+    /// Upper bound on the delay between retries in milliseconds
+    pub max_backoff_ms: u64,
+
+    /// Connection error kinds which are considered transient
+    pub retryable_errors: Vec<ConnectionErrorKind>,
+}
+
+impl RetryPolicy {
+    /// Create a policy which makes up to `max_attempts` attempts per command,
+    /// using the default backoff and retryable errors
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Should a command which failed on its `attempt`-th attempt be retried?
+    pub fn should_retry(&self, command: CommandCode, attempt: u32, err: &ClientError) -> bool {
+        if attempt >= self.max_attempts || !command.is_idempotent() {
+            return false;
+        }
+
+        match err.kind() {
+            ClientErrorKind::ConnectionError { kind } => self.retryable_errors.contains(&kind),
+            _ => false,
+        }
+    }
+
+    /// Delay to wait after the `attempt`-th failed attempt
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let multiplier = 1u64
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or_else(u64::max_value);
+
+        Duration::from_millis(
+            self.backoff_ms
+                .saturating_mul(multiplier)
+                .min(self.max_backoff_ms),
+        )
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut retryable_errors = vec![
+            ConnectionErrorKind::ConnectionFailed,
+            ConnectionErrorKind::IoError,
+            ConnectionErrorKind::ResponseError,
+        ];
+
+        #[cfg(feature = "usb")]
+        retryable_errors.push(ConnectionErrorKind::UsbError);
+
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff_ms: DEFAULT_BACKOFF_MILLIS,
+            max_backoff_ms: DEFAULT_MAX_BACKOFF_MILLIS,
+            retryable_errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection_error(kind: ConnectionErrorKind) -> ClientError {
+        ClientError::new(ClientErrorKind::ConnectionError { kind }, None)
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::new(10);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(64), Duration::from_millis(5000));
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::new(3);
+        let err = connection_error(ConnectionErrorKind::IoError);
+
+        assert!(policy.should_retry(CommandCode::SignDataEdDSA, 1, &err));
+        assert!(policy.should_retry(CommandCode::GetPubKey, 2, &err));
+        assert!(!policy.should_retry(CommandCode::GetPubKey, 3, &err));
+        assert!(!policy.should_retry(CommandCode::PutAsymmetricKey, 1, &err));
+
+        let err = connection_error(ConnectionErrorKind::AccessDenied);
+        assert!(!policy.should_retry(CommandCode::GetPubKey, 1, &err));
+    }
+
+    #[test]
+    fn test_default_never_retries() {
+        let err = connection_error(ConnectionErrorKind::IoError);
+        assert!(!RetryPolicy::default().should_retry(CommandCode::ListObjects, 1, &err));
+    }
+}
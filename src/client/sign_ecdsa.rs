@@ -2,7 +2,9 @@
 //!
 //! <https://developers.yubico.com/YubiHSM2/Commands/Sign_Data_Ecdsa.html>
 
+use algorithm::AsymmetricAlg;
 use command::{Command, CommandCode};
+use der;
 use object::ObjectId;
 use response::Response;
 
@@ -29,12 +31,74 @@ impl Response for ECDSASignature {
 }
 
 // TODO: use clippy's scoped lints once they work on stable
-#[allow(
-    unknown_lints,
-    renamed_and_removed_lints,
-    len_without_is_empty
-)]
+#[allow(unknown_lints, renamed_and_removed_lints, len_without_is_empty)]
 impl ECDSASignature {
+    /// Convert a fixed-width IEEE P1363 signature (`r || s`, each the size of
+    /// the curve's field elements) to ASN.1 DER.
+    ///
+    /// Returns `None` if the signature is the wrong length for the curve or
+    /// `curve` isn't an elliptic curve algorithm.
+    pub fn from_p1363(curve: AsymmetricAlg, bytes: &[u8]) -> Option<Self> {
+        curve.ecdsa_alg()?;
+
+        if bytes.len() != curve.key_len() * 2 {
+            return None;
+        }
+
+        let (r, s) = bytes.split_at(curve.key_len());
+
+        Some(ECDSASignature(der::sequence(&[
+            &der::unsigned_integer(r),
+            &der::unsigned_integer(s),
+        ])))
+    }
+
+    /// Convert this signature to the fixed-width IEEE P1363 encoding
+    /// (`r || s`) used by JOSE, COSE, and WebAuthn.
+    ///
+    /// Returns `None` if the signature is malformed, too large for the curve,
+    /// or `curve` isn't an elliptic curve algorithm.
+    pub fn to_p1363(&self, curve: AsymmetricAlg) -> Option<Vec<u8>> {
+        curve.ecdsa_alg()?;
+
+        let field_size = curve.key_len();
+        let (sequence, rest) = der::parse(&self.0)?;
+        let integers = der::elements(sequence.value)?;
+
+        if !rest.is_empty() || sequence.tag != der::SEQUENCE || integers.len() != 2 {
+            return None;
+        }
+
+        let mut p1363 = vec![0u8; field_size * 2];
+
+        for (i, integer) in integers.iter().enumerate() {
+            // r and s are positive, so anything else is malformed
+            let positive = integer.value.first().map_or(false, |&b| b & 0x80 == 0);
+
+            if integer.tag != der::INTEGER || !positive {
+                return None;
+            }
+
+            // Strip the leading zero which keeps the high bit clear
+            let start = integer
+                .value
+                .iter()
+                .position(|&b| b != 0)
+                .unwrap_or(integer.value.len());
+
+            let value = &integer.value[start..];
+
+            if value.len() > field_size {
+                return None;
+            }
+
+            let end = (i + 1) * field_size;
+            p1363[end - value.len()..end].copy_from_slice(value);
+        }
+
+        Some(p1363)
+    }
+
     /// Unwrap inner byte vector
     pub fn into_vec(self) -> Vec<u8> {
         self.into()
@@ -57,8 +121,61 @@ impl AsRef<[u8]> for ECDSASignature {
     }
 }
 
-impl Into<Vec<u8>> for ECDSASignature {
-    fn into(self) -> Vec<u8> {
-        self.0
+impl From<ECDSASignature> for Vec<u8> {
+    fn from(signature: ECDSASignature) -> Vec<u8> {
+        signature.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// P-256 signature whose `r` has the high bit set (so DER adds a leading
+    /// zero) and whose `s` is short (so P1363 needs padding)
+    const P1363_SIGNATURE: [u8; 64] = [
+        0x9a, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d,
+        0x1e, 0x1f, 0x00, 0x00, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c,
+        0x2d, 0x2e, 0x2f, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x3b,
+        0x3c, 0x3d, 0x3e, 0x3f,
+    ];
+
+    #[test]
+    fn p1363_round_trip() {
+        let signature =
+            ECDSASignature::from_p1363(AsymmetricAlg::EC_P256, &P1363_SIGNATURE).unwrap();
+
+        // SEQUENCE { INTEGER (33 bytes), INTEGER (30 bytes) }
+        assert_eq!(&signature.as_slice()[..5], &[0x30, 0x43, 0x02, 0x21, 0x00]);
+        assert_eq!(&signature.as_slice()[37..39], &[0x02, 0x1e]);
+
+        assert_eq!(
+            signature.to_p1363(AsymmetricAlg::EC_P256).unwrap(),
+            &P1363_SIGNATURE[..]
+        );
+    }
+
+    #[test]
+    fn p1363_rejects_mismatched_curves() {
+        assert!(ECDSASignature::from_p1363(AsymmetricAlg::EC_P384, &P1363_SIGNATURE).is_none());
+        assert!(ECDSASignature::from_p1363(AsymmetricAlg::Ed25519, &P1363_SIGNATURE).is_none());
+
+        let signature =
+            ECDSASignature::from_p1363(AsymmetricAlg::EC_P256, &P1363_SIGNATURE).unwrap();
+
+        assert!(signature.to_p1363(AsymmetricAlg::EC_P224).is_none());
+        assert!(signature.to_p1363(AsymmetricAlg::RSA_2048).is_none());
+    }
+
+    #[test]
+    fn p1363_rejects_malformed_signatures() {
+        // Negative r
+        let negative = ECDSASignature(vec![0x30, 0x06, 0x02, 0x01, 0x80, 0x02, 0x01, 0x01]);
+        assert!(negative.to_p1363(AsymmetricAlg::EC_P256).is_none());
+
+        // Trailing data
+        let trailing = ECDSASignature(vec![0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00]);
+        assert!(trailing.to_p1363(AsymmetricAlg::EC_P256).is_none());
     }
 }
@@ -29,11 +29,7 @@ impl Response for RSAPKCS1Signature {
 }
 
 // TODO: use clippy's scoped lints once they work on stable
-#[allow(
-    unknown_lints,
-    renamed_and_removed_lints,
-    len_without_is_empty
-)]
+#[allow(unknown_lints, renamed_and_removed_lints, len_without_is_empty)]
 impl RSAPKCS1Signature {
     /// Unwrap inner byte vector
     pub fn into_vec(self) -> Vec<u8> {
@@ -57,8 +53,8 @@ impl AsRef<[u8]> for RSAPKCS1Signature {
     }
 }
 
-impl Into<Vec<u8>> for RSAPKCS1Signature {
-    fn into(self) -> Vec<u8> {
-        self.0
+impl From<RSAPKCS1Signature> for Vec<u8> {
+    fn from(signature: RSAPKCS1Signature) -> Vec<u8> {
+        signature.0
     }
 }
@@ -8,6 +8,7 @@ use object::ObjectId;
 use response::Response;
 
 /// Maximum message size supported for RSASSA-PSS
+#[deprecated(note = "messages are hashed locally, so any size can be signed")]
 pub const RSA_PSS_MAX_MESSAGE_SIZE: usize = 0xFFFF;
 
 /// Request parameters for `command::sign_rsa_pss*`
@@ -39,11 +40,7 @@ impl Response for RSAPSSSignature {
 }
 
 // TODO: use clippy's scoped lints once they work on stable
-#[allow(
-    unknown_lints,
-    renamed_and_removed_lints,
-    len_without_is_empty
-)]
+#[allow(unknown_lints, renamed_and_removed_lints, len_without_is_empty)]
 impl RSAPSSSignature {
     /// Unwrap inner byte vector
     pub fn into_vec(self) -> Vec<u8> {
@@ -67,8 +64,8 @@ impl AsRef<[u8]> for RSAPSSSignature {
     }
 }
 
-impl Into<Vec<u8>> for RSAPSSSignature {
-    fn into(self) -> Vec<u8> {
-        self.0
+impl From<RSAPSSSignature> for Vec<u8> {
+    fn from(signature: RSAPSSSignature) -> Vec<u8> {
+        signature.0
     }
 }
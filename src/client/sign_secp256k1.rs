@@ -0,0 +1,208 @@
+//! secp256k1 ECDSA signatures in the forms expected by cryptocurrency
+//! applications: normalized to "low S", compact (`r || s`), and recoverable
+//! (`r || s || v`, as used by Ethereum).
+//!
+//! The HSM computes these with the `Sign_Data_Ecdsa` command:
+//! <https://developers.yubico.com/YubiHSM2/Commands/Sign_Data_Ecdsa.html>
+
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use std::fmt::{self, Debug};
+
+/// Size of a compact (`r || s`) secp256k1 signature
+pub const SECP256K1_SIGNATURE_SIZE: usize = 64;
+
+/// Size of a recoverable (`r || s || v`) secp256k1 signature
+pub const SECP256K1_RECOVERABLE_SIGNATURE_SIZE: usize = 65;
+
+/// Compact secp256k1 signature (`r || s`, 64-bytes) in "low S" form
+pub struct Secp256k1Signature(pub [u8; SECP256K1_SIGNATURE_SIZE]);
+
+impl Secp256k1Signature {
+    /// Get slice of the inner byte array
+    pub fn as_slice(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+impl AsRef<[u8]> for Secp256k1Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl Debug for Secp256k1Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Secp256k1Signature(")?;
+        write_hex(f, &self.0)?;
+        write!(f, ")")
+    }
+}
+
+/// Recoverable secp256k1 signature (`r || s || v`, 65-bytes) in "low S" form,
+/// where `v` is the recovery ID (0-3).
+///
+/// Legacy Ethereum transactions expect `v` to be offset by 27 (or by
+/// `35 + 2 * chain_id` with EIP-155), which is left up to the caller.
+pub struct Secp256k1RecoverableSignature(pub [u8; SECP256K1_RECOVERABLE_SIGNATURE_SIZE]);
+
+impl Secp256k1RecoverableSignature {
+    /// Get the compact (`r || s`) signature
+    pub fn signature(&self) -> Secp256k1Signature {
+        let mut signature = [0u8; SECP256K1_SIGNATURE_SIZE];
+        signature.copy_from_slice(&self.0[..SECP256K1_SIGNATURE_SIZE]);
+        Secp256k1Signature(signature)
+    }
+
+    /// Get the recovery ID (`v`)
+    pub fn recovery_id(&self) -> u8 {
+        self.0[SECP256K1_SIGNATURE_SIZE]
+    }
+
+    /// Get slice of the inner byte array
+    pub fn as_slice(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+impl AsRef<[u8]> for Secp256k1RecoverableSignature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl Debug for Secp256k1RecoverableSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Secp256k1RecoverableSignature(")?;
+        write_hex(f, &self.0)?;
+        write!(f, ")")
+    }
+}
+
+/// Parse an ASN.1 DER signature from the HSM, normalizing it to "low S"
+/// form, returning `None` if it's malformed
+pub(crate) fn normalize_signature(der_signature: &[u8]) -> Option<Signature> {
+    let signature = Signature::from_der(der_signature).ok()?;
+    Some(signature.normalize_s().unwrap_or(signature))
+}
+
+/// Find the recovery ID which recovers the given public key (as returned by
+/// the HSM, i.e. `x || y`) from a signature of `digest`
+pub(crate) fn recovery_id(
+    public_key: &[u8],
+    digest: &[u8],
+    signature: &Signature,
+) -> Option<RecoveryId> {
+    let mut sec1_public_key = vec![0x04];
+    sec1_public_key.extend_from_slice(public_key);
+    let verifying_key = VerifyingKey::from_sec1_bytes(&sec1_public_key).ok()?;
+
+    (0..4).filter_map(RecoveryId::from_byte).find(|&id| {
+        VerifyingKey::recover_from_prehash(digest, signature, id)
+            .map(|recovered| recovered == verifying_key)
+            .unwrap_or(false)
+    })
+}
+
+/// Serialize a signature as compact `r || s`
+pub(crate) fn compact_signature(signature: &Signature) -> Secp256k1Signature {
+    let mut bytes = [0u8; SECP256K1_SIGNATURE_SIZE];
+    bytes.copy_from_slice(&signature.to_bytes());
+    Secp256k1Signature(bytes)
+}
+
+/// Serialize a signature and recovery ID as `r || s || v`
+pub(crate) fn recoverable_signature(
+    signature: &Signature,
+    recovery_id: RecoveryId,
+) -> Secp256k1RecoverableSignature {
+    let mut bytes = [0u8; SECP256K1_RECOVERABLE_SIGNATURE_SIZE];
+    bytes[..SECP256K1_SIGNATURE_SIZE].copy_from_slice(&signature.to_bytes());
+    bytes[SECP256K1_SIGNATURE_SIZE] = recovery_id.to_byte();
+    Secp256k1RecoverableSignature(bytes)
+}
+
+/// Write bytes as colon-delimited hex
+fn write_hex(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+            write!(f, ":")?;
+        }
+
+        write!(f, "{:02x}", byte)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::SigningKey;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+    /// Arbitrary 32-byte message digest
+    const DIGEST: [u8; 32] = [
+        0x1b, 0x8a, 0x2f, 0x4e, 0x6c, 0x90, 0x3d, 0x57, 0xa2, 0x11, 0xc8, 0x7e, 0x05, 0xf3, 0x64,
+        0x9d, 0x28, 0xbe, 0x71, 0x0a, 0xd4, 0x3c, 0x86, 0xe9, 0x52, 0x17, 0xfa, 0x40, 0x9b, 0x63,
+        0xc5, 0x2e,
+    ];
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    /// Public key in the format returned by the HSM (`x || y`)
+    fn hsm_public_key(signing_key: &SigningKey) -> Vec<u8> {
+        signing_key
+            .verifying_key()
+            .as_affine()
+            .to_encoded_point(false)
+            .as_bytes()[1..]
+            .to_vec()
+    }
+
+    #[test]
+    fn normalizes_high_s() {
+        let (signature, _) = signing_key().sign_prehash_recoverable(&DIGEST).unwrap();
+
+        // Negate s to get the equally valid "high S" signature
+        let (r, s) = signature.split_scalars();
+        let high_s = Signature::from_scalars(r, -*s).unwrap();
+        assert!(high_s.normalize_s().is_some());
+
+        let der_signature = high_s.to_der();
+        let normalized = normalize_signature(der_signature.as_bytes()).unwrap();
+
+        assert_eq!(normalized, signature);
+        assert!(normalized.normalize_s().is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        assert!(normalize_signature(&[0x30, 0x00]).is_none());
+    }
+
+    #[test]
+    fn finds_recovery_id() {
+        let signing_key = signing_key();
+        let (signature, expected_id) = signing_key.sign_prehash_recoverable(&DIGEST).unwrap();
+
+        let id = recovery_id(&hsm_public_key(&signing_key), &DIGEST, &signature).unwrap();
+        assert_eq!(id, expected_id);
+
+        let recoverable = recoverable_signature(&signature, id);
+        assert_eq!(recoverable.recovery_id(), expected_id.to_byte());
+        assert_eq!(
+            recoverable.signature().as_slice(),
+            compact_signature(&signature).as_slice()
+        );
+    }
+
+    #[test]
+    fn no_recovery_id_for_other_keys() {
+        let (signature, _) = signing_key().sign_prehash_recoverable(&DIGEST).unwrap();
+        let other_key = SigningKey::from_slice(&[0x24; 32]).unwrap();
+
+        assert!(recovery_id(&hsm_public_key(&other_key), &DIGEST, &signature).is_none());
+    }
+}
@@ -18,6 +18,7 @@ pub enum CommandCode {
     BSL = 0x07,
     Reset = 0x08,
     Command9 = 0x09, // What is Command 9???
+    GetDevicePubKey = 0x0a,
     CloseSession = 0x40,
     StorageStatus = 0x41,
     PutOpaqueObject = 0x42,
@@ -62,6 +63,7 @@ pub enum CommandCode {
     UnwrapData = 0x69,
     SignDataEdDSA = 0x6a,
     Blink = 0x6b,
+    ChangeAuthKey = 0x6c,
     Error = 0x7f,
 }
 
@@ -78,6 +80,7 @@ impl CommandCode {
             0x07 => CommandCode::BSL,
             0x08 => CommandCode::Reset,
             0x09 => CommandCode::Command9,
+            0x0a => CommandCode::GetDevicePubKey,
             0x40 => CommandCode::CloseSession,
             0x41 => CommandCode::StorageStatus,
             0x42 => CommandCode::PutOpaqueObject,
@@ -122,6 +125,7 @@ impl CommandCode {
             0x69 => CommandCode::UnwrapData,
             0x6a => CommandCode::SignDataEdDSA,
             0x6b => CommandCode::Blink,
+            0x6c => CommandCode::ChangeAuthKey,
             0x7f => CommandCode::Error,
             _ => bail!("invalid command type: {}", byte),
         })
@@ -131,6 +135,46 @@ impl CommandCode {
     pub fn to_u8(self) -> u8 {
         self as u8
     }
+
+    /// Is it safe to send this command to the HSM more than once?
+    ///
+    /// Idempotent commands only read state or compute a result from existing
+    /// keys (e.g. signing), so replaying them after a connection failure has
+    /// no additional effect on the HSM besides an extra audit log entry.
+    /// Commands which create, modify, or delete objects are never idempotent.
+    pub fn is_idempotent(self) -> bool {
+        matches!(
+            self,
+            CommandCode::Echo
+                | CommandCode::DeviceInfo
+                | CommandCode::GetDevicePubKey
+                | CommandCode::StorageStatus
+                | CommandCode::GetOpaqueObject
+                | CommandCode::SignDataPKCS1
+                | CommandCode::ListObjects
+                | CommandCode::DecryptPKCS1
+                | CommandCode::ExportWrapped
+                | CommandCode::GetLogs
+                | CommandCode::GetObjectInfo
+                | CommandCode::GetOption
+                | CommandCode::GetPseudoRandom
+                | CommandCode::HMACData
+                | CommandCode::GetPubKey
+                | CommandCode::SignDataPSS
+                | CommandCode::SignDataECDSA
+                | CommandCode::DecryptECDH
+                | CommandCode::DecryptOAEP
+                | CommandCode::VerifyHMAC
+                | CommandCode::SSHCertify
+                | CommandCode::GetTemplate
+                | CommandCode::DecryptOTP
+                | CommandCode::AttestAsymmetric
+                | CommandCode::WrapData
+                | CommandCode::UnwrapData
+                | CommandCode::SignDataEdDSA
+                | CommandCode::Blink
+        )
+    }
 }
 
 impl Serialize for CommandCode {
@@ -148,6 +192,6 @@ impl<'de> Deserialize<'de> for CommandCode {
         D: Deserializer<'de>,
     {
         CommandCode::from_u8(u8::deserialize(deserializer)?)
-            .or_else(|e| Err(D::Error::custom(format!("{}", e))))
+            .map_err(|e| D::Error::custom(format!("{}", e)))
     }
 }
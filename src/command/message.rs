@@ -175,20 +175,20 @@ impl CommandMessage {
     }
 }
 
-impl Into<Vec<u8>> for CommandMessage {
+impl From<CommandMessage> for Vec<u8> {
     /// Serialize this Command, consuming it and creating a Vec<u8>
-    fn into(mut self) -> Vec<u8> {
-        let mut result = Vec::with_capacity(3 + self.len());
-        result.push(self.command_type as u8);
-        result.write_u16::<BigEndian>(self.len() as u16).unwrap();
+    fn from(mut command: CommandMessage) -> Vec<u8> {
+        let mut result = Vec::with_capacity(3 + command.len());
+        result.push(command.command_type as u8);
+        result.write_u16::<BigEndian>(command.len() as u16).unwrap();
 
-        if let Some(session_id) = self.session_id {
+        if let Some(session_id) = command.session_id {
             result.push(session_id.to_u8());
         }
 
-        result.append(&mut self.data);
+        result.append(&mut command.data);
 
-        if let Some(mac) = self.mac {
+        if let Some(mac) = command.mac {
             result.extend_from_slice(mac.as_slice());
         }
 
@@ -0,0 +1,262 @@
+//! Unified configuration for all `Connector` types
+
+use std::str::FromStr;
+
+#[cfg(feature = "http")]
+use super::http::{HttpConfig, HttpConnector};
+#[cfg(feature = "usb")]
+use super::usb::{UsbConfig, UsbConnector};
+use super::ConnectionErrorKind::AddrInvalid;
+use super::{ConnectionError, Connector};
+#[cfg(feature = "mockhsm")]
+use mockhsm::MockHsm;
+
+/// Configuration for any of the supported `Connector` types, selected at
+/// runtime. Deserializes from a table with a `type` field, e.g. in TOML:
+///
+/// ```toml
+/// [connector]
+/// type = "http"
+/// addr = "127.0.0.1"
+/// port = 12345
+/// timeout_ms = 5000
+/// ```
+///
+/// It can also be parsed from a URL (see `FromStr`):
+///
+/// * `http://127.0.0.1:12345`: `yubihsm-connector` at the given address
+/// * `usb://`: first YubiHSM2 detected on the USB bus
+/// * `usb://?serial=0123456789`: YubiHSM2 with the given serial number
+/// * `mock://`: `MockHsm` (for testing)
+///
+/// Both `http` and `usb` URLs accept a `timeout_ms` query parameter.
+///
+/// `https://` URLs are rejected: `HttpConnector` speaks plain HTTP only, as
+/// `yubihsm-connector` does by default. Traffic to the HSM is still
+/// encrypted and authenticated by the session's secure channel.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ConnectorConfig {
+    /// Connect to `yubihsm-connector` over HTTP
+    #[cfg(feature = "http")]
+    Http(HttpConfig),
+
+    /// Connect to a YubiHSM2 directly via USB
+    #[cfg(feature = "usb")]
+    Usb(UsbConfig),
+
+    /// Use a simulated HSM (for testing)
+    #[cfg(feature = "mockhsm")]
+    #[serde(rename = "mock")]
+    MockHsm,
+}
+
+impl ConnectorConfig {
+    /// Create a new `Connector` from this configuration
+    pub fn new_connector(&self) -> Result<Box<dyn Connector>, ConnectionError> {
+        match *self {
+            #[cfg(feature = "http")]
+            ConnectorConfig::Http(ref config) => Ok(HttpConnector::new(config)?.into()),
+            #[cfg(feature = "usb")]
+            ConnectorConfig::Usb(ref config) => Ok(UsbConnector::new(config)?.into()),
+            #[cfg(feature = "mockhsm")]
+            ConnectorConfig::MockHsm => Ok(MockHsm::new().into()),
+        }
+    }
+}
+
+impl FromStr for ConnectorConfig {
+    type Err = ConnectionError;
+
+    fn from_str(url: &str) -> Result<Self, ConnectionError> {
+        let (scheme, rest) = match url.find("://") {
+            Some(pos) => (&url[..pos], &url[pos + 3..]),
+            None => fail!(AddrInvalid, "missing scheme in connector URL: {}", url),
+        };
+
+        let (authority, query) = match rest.find('?') {
+            Some(pos) => (&rest[..pos], Some(&rest[pos + 1..])),
+            None => (rest, None),
+        };
+
+        let authority = authority.trim_end_matches('/');
+        let params = parse_query(query.unwrap_or(""))?;
+
+        match scheme {
+            "http" => parse_http(authority, &params),
+            "https" => fail!(
+                AddrInvalid,
+                "HTTPS is not supported (use http:// with yubihsm-connector on a trusted network): {}",
+                url
+            ),
+            "usb" => parse_usb(authority, &params),
+            "mock" => parse_mock(authority, &params),
+            _ => fail!(AddrInvalid, "unknown connector URL scheme: {}", url),
+        }
+    }
+}
+
+/// Parse a URL into a `ConnectorConfig` and create a `Connector` from it.
+///
+/// Fails with `AddrInvalid` for `https://` URLs (see `ConnectorConfig`).
+pub fn from_url(url: &str) -> Result<Box<dyn Connector>, ConnectionError> {
+    url.parse::<ConnectorConfig>()?.new_connector()
+}
+
+/// Parse `http://host:port?timeout_ms=...`
+#[cfg(feature = "http")]
+fn parse_http(
+    authority: &str,
+    params: &[(&str, &str)],
+) -> Result<ConnectorConfig, ConnectionError> {
+    let mut config = HttpConfig::default();
+
+    // Find the port separator, skipping over bracketed IPv6 addresses
+    let host_end = authority.rfind(']').unwrap_or(0);
+
+    match authority[host_end..].rfind(':') {
+        Some(pos) => {
+            let (host, port) = authority.split_at(host_end + pos);
+            config.addr = host.to_owned();
+            config.port = port[1..]
+                .parse()
+                .map_err(|_| err!(AddrInvalid, "invalid port: {}", &port[1..]))?;
+        }
+        None => config.addr = authority.to_owned(),
+    }
+
+    ensure!(
+        !config.addr.is_empty(),
+        AddrInvalid,
+        "missing host in http:// URL"
+    );
+
+    for &(key, value) in params {
+        match key {
+            "timeout_ms" => config.timeout_ms = parse_timeout(value)?,
+            _ => fail!(AddrInvalid, "unknown http:// URL parameter: {}", key),
+        }
+    }
+
+    Ok(ConnectorConfig::Http(config))
+}
+
+#[cfg(not(feature = "http"))]
+fn parse_http(_: &str, _: &[(&str, &str)]) -> Result<ConnectorConfig, ConnectionError> {
+    fail!(
+        AddrInvalid,
+        "http:// URLs require the \"http\" cargo feature"
+    );
+}
+
+/// Parse `usb://?serial=...&timeout_ms=...`
+#[cfg(feature = "usb")]
+fn parse_usb(authority: &str, params: &[(&str, &str)]) -> Result<ConnectorConfig, ConnectionError> {
+    ensure!(
+        authority.is_empty(),
+        AddrInvalid,
+        "usb:// URLs don't take a host (use usb://?serial=...): {}",
+        authority
+    );
+
+    let mut config = UsbConfig::default();
+
+    for &(key, value) in params {
+        match key {
+            "serial" => config.serial = Some(value.parse()?),
+            "timeout_ms" => config.timeout_ms = parse_timeout(value)?,
+            _ => fail!(AddrInvalid, "unknown usb:// URL parameter: {}", key),
+        }
+    }
+
+    Ok(ConnectorConfig::Usb(config))
+}
+
+#[cfg(not(feature = "usb"))]
+fn parse_usb(_: &str, _: &[(&str, &str)]) -> Result<ConnectorConfig, ConnectionError> {
+    fail!(AddrInvalid, "usb:// URLs require the \"usb\" cargo feature");
+}
+
+/// Parse `mock://`
+#[cfg(feature = "mockhsm")]
+fn parse_mock(
+    authority: &str,
+    params: &[(&str, &str)],
+) -> Result<ConnectorConfig, ConnectionError> {
+    ensure!(
+        authority.is_empty() && params.is_empty(),
+        AddrInvalid,
+        "mock:// URLs don't take a host or parameters"
+    );
+
+    Ok(ConnectorConfig::MockHsm)
+}
+
+#[cfg(not(feature = "mockhsm"))]
+fn parse_mock(_: &str, _: &[(&str, &str)]) -> Result<ConnectorConfig, ConnectionError> {
+    fail!(
+        AddrInvalid,
+        "mock:// URLs require the \"mockhsm\" cargo feature"
+    );
+}
+
+/// Split a query string into key/value pairs
+fn parse_query(query: &str) -> Result<Vec<(&str, &str)>, ConnectionError> {
+    let mut params = vec![];
+
+    for param in query.split('&').filter(|p| !p.is_empty()) {
+        match param.find('=') {
+            Some(pos) => params.push((&param[..pos], &param[pos + 1..])),
+            None => fail!(AddrInvalid, "malformed URL parameter: {}", param),
+        }
+    }
+
+    Ok(params)
+}
+
+/// Parse a `timeout_ms` parameter
+#[cfg(any(feature = "http", feature = "usb"))]
+fn parse_timeout(value: &str) -> Result<u64, ConnectionError> {
+    value
+        .parse()
+        .map_err(|_| err!(AddrInvalid, "invalid timeout_ms: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "http")]
+    #[test]
+    // `Http` is the only variant unless other connector features are enabled
+    #[allow(unreachable_patterns)]
+    fn test_parse_http_url() {
+        match "http://10.0.0.1:8080/?timeout_ms=100".parse().unwrap() {
+            ConnectorConfig::Http(config) => {
+                assert_eq!(config.addr, "10.0.0.1");
+                assert_eq!(config.port, 8080);
+                assert_eq!(config.timeout_ms, 100);
+            }
+            other => panic!("unexpected config: {:?}", other),
+        }
+
+        match "http://[::1]".parse().unwrap() {
+            ConnectorConfig::Http(config) => {
+                assert_eq!(config.addr, "[::1]");
+                assert_eq!(config.port, HttpConfig::default().port);
+            }
+            other => panic!("unexpected config: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_urls() {
+        for url in &[
+            "https://127.0.0.1:12345",
+            "127.0.0.1:12345",
+            "ftp://example.com",
+        ] {
+            assert!(url.parse::<ConnectorConfig>().is_err(), "parsed {}", url);
+        }
+    }
+}
@@ -12,7 +12,7 @@ use error::Error;
 pub type ConnectionError = Error<ConnectionErrorKind>;
 
 /// `yubihsm-connector` related error kinds
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Deserialize, Fail, Serialize)]
 pub enum ConnectionErrorKind {
     /// Address provided was not valid
     #[fail(display = "invalid address")]
@@ -65,6 +65,12 @@ impl From<libusb::Error> for ConnectionError {
     fn from(err: libusb::Error) -> ConnectionError {
         match err {
             libusb::Error::Access => err!(ConnectionErrorKind::AccessDenied, "{}", err),
+            libusb::Error::Busy => err!(ConnectionErrorKind::DeviceBusyError, "{}", err),
+            libusb::Error::NoDevice => err!(
+                ConnectionErrorKind::UsbError,
+                "YubiHSM 2 disconnected: {}",
+                err
+            ),
             libusb::Error::Io => err!(ConnectionErrorKind::IoError, "{}", err),
             _ => err!(ConnectionErrorKind::UsbError, "{}", err),
         }
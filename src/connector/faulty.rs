@@ -0,0 +1,292 @@
+//! Connector which injects faults into another connector (for testing)
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::ConnectionErrorKind::{self, ConnectionFailed, IoError};
+use super::{Connection, ConnectionError, Connector, HostChallenge};
+use serial_number::SerialNumber;
+
+/// Faults which can be injected into a message exchanged with the HSM
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Fault {
+    /// Wait for the given duration before sending the message
+    Latency(Duration),
+
+    /// Send the message, but discard the response and return an `IoError`
+    DropResponse,
+
+    /// Truncate the response to the given number of bytes
+    Truncate(usize),
+
+    /// Flip the bit at the given offset in the response (wrapping around
+    /// if the offset is larger than the response)
+    BitFlip(usize),
+
+    /// Fail with the given error kind without sending the message
+    Error(ConnectionErrorKind),
+}
+
+/// Plan for which faults to inject into which messages.
+///
+/// A plan consists of a script, which is consumed one entry per message
+/// (with `None` letting a message through untouched), optionally followed
+/// by randomly injected faults once the script runs out. Randomness comes
+/// from a seeded RNG, so a given seed always produces the same faults.
+#[derive(Debug, Default)]
+pub struct FaultPlan {
+    /// Faults to inject into upcoming messages, in order
+    script: VecDeque<Option<Fault>>,
+
+    /// Faults to inject at random after the script has been consumed
+    random: Option<RandomFaults>,
+
+    /// Number of upcoming health checks to fail
+    healthcheck_failures: usize,
+
+    /// Number of faults injected so far
+    injected: usize,
+}
+
+/// Randomly injected faults
+#[derive(Debug)]
+struct RandomFaults {
+    /// Seeded RNG
+    rng: StdRng,
+
+    /// Probability of injecting a fault into any given message
+    probability: f64,
+
+    /// Faults to choose from
+    faults: Vec<Fault>,
+}
+
+impl FaultPlan {
+    /// Create a plan which doesn't inject any faults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a plan which injects the given faults into upcoming messages
+    pub fn scripted<I>(script: I) -> Self
+    where
+        I: IntoIterator<Item = Option<Fault>>,
+    {
+        Self {
+            script: script.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Inject one of the given faults (picked at random) into each message
+    /// with the given probability, once any scripted faults have been used.
+    ///
+    /// Panics if `probability` isn't between 0 and 1.
+    pub fn random(mut self, seed: [u8; 32], probability: f64, faults: Vec<Fault>) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "probability must be between 0 and 1: {}",
+            probability
+        );
+
+        self.random = Some(RandomFaults {
+            rng: StdRng::from_seed(seed),
+            probability,
+            faults,
+        });
+        self
+    }
+
+    /// Fail the next `count` health checks
+    pub fn fail_healthchecks(mut self, count: usize) -> Self {
+        self.healthcheck_failures = count;
+        self
+    }
+
+    /// Number of faults injected so far (including failed health checks)
+    pub fn injected(&self) -> usize {
+        self.injected
+    }
+
+    /// Get the fault to inject into the next message (if any)
+    fn next_fault(&mut self) -> Option<Fault> {
+        let fault = match self.script.pop_front() {
+            Some(scripted) => scripted,
+            None => self.random.as_mut().and_then(RandomFaults::next_fault),
+        };
+
+        if fault.is_some() {
+            self.injected += 1;
+        }
+
+        fault
+    }
+
+    /// Should the next health check fail?
+    fn next_healthcheck_fails(&mut self) -> bool {
+        if self.healthcheck_failures == 0 {
+            return false;
+        }
+
+        self.healthcheck_failures -= 1;
+        self.injected += 1;
+        true
+    }
+}
+
+impl RandomFaults {
+    /// Maybe pick a fault at random
+    fn next_fault(&mut self) -> Option<Fault> {
+        if self.faults.is_empty() || !self.rng.gen_bool(self.probability) {
+            return None;
+        }
+
+        let index = self.rng.gen_range(0, self.faults.len());
+        Some(self.faults[index])
+    }
+}
+
+/// Wraps another `Connector`, injecting faults into the messages sent via
+/// its connections according to a `FaultPlan`. This makes it possible to
+/// test how error conditions like flaky connections or corrupted responses
+/// are handled, e.g. in combination with the `MockHsm`.
+///
+/// All connections opened by a `FaultyConnector` share the same plan.
+pub struct FaultyConnector {
+    /// Connector to inject faults into
+    inner: Box<dyn Connector>,
+
+    /// Plan for which faults to inject
+    plan: Arc<Mutex<FaultPlan>>,
+}
+
+impl FaultyConnector {
+    /// Inject faults into the given connector according to the given plan
+    pub fn new<C: Into<Box<dyn Connector>>>(inner: C, plan: FaultPlan) -> Self {
+        Self {
+            inner: inner.into(),
+            plan: Arc::new(Mutex::new(plan)),
+        }
+    }
+
+    /// Get a handle which can be used to replace the plan or count injected
+    /// faults after this connector has been moved into a `Client`
+    pub fn handle(&self) -> FaultyConnectorHandle {
+        FaultyConnectorHandle(Arc::clone(&self.plan))
+    }
+}
+
+impl Connector for FaultyConnector {
+    /// Open a connection via the inner connector which injects faults
+    fn connect(&self) -> Result<Box<dyn Connection>, ConnectionError> {
+        Ok(Box::new(FaultyConnection {
+            inner: self.inner.connect()?,
+            plan: Arc::clone(&self.plan),
+        }))
+    }
+
+    /// Check the health of the inner connector, unless a failure is planned
+    fn healthcheck(&self) -> Result<(), ConnectionError> {
+        if self.plan.lock().unwrap().next_healthcheck_fails() {
+            fail!(ConnectionFailed, "injected fault: health check failed");
+        }
+
+        self.inner.healthcheck()
+    }
+
+    /// Get the serial number from the inner connector
+    fn serial_number(&self) -> Result<SerialNumber, ConnectionError> {
+        self.inner.serial_number()
+    }
+
+    /// Use the inner connector's host challenge (if any)
+    fn host_challenge(&self) -> Option<HostChallenge> {
+        self.inner.host_challenge()
+    }
+}
+
+impl From<FaultyConnector> for Box<dyn Connector> {
+    fn from(connector: FaultyConnector) -> Box<dyn Connector> {
+        Box::new(connector)
+    }
+}
+
+/// Handle to the plan of a `FaultyConnector`
+#[derive(Clone)]
+pub struct FaultyConnectorHandle(Arc<Mutex<FaultPlan>>);
+
+impl FaultyConnectorHandle {
+    /// Replace the current plan
+    pub fn set_plan(&self, plan: FaultPlan) {
+        *self.0.lock().unwrap() = plan;
+    }
+
+    /// Number of faults injected so far
+    pub fn injected(&self) -> usize {
+        self.0.lock().unwrap().injected()
+    }
+}
+
+/// Wraps another `Connection`, injecting faults into the messages sent
+/// through it according to a `FaultPlan`
+pub struct FaultyConnection {
+    /// Connection to inject faults into
+    inner: Box<dyn Connection>,
+
+    /// Plan for which faults to inject
+    plan: Arc<Mutex<FaultPlan>>,
+}
+
+impl FaultyConnection {
+    /// Inject faults into the given connection according to the given plan
+    pub fn new(inner: Box<dyn Connection>, plan: FaultPlan) -> Self {
+        Self {
+            inner,
+            plan: Arc::new(Mutex::new(plan)),
+        }
+    }
+}
+
+impl Connection for FaultyConnection {
+    /// Send a message via the inner connection, injecting the next planned fault
+    fn send_message(&self, uuid: Uuid, msg: Vec<u8>) -> Result<Vec<u8>, ConnectionError> {
+        let fault = self.plan.lock().unwrap().next_fault();
+
+        if let Some(fault) = fault {
+            debug!("uuid={} injecting fault: {:?}", uuid, fault);
+        }
+
+        match fault {
+            None => self.inner.send_message(uuid, msg),
+            Some(Fault::Latency(duration)) => {
+                thread::sleep(duration);
+                self.inner.send_message(uuid, msg)
+            }
+            Some(Fault::DropResponse) => {
+                self.inner.send_message(uuid, msg)?;
+                fail!(IoError, "injected fault: response dropped");
+            }
+            Some(Fault::Truncate(len)) => {
+                let mut response = self.inner.send_message(uuid, msg)?;
+                response.truncate(len);
+                Ok(response)
+            }
+            Some(Fault::BitFlip(offset)) => {
+                let mut response = self.inner.send_message(uuid, msg)?;
+
+                if !response.is_empty() {
+                    let bit = offset % (response.len() * 8);
+                    response[bit / 8] ^= 1 << (bit % 8);
+                }
+
+                Ok(response)
+            }
+            Some(Fault::Error(kind)) => Err(err!(kind, "injected fault")),
+        }
+    }
+}
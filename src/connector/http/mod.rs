@@ -58,7 +58,7 @@ impl HttpConnector {
 
 impl Connector for HttpConnector {
     /// Open a connection to `yubihsm-connector`
-    fn connect(&self) -> Result<Box<Connection>, ConnectionError> {
+    fn connect(&self) -> Result<Box<dyn Connection>, ConnectionError> {
         Ok(Box::new(HttpConnection::open(&self.0)?))
     }
 
@@ -88,8 +88,8 @@ impl Connector for HttpConnector {
     }
 }
 
-impl Into<Box<Connector>> for HttpConnector {
-    fn into(self) -> Box<Connector> {
-        Box::new(self)
+impl From<HttpConnector> for Box<dyn Connector> {
+    fn from(connector: HttpConnector) -> Box<dyn Connector> {
+        Box::new(connector)
     }
 }
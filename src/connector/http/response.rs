@@ -105,8 +105,8 @@ impl ResponseReader {
         }
 
         for header in header_iter {
-            if header.starts_with(CONTENT_LENGTH_HEADER) {
-                let content_length: usize = header[CONTENT_LENGTH_HEADER.len()..].parse()?;
+            if let Some(content_length) = header.strip_prefix(CONTENT_LENGTH_HEADER) {
+                let content_length: usize = content_length.parse()?;
 
                 if MAX_RESPONSE_SIZE - body_offset < content_length {
                     fail!(
@@ -117,8 +117,7 @@ impl ResponseReader {
                 }
 
                 self.content_length = content_length;
-            } else if header.starts_with(TRANSFER_ENCODING_HEADER) {
-                let transfer_encoding = &header[TRANSFER_ENCODING_HEADER.len()..];
+            } else if let Some(transfer_encoding) = header.strip_prefix(TRANSFER_ENCODING_HEADER) {
                 fail!(
                     ResponseError,
                     "connection sent unsupported transfer encoding: {}",
@@ -143,12 +142,12 @@ impl ResponseReader {
     }
 }
 
-impl Into<Vec<u8>> for ResponseReader {
-    fn into(self) -> Vec<u8> {
-        let body_offset = self
+impl From<ResponseReader> for Vec<u8> {
+    fn from(reader: ResponseReader) -> Vec<u8> {
+        let body_offset = reader
             .body_offset
             .expect("we should've already read the body");
 
-        Vec::from(&self.buffer[body_offset..self.pos])
+        Vec::from(&reader.buffer[body_offset..reader.pos])
     }
 }
@@ -1,27 +1,58 @@
 #[macro_use]
 mod error;
+mod config;
+#[cfg(feature = "testing")]
+mod faulty;
 #[cfg(feature = "http")]
 pub mod http;
+#[cfg(feature = "testing")]
+mod recording;
+#[cfg(feature = "testing")]
+mod replay;
 #[cfg(feature = "usb")]
 pub mod usb;
 
 use uuid::Uuid;
 
+pub use self::config::{from_url, ConnectorConfig};
 pub use self::error::{ConnectionError, ConnectionErrorKind};
+#[cfg(feature = "testing")]
+pub use self::faulty::{
+    Fault, FaultPlan, FaultyConnection, FaultyConnector, FaultyConnectorHandle,
+};
+#[cfg(feature = "testing")]
+pub use self::recording::RecordingConnector;
+#[cfg(feature = "testing")]
+pub use self::replay::ReplayConnector;
 use serial_number::SerialNumber;
 
 /// Connectors which create `Connection` objects to the HSM
 pub trait Connector: Send + Sync {
     /// Open a connection to the HSM using this `Connector`
-    fn connect(&self) -> Result<Box<Connection>, ConnectionError>;
+    fn connect(&self) -> Result<Box<dyn Connection>, ConnectionError>;
 
     /// Ensure the connection to the HSM is healthy, or return an error
     fn healthcheck(&self) -> Result<(), ConnectionError>;
 
     /// Get the serial number for the HSM (if available)
     fn serial_number(&self) -> Result<SerialNumber, ConnectionError>;
+
+    /// Host challenge to use when opening the next session. Connectors only
+    /// need to provide one if they require a particular challenge (e.g. to
+    /// replay a recorded session). Otherwise a random challenge is used.
+    ///
+    /// Only available with the `testing` cargo feature, so sessions can't be
+    /// opened with a predictable challenge in production builds.
+    #[cfg(feature = "testing")]
+    fn host_challenge(&self) -> Option<HostChallenge> {
+        None
+    }
 }
 
+/// Challenge sent by the host when creating a session
+#[cfg(feature = "testing")]
+pub type HostChallenge = [u8; 8];
+
 /// Connections to the HSM
 pub trait Connection: Send + Sync {
     /// Send a command message to the HSM, then read and return the response
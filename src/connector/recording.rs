@@ -0,0 +1,120 @@
+//! Connector which records all messages exchanged with the HSM to a transcript
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use uuid::Uuid;
+
+use super::{Connection, ConnectionError, Connector, HostChallenge};
+use hex;
+use serial_number::SerialNumber;
+
+/// First line of every transcript
+pub(super) const TRANSCRIPT_HEADER: &str = "# yubihsm.rs connector transcript v1";
+
+/// Wraps another `Connector`, writing every request/response pair exchanged
+/// with the HSM to a transcript file which can be played back with
+/// `ReplayConnector`.
+///
+/// Each exchange is written as a line containing the request UUID, the time
+/// taken in microseconds, and the hex-encoded request and response messages:
+///
+/// ```text
+/// <uuid> <microseconds> <request hex> <response hex>
+/// ```
+///
+/// Requests which fail with a `ConnectionError` are not recorded.
+///
+/// Transcripts contain the encrypted session traffic, and the session keys
+/// can be derived from them given the auth key, so they should be treated
+/// as sensitive unless they were made using throwaway credentials.
+///
+/// Requires the `testing` cargo feature.
+pub struct RecordingConnector {
+    /// Connector being recorded
+    inner: Box<dyn Connector>,
+
+    /// Transcript being written
+    transcript: Arc<Mutex<File>>,
+}
+
+impl RecordingConnector {
+    /// Record messages sent via the given connector to a transcript file at
+    /// the given path (overwriting it if it already exists)
+    pub fn create<C, P>(inner: C, path: P) -> Result<Self, ConnectionError>
+    where
+        C: Into<Box<dyn Connector>>,
+        P: AsRef<Path>,
+    {
+        let mut file = File::create(path)?;
+        writeln!(file, "{}", TRANSCRIPT_HEADER)?;
+
+        Ok(Self {
+            inner: inner.into(),
+            transcript: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+impl Connector for RecordingConnector {
+    /// Open a connection via the inner connector which records its messages
+    fn connect(&self) -> Result<Box<dyn Connection>, ConnectionError> {
+        Ok(Box::new(RecordingConnection {
+            inner: self.inner.connect()?,
+            transcript: Arc::clone(&self.transcript),
+        }))
+    }
+
+    /// Check the health of the inner connector
+    fn healthcheck(&self) -> Result<(), ConnectionError> {
+        self.inner.healthcheck()
+    }
+
+    /// Get the serial number from the inner connector
+    fn serial_number(&self) -> Result<SerialNumber, ConnectionError> {
+        self.inner.serial_number()
+    }
+
+    /// Use the inner connector's host challenge (if any)
+    fn host_challenge(&self) -> Option<HostChallenge> {
+        self.inner.host_challenge()
+    }
+}
+
+impl From<RecordingConnector> for Box<dyn Connector> {
+    fn from(connector: RecordingConnector) -> Box<dyn Connector> {
+        Box::new(connector)
+    }
+}
+
+/// Connection which records the messages it sends and receives
+struct RecordingConnection {
+    /// Connection being recorded
+    inner: Box<dyn Connection>,
+
+    /// Transcript being written
+    transcript: Arc<Mutex<File>>,
+}
+
+impl Connection for RecordingConnection {
+    /// Send a message via the inner connection, recording it and its response
+    fn send_message(&self, uuid: Uuid, msg: Vec<u8>) -> Result<Vec<u8>, ConnectionError> {
+        let request = hex::encode(&msg);
+        let started_at = Instant::now();
+        let response = self.inner.send_message(uuid, msg)?;
+        let elapsed = started_at.elapsed();
+
+        writeln!(
+            self.transcript.lock().unwrap(),
+            "{} {} {} {}",
+            uuid,
+            elapsed.as_secs() * 1_000_000 + u64::from(elapsed.subsec_micros()),
+            request,
+            hex::encode(&response)
+        )?;
+
+        Ok(response)
+    }
+}
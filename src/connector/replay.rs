@@ -0,0 +1,203 @@
+//! Connector which plays back a transcript made with `RecordingConnector`
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::recording::TRANSCRIPT_HEADER;
+use super::ConnectionErrorKind::{ConnectionFailed, RequestError, ResponseError};
+use super::{Connection, ConnectionError, Connector, HostChallenge};
+use command::CommandCode;
+use hex;
+use serial_number::SerialNumber;
+
+/// Offset of the host challenge within a `CreateSession` request:
+/// command code (1 byte), length (2 bytes), and auth key ID (2 bytes)
+const HOST_CHALLENGE_OFFSET: usize = 5;
+
+/// A request/response pair from a transcript
+struct Exchange {
+    /// Time the HSM took to respond
+    elapsed: Duration,
+
+    /// Request message sent to the HSM
+    request: Vec<u8>,
+
+    /// Response message received from the HSM
+    response: Vec<u8>,
+}
+
+/// Plays back a transcript recorded with `RecordingConnector`, returning the
+/// recorded responses in order.
+///
+/// The host challenges used to open each recorded session are provided via
+/// `Connector::host_challenge`, so replaying the same sequence of commands
+/// with the same credentials produces byte-for-byte identical requests.
+/// Any request which differs from the transcript fails with a
+/// `RequestError`, which makes replays useful for catching protocol
+/// regressions without access to an HSM.
+///
+/// Note that commands containing random data (e.g. `Client::ping`, which
+/// echoes a random UUID) can't be replayed.
+///
+/// Requires the `testing` cargo feature.
+#[derive(Clone)]
+pub struct ReplayConnector {
+    /// Exchanges which haven't been replayed yet
+    exchanges: Arc<Mutex<VecDeque<Exchange>>>,
+
+    /// Host challenges for sessions which haven't been opened yet
+    host_challenges: Arc<Mutex<VecDeque<HostChallenge>>>,
+
+    /// Sleep for the recorded duration of each exchange
+    replay_timing: bool,
+}
+
+impl ReplayConnector {
+    /// Load a transcript from the file at the given path
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ConnectionError> {
+        let mut transcript = String::new();
+        File::open(path)?.read_to_string(&mut transcript)?;
+        Self::parse(&transcript)
+    }
+
+    /// Parse a transcript
+    pub fn parse(transcript: &str) -> Result<Self, ConnectionError> {
+        let mut lines = transcript.lines().enumerate();
+
+        match lines.next() {
+            Some((_, TRANSCRIPT_HEADER)) => (),
+            _ => fail!(ResponseError, "missing transcript header"),
+        }
+
+        let mut exchanges = VecDeque::new();
+        let mut host_challenges = VecDeque::new();
+
+        for (line_num, line) in lines {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let exchange = parse_exchange(line)
+                .ok_or_else(|| err!(ResponseError, "malformed transcript line {}", line_num + 1))?;
+
+            if let Some(challenge) = host_challenge(&exchange.request) {
+                host_challenges.push_back(challenge);
+            }
+
+            exchanges.push_back(exchange);
+        }
+
+        Ok(Self {
+            exchanges: Arc::new(Mutex::new(exchanges)),
+            host_challenges: Arc::new(Mutex::new(host_challenges)),
+            replay_timing: false,
+        })
+    }
+
+    /// Sleep for the time the HSM originally took to respond before
+    /// returning each response (default false)
+    pub fn replay_timing(mut self, replay_timing: bool) -> Self {
+        self.replay_timing = replay_timing;
+        self
+    }
+
+    /// Number of exchanges in the transcript which haven't been replayed yet
+    pub fn remaining(&self) -> usize {
+        self.exchanges.lock().unwrap().len()
+    }
+}
+
+impl Connector for ReplayConnector {
+    /// Open a connection which replays the transcript
+    fn connect(&self) -> Result<Box<dyn Connection>, ConnectionError> {
+        Ok(Box::new(self.clone()))
+    }
+
+    /// Transcripts are always healthy
+    fn healthcheck(&self) -> Result<(), ConnectionError> {
+        Ok(())
+    }
+
+    /// Serial numbers aren't recorded in transcripts
+    fn serial_number(&self) -> Result<SerialNumber, ConnectionError> {
+        fail!(
+            ConnectionFailed,
+            "serial numbers aren't available from transcripts"
+        );
+    }
+
+    /// Use the host challenge from the next recorded session
+    fn host_challenge(&self) -> Option<HostChallenge> {
+        self.host_challenges.lock().unwrap().pop_front()
+    }
+}
+
+impl Connection for ReplayConnector {
+    /// Check the message matches the next recorded request, and return the
+    /// corresponding response
+    fn send_message(&self, _uuid: Uuid, msg: Vec<u8>) -> Result<Vec<u8>, ConnectionError> {
+        let exchange = self
+            .exchanges
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| err!(ConnectionFailed, "end of transcript"))?;
+
+        ensure!(
+            msg == exchange.request,
+            RequestError,
+            "request doesn't match transcript: expected {}, got {}",
+            hex::encode(&exchange.request),
+            hex::encode(&msg)
+        );
+
+        if self.replay_timing {
+            thread::sleep(exchange.elapsed);
+        }
+
+        Ok(exchange.response)
+    }
+}
+
+impl From<ReplayConnector> for Box<dyn Connector> {
+    fn from(connector: ReplayConnector) -> Box<dyn Connector> {
+        Box::new(connector)
+    }
+}
+
+/// Parse a transcript line: `<uuid> <microseconds> <request hex> <response hex>`
+fn parse_exchange(line: &str) -> Option<Exchange> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+
+    if fields.len() != 4 {
+        return None;
+    }
+
+    let micros: u64 = fields[1].parse().ok()?;
+
+    Some(Exchange {
+        elapsed: Duration::from_micros(micros),
+        request: hex::decode(fields[2])?,
+        response: hex::decode(fields[3])?,
+    })
+}
+
+/// Extract the host challenge from a `CreateSession` request
+fn host_challenge(request: &[u8]) -> Option<HostChallenge> {
+    let mut challenge = HostChallenge::default();
+
+    if request.first() != Some(&CommandCode::CreateSession.to_u8())
+        || request.len() != HOST_CHALLENGE_OFFSET + challenge.len()
+    {
+        return None;
+    }
+
+    challenge.copy_from_slice(&request[HOST_CHALLENGE_OFFSET..]);
+    Some(challenge)
+}
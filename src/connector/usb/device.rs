@@ -22,7 +22,7 @@ use serial_number::SerialNumber;
 lazy_static! {
     /// Global USB context for accessing YubiHSM2s
     static ref GLOBAL_USB_CONTEXT: libusb::Context = libusb::Context::new().unwrap_or_else(|e| {
-        eprintln!("*** ERROR: yubihsm-rs USB context init failed: {}", e);
+        error!("USB: context init failed: {}", e);
         exit(1);
     });
 }
@@ -77,6 +77,19 @@ impl Devices {
 
     /// Detect connected YubiHSM 2s, returning a collection of them
     pub fn new(timeout: UsbTimeout) -> Result<Self, ConnectionError> {
+        Self::scan(timeout, true)
+    }
+
+    /// Detect connected YubiHSM 2s without resetting them.
+    ///
+    /// Unlike `Devices::new`, this doesn't disturb any open connections, so
+    /// it can be used to check whether a device is still attached.
+    pub fn detect(timeout: UsbTimeout) -> Result<Self, ConnectionError> {
+        Self::scan(timeout, false)
+    }
+
+    /// Enumerate YubiHSM 2s on the USB bus, optionally resetting each of them
+    fn scan(timeout: UsbTimeout, reset: bool) -> Result<Self, ConnectionError> {
         let device_list = GLOBAL_USB_CONTEXT.devices()?;
         let mut devices = vec![];
 
@@ -91,19 +104,28 @@ impl Devices {
 
             usb_debug!(device, "found YubiHSM device");
 
-            let mut handle = device
-                .open()
-                .map_err(|e| usb_err!(device, "error opening device: {}", e))?;
-
-            handle.reset().map_err(|error| match error {
-                libusb::Error::NoDevice => err!(
-                    DeviceBusyError,
-                    "USB(bus={},addr={}): couldn't reset device (already in use or disconnected)",
-                    device.bus_number(),
-                    device.address()
-                ),
-                other => usb_err!(device, "error resetting device: {}", other),
-            })?;
+            let mut handle = match device.open() {
+                Ok(handle) => handle,
+                // Devices in use by another process can't be opened, but
+                // that shouldn't keep us from detecting the others
+                Err(e) if !reset => {
+                    usb_debug!(device, "skipping device (couldn't open: {})", e);
+                    continue;
+                }
+                Err(e) => return Err(usb_err!(device, "error opening device: {}", e)),
+            };
+
+            if reset {
+                handle.reset().map_err(|error| match error {
+                    libusb::Error::NoDevice => err!(
+                        DeviceBusyError,
+                        "USB(bus={},addr={}): couldn't reset device (already in use or disconnected)",
+                        device.bus_number(),
+                        device.address()
+                    ),
+                    other => usb_err!(device, "error resetting device: {}", other),
+                })?;
+            }
 
             let language = *handle
                 .read_languages(timeout.duration())?
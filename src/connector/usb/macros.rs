@@ -13,7 +13,7 @@ macro_rules! usb_debug {
             $device.bus_number(),
             $device.address(),
             $($arg)+
-        );
+        )
     };
 }
 
@@ -26,7 +26,7 @@ macro_rules! usb_err {
             $device.bus_number(),
             $device.address(),
             $msg
-        );
+        )
     };
     ($device:expr, $fmt:expr, $($arg:tt)+) => {
         err!(
@@ -35,6 +35,6 @@ macro_rules! usb_err {
             $device.bus_number(),
             $device.address(),
             $($arg)+
-        );
+        )
     };
 }
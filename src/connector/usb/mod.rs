@@ -14,8 +14,9 @@ pub use self::{
     device::{Device, Devices},
     timeout::UsbTimeout,
 };
-use connector::{Connection, ConnectionError, Connector};
+use connector::{Connection, ConnectionError, ConnectionErrorKind::UsbError, Connector};
 use serial_number::SerialNumber;
+use std::sync::{Arc, Mutex};
 
 /// USB vendor ID for Yubico
 pub const YUBICO_VENDOR_ID: u16 = 0x1050;
@@ -39,37 +40,167 @@ pub const YUBIHSM2_BULK_IN_ENDPOINT: u8 = 0x81;
 /// [Yubico SDK] (which is a vicarious dependency of `UsbConnector` which
 /// needs the `yubihsm-connector` process).
 ///
+/// If no serial number is configured, the serial number of the first
+/// YubiHSM 2 we connect to is remembered, and subsequent connections are
+/// only made to that device. This allows the connector to reattach to
+/// the same HSM after it has been unplugged and re-enumerated (possibly at
+/// a different bus address), but never to silently switch to another one.
+///
+/// Device (re)attachment is detected by re-enumerating the USB bus in
+/// `healthcheck()` and `connect()`. The `libusb` bindings we use don't
+/// expose hotplug callback registration, so this works the same whether or
+/// not the platform supports hotplug events.
+///
 /// [Yubico SDK]: https://developers.yubico.com/YubiHSM2/Releases/
 #[derive(Clone, Default, Debug)]
-pub struct UsbConnector(UsbConfig);
+pub struct UsbConnector {
+    /// Configuration for this connector
+    config: UsbConfig,
+
+    /// Serial number of the YubiHSM 2 this connector is pinned to
+    pinned_serial: Arc<Mutex<Option<SerialNumber>>>,
+}
 
 impl UsbConnector {
     /// Create a new `UsbConnector` with the given configuration
     pub fn new(config: &UsbConfig) -> Result<Self, ConnectionError> {
-        Ok(UsbConnector(config.clone()))
+        Ok(UsbConnector {
+            config: config.clone(),
+            pinned_serial: Arc::new(Mutex::new(config.serial)),
+        })
+    }
+
+    /// Serial number of the YubiHSM 2 we connect to (if known)
+    fn target_serial(&self) -> Option<SerialNumber> {
+        *self.pinned_serial.lock().unwrap()
+    }
+
+    /// Pin this connector to the YubiHSM 2 with the given serial number,
+    /// unless it's already pinned to one
+    fn pin(&self, serial_number: SerialNumber) {
+        let mut pinned_serial = self.pinned_serial.lock().unwrap();
+
+        if pinned_serial.is_none() {
+            debug!(
+                "USB: pinning connector to YubiHSM 2 (serial #{})",
+                serial_number
+            );
+            *pinned_serial = Some(serial_number);
+        }
+    }
+
+    /// Timeout for USB operations
+    fn timeout(&self) -> UsbTimeout {
+        UsbTimeout::from_millis(self.config.timeout_ms)
     }
 }
 
 impl Connector for UsbConnector {
     /// Open a connection to `yubihsm-connector`
-    fn connect(&self) -> Result<Box<Connection>, ConnectionError> {
-        Ok(Box::new(UsbConnection::open(&self.0)?))
+    fn connect(&self) -> Result<Box<dyn Connection>, ConnectionError> {
+        let connection = Devices::open(self.target_serial(), self.timeout())?;
+        self.pin(connection.device().serial_number);
+        Ok(Box::new(connection))
     }
 
-    /// Check that the connection to the HSM is healthy
+    /// Check that the HSM is attached to the USB bus (without resetting it)
     fn healthcheck(&self) -> Result<(), ConnectionError> {
-        // TODO: do something here?
-        Ok(())
+        let devices = Devices::detect(self.timeout())?;
+        let serials: Vec<_> = devices.iter().map(|d| d.serial_number).collect();
+        check_attached(self.target_serial(), &serials)
     }
 
     /// Get the serial number for the current YubiHSM2 (if available)
     fn serial_number(&self) -> Result<SerialNumber, ConnectionError> {
-        Ok(UsbConnection::open(&self.0)?.device().serial_number)
+        match self.target_serial() {
+            Some(serial) => Ok(serial),
+            None => Ok(UsbConnection::open(&self.config)?.device().serial_number),
+        }
+    }
+}
+
+impl From<UsbConnector> for Box<dyn Connector> {
+    fn from(connector: UsbConnector) -> Box<dyn Connector> {
+        Box::new(connector)
     }
 }
 
-impl Into<Box<Connector>> for UsbConnector {
-    fn into(self) -> Box<Connector> {
-        Box::new(self)
+/// Check that the YubiHSM 2 with the given serial number (or, if we aren't
+/// pinned to one yet, any YubiHSM 2) is among the detected devices
+fn check_attached(
+    target_serial: Option<SerialNumber>,
+    detected: &[SerialNumber],
+) -> Result<(), ConnectionError> {
+    match target_serial {
+        Some(serial) => ensure!(
+            detected.contains(&serial),
+            UsbError,
+            "YubiHSM 2 (serial #{}) not detected (disconnected?)",
+            serial
+        ),
+        None => ensure!(!detected.is_empty(), UsbError, "no YubiHSM 2 detected"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn serial(s: &str) -> SerialNumber {
+        SerialNumber::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn pins_first_device() {
+        let connector = UsbConnector::default();
+        assert_eq!(connector.target_serial(), None);
+
+        connector.pin(serial("0001234567"));
+        assert_eq!(connector.target_serial(), Some(serial("0001234567")));
+
+        // Reconnecting to another device doesn't move the pin
+        connector.pin(serial("0007654321"));
+        assert_eq!(connector.target_serial(), Some(serial("0001234567")));
+    }
+
+    #[test]
+    fn configured_serial_is_pinned() {
+        let connector = UsbConnector::new(&UsbConfig {
+            serial: Some(serial("0001234567")),
+            ..UsbConfig::default()
+        })
+        .unwrap();
+
+        connector.pin(serial("0007654321"));
+        assert_eq!(connector.target_serial(), Some(serial("0001234567")));
+    }
+
+    #[test]
+    fn clones_share_pin() {
+        let connector = UsbConnector::default();
+        let clone = connector.clone();
+
+        clone.pin(serial("0001234567"));
+        assert_eq!(connector.target_serial(), Some(serial("0001234567")));
+    }
+
+    #[test]
+    fn pinned_device_must_be_attached() {
+        let pinned = Some(serial("0001234567"));
+
+        assert!(check_attached(pinned, &[serial("0007654321"), serial("0001234567")]).is_ok());
+
+        let err = check_attached(pinned, &[serial("0007654321")]).unwrap_err();
+        assert_eq!(err.kind(), UsbError);
+        assert!(check_attached(pinned, &[]).is_err());
+    }
+
+    #[test]
+    fn unpinned_accepts_any_device() {
+        assert!(check_attached(None, &[serial("0007654321")]).is_ok());
+        assert_eq!(check_attached(None, &[]).unwrap_err().kind(), UsbError);
     }
 }
@@ -0,0 +1,168 @@
+//! Minimal CBOR (RFC 7049) encoder for the structures COSE needs.
+//!
+//! Maps are encoded in the order their entries are given, so callers are
+//! responsible for using the canonical key order (RFC 7049 Section 3.9).
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+/// Major type: unsigned integer
+const UNSIGNED: u8 = 0;
+
+/// Major type: negative integer
+const NEGATIVE: u8 = 1;
+
+/// Major type: byte string
+const BYTES: u8 = 2;
+
+/// Major type: text string
+const TEXT: u8 = 3;
+
+/// Major type: array
+const ARRAY: u8 = 4;
+
+/// Major type: map
+const MAP: u8 = 5;
+
+/// Major type: tagged value
+const TAG: u8 = 6;
+
+/// CBOR data items
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Value {
+    /// Integer (major types 0 and 1)
+    Int(i64),
+
+    /// Byte string
+    Bytes(Vec<u8>),
+
+    /// Text string
+    Text(String),
+
+    /// Array
+    Array(Vec<Value>),
+
+    /// Map
+    Map(Vec<(Value, Value)>),
+
+    /// Tagged value
+    Tag(u64, Box<Value>),
+}
+
+impl Value {
+    /// Encode this value as CBOR
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut out = vec![];
+        self.encode(&mut out);
+        out
+    }
+
+    /// Append the CBOR encoding of this value to the given buffer
+    fn encode(&self, out: &mut Vec<u8>) {
+        match *self {
+            Value::Int(n) if n >= 0 => encode_header(out, UNSIGNED, n as u64),
+            Value::Int(n) => encode_header(out, NEGATIVE, (-1 - n) as u64),
+            Value::Bytes(ref bytes) => {
+                encode_header(out, BYTES, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+            Value::Text(ref text) => {
+                encode_header(out, TEXT, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Value::Array(ref items) => {
+                encode_header(out, ARRAY, items.len() as u64);
+
+                for item in items {
+                    item.encode(out);
+                }
+            }
+            Value::Map(ref entries) => {
+                encode_header(out, MAP, entries.len() as u64);
+
+                for &(ref key, ref value) in entries {
+                    key.encode(out);
+                    value.encode(out);
+                }
+            }
+            Value::Tag(tag, ref value) => {
+                encode_header(out, TAG, tag);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+/// Encode the initial byte (and any following argument bytes) of an item.
+///
+/// Writes to a `Vec` are infallible.
+fn encode_header(out: &mut Vec<u8>, major_type: u8, n: u64) {
+    let major_type = major_type << 5;
+
+    if n < 24 {
+        out.push(major_type | n as u8);
+    } else if n <= 0xff {
+        out.push(major_type | 24);
+        out.push(n as u8);
+    } else if n <= 0xffff {
+        out.push(major_type | 25);
+        out.write_u16::<BigEndian>(n as u16).unwrap();
+    } else if n <= 0xffff_ffff {
+        out.push(major_type | 26);
+        out.write_u32::<BigEndian>(n as u32).unwrap();
+    } else {
+        out.push(major_type | 27);
+        out.write_u64::<BigEndian>(n).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Examples from RFC 7049 Appendix A
+    #[test]
+    fn rfc7049_examples() {
+        let examples = vec![
+            (Value::Int(0), vec![0x00]),
+            (Value::Int(23), vec![0x17]),
+            (Value::Int(24), vec![0x18, 0x18]),
+            (Value::Int(1000), vec![0x19, 0x03, 0xe8]),
+            (
+                Value::Int(1_000_000_000_000),
+                vec![0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00],
+            ),
+            (Value::Int(-1), vec![0x20]),
+            (Value::Int(-1000), vec![0x39, 0x03, 0xe7]),
+            (
+                Value::Bytes(vec![1, 2, 3, 4]),
+                vec![0x44, 0x01, 0x02, 0x03, 0x04],
+            ),
+            (
+                Value::Text("IETF".to_owned()),
+                vec![0x64, 0x49, 0x45, 0x54, 0x46],
+            ),
+            (
+                Value::Array(vec![
+                    Value::Int(1),
+                    Value::Array(vec![Value::Int(2), Value::Int(3)]),
+                ]),
+                vec![0x82, 0x01, 0x82, 0x02, 0x03],
+            ),
+            (
+                Value::Map(vec![
+                    (Value::Int(1), Value::Int(2)),
+                    (Value::Int(3), Value::Int(4)),
+                ]),
+                vec![0xa2, 0x01, 0x02, 0x03, 0x04],
+            ),
+            (
+                Value::Tag(1, Box::new(Value::Int(1_363_896_240))),
+                vec![0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0],
+            ),
+        ];
+
+        for (value, encoded) in examples {
+            assert_eq!(value.to_vec(), encoded, "{:?}", value);
+        }
+    }
+}
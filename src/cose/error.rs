@@ -0,0 +1,30 @@
+//! Errors that occur when building COSE structures
+
+use client::{ClientError, ClientErrorKind};
+use error::Error;
+
+/// COSE errors
+pub type CoseError = Error<CoseErrorKind>;
+
+/// Kinds of COSE errors
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum CoseErrorKind {
+    /// The key's algorithm has no COSE equivalent we support
+    #[fail(display = "unsupported algorithm")]
+    AlgorithmUnsupported,
+
+    /// Errors communicating with the HSM
+    #[fail(display = "client error: {}", kind)]
+    ClientError {
+        /// Client error kind
+        kind: ClientErrorKind,
+    },
+}
+
+// TODO: capture causes?
+impl From<ClientError> for CoseError {
+    fn from(err: ClientError) -> Self {
+        let kind = CoseErrorKind::ClientError { kind: err.kind() };
+        Error::new(kind, err.message().map(str::to_owned))
+    }
+}
@@ -0,0 +1,163 @@
+//! `COSE_Key` encodings of the public keys of HSM keys
+
+use super::cbor::Value;
+use super::{CoseAlg, CoseError, CoseErrorKind::AlgorithmUnsupported};
+use client::get_pubkey::PublicKey;
+use AsymmetricAlg;
+
+/// Key type: octet key pair (e.g. Ed25519)
+const KTY_OKP: i64 = 1;
+
+/// Key type: elliptic curve keys with x and y coordinates
+const KTY_EC2: i64 = 2;
+
+/// Curve: Ed25519
+const CRV_ED25519: i64 = 6;
+
+/// `COSE_Key` (RFC 8152 Section 7) for an Ed25519 or ECDSA public key
+/// stored in the HSM
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoseKey {
+    /// Key type
+    pub kty: i64,
+
+    /// Key ID
+    pub kid: Option<Vec<u8>>,
+
+    /// Algorithm the key is used with
+    pub alg: Option<i64>,
+
+    /// Curve
+    pub crv: i64,
+
+    /// X coordinate (EC2 keys) or public key (OKP keys)
+    pub x: Vec<u8>,
+
+    /// Y coordinate (EC2 keys)
+    pub y: Option<Vec<u8>>,
+}
+
+impl CoseKey {
+    /// Create a `COSE_Key` for the given public key (as returned by
+    /// `Client::get_pubkey`), for use with the algorithm `Sign1Builder`
+    /// signs with
+    pub fn from_public_key(public_key: &PublicKey) -> Result<Self, CoseError> {
+        let alg = CoseAlg::from_asymmetric(public_key.algorithm).ok_or_else(|| {
+            err!(
+                AlgorithmUnsupported,
+                "no COSE_Key for {:?}",
+                public_key.algorithm
+            )
+        })?;
+
+        let bytes = &public_key.bytes;
+
+        let crv = match public_key.algorithm {
+            AsymmetricAlg::Ed25519 => {
+                return Ok(CoseKey {
+                    kty: KTY_OKP,
+                    kid: None,
+                    alg: Some(alg.value()),
+                    crv: CRV_ED25519,
+                    x: bytes.clone(),
+                    y: None,
+                })
+            }
+            AsymmetricAlg::EC_P256 => 1,
+            AsymmetricAlg::EC_P384 => 2,
+            AsymmetricAlg::EC_P521 => 3,
+            other => fail!(AlgorithmUnsupported, "no COSE_Key curve for {:?}", other),
+        };
+
+        // The HSM returns EC public keys as x || y
+        let (x, y) = bytes.split_at(bytes.len() / 2);
+
+        Ok(CoseKey {
+            kty: KTY_EC2,
+            kid: None,
+            alg: Some(alg.value()),
+            crv,
+            x: x.to_vec(),
+            y: Some(y.to_vec()),
+        })
+    }
+
+    /// Set the key ID
+    pub fn kid<K: Into<Vec<u8>>>(mut self, kid: K) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    /// Encode this key as CBOR
+    pub fn to_cbor(&self) -> Vec<u8> {
+        // Labels in canonical order: kty (1), kid (2), alg (3), crv (-1),
+        // x (-2), y (-3)
+        let mut entries = vec![(Value::Int(1), Value::Int(self.kty))];
+
+        if let Some(ref kid) = self.kid {
+            entries.push((Value::Int(2), Value::Bytes(kid.clone())));
+        }
+
+        if let Some(alg) = self.alg {
+            entries.push((Value::Int(3), Value::Int(alg)));
+        }
+
+        entries.push((Value::Int(-1), Value::Int(self.crv)));
+        entries.push((Value::Int(-2), Value::Bytes(self.x.clone())));
+
+        if let Some(ref y) = self.y {
+            entries.push((Value::Int(-3), Value::Bytes(y.clone())));
+        }
+
+        Value::Map(entries).to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// P-256 public key (`x || y`) from RFC 7515 Appendix A.3
+    const P256_PUBLIC_KEY: [u8; 64] = [
+        0x7f, 0xcd, 0xce, 0x27, 0x70, 0xf6, 0xc4, 0x5d, 0x41, 0x83, 0xcb, 0xee, 0x6f, 0xdb, 0x4b,
+        0x7b, 0x58, 0x07, 0x33, 0x35, 0x7b, 0xe9, 0xef, 0x13, 0xba, 0xcf, 0x6e, 0x3c, 0x7b, 0xd1,
+        0x54, 0x45, 0xc7, 0xf1, 0x44, 0xcd, 0x18, 0xc7, 0x37, 0x49, 0xde, 0xfb, 0x69, 0x29, 0x0e,
+        0xd5, 0x93, 0xfe, 0x01, 0x26, 0x91, 0x15, 0x8c, 0xb1, 0x5c, 0xf1, 0xcf, 0xf1, 0x0e, 0xf8,
+        0x29, 0x28, 0x53, 0x9c,
+    ];
+
+    #[test]
+    fn ec2_key_encoding() {
+        let public_key = PublicKey {
+            algorithm: AsymmetricAlg::EC_P256,
+            bytes: P256_PUBLIC_KEY.to_vec(),
+        };
+
+        let key = CoseKey::from_public_key(&public_key)
+            .unwrap()
+            .kid(b"11".to_vec());
+
+        // {1: 2, 2: h'3131', 3: -7, -1: 1, -2: x, -3: y}
+        let mut expected = vec![
+            0xa6, 0x01, 0x02, 0x02, 0x42, 0x31, 0x31, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20,
+        ];
+        expected.extend_from_slice(&P256_PUBLIC_KEY[..32]);
+        expected.extend_from_slice(&[0x22, 0x58, 0x20]);
+        expected.extend_from_slice(&P256_PUBLIC_KEY[32..]);
+
+        assert_eq!(key.to_cbor(), expected);
+    }
+
+    #[test]
+    fn unsupported_curve() {
+        let public_key = PublicKey {
+            algorithm: AsymmetricAlg::EC_K256,
+            bytes: P256_PUBLIC_KEY.to_vec(),
+        };
+
+        assert_eq!(
+            CoseKey::from_public_key(&public_key).unwrap_err().kind(),
+            AlgorithmUnsupported
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! `COSE_Mac0`: MACs with an implicit key
+
+use super::cbor::Value;
+use super::{headers, CoseAlg, CoseError, CoseErrorKind::*};
+use {Algorithm, Client, ObjectId, ObjectType};
+
+/// CBOR tag for `COSE_Mac0_Tagged`
+const COSE_MAC0_TAG: u64 = 17;
+
+/// Context string for `MAC_structure`s of `COSE_Mac0`
+const MAC0_CONTEXT: &str = "MAC0";
+
+/// Builder for `COSE_Mac0` structures authenticated by an HMAC key stored in
+/// the HSM
+#[derive(Clone, Debug)]
+pub struct Mac0Builder {
+    /// Payload to authenticate
+    payload: Vec<u8>,
+
+    /// Key ID to include in the unprotected header
+    kid: Option<Vec<u8>>,
+
+    /// Externally supplied data to authenticate along with the payload
+    external_aad: Vec<u8>,
+}
+
+impl Mac0Builder {
+    /// Create a builder for authenticating the given payload
+    pub fn new<P: Into<Vec<u8>>>(payload: P) -> Self {
+        Self {
+            payload: payload.into(),
+            kid: None,
+            external_aad: vec![],
+        }
+    }
+
+    /// Include the given key ID in the unprotected header
+    pub fn kid<K: Into<Vec<u8>>>(mut self, kid: K) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    /// Authenticate the given external data along with the payload (it
+    /// isn't included in the `COSE_Mac0`)
+    pub fn external_aad<A: Into<Vec<u8>>>(mut self, external_aad: A) -> Self {
+        self.external_aad = external_aad.into();
+        self
+    }
+
+    /// Compute the MAC with the given HMAC key
+    pub fn mac(&self, client: &mut Client, key_id: ObjectId) -> Result<CoseMac0, CoseError> {
+        let algorithm = client
+            .get_object_info(key_id, ObjectType::HMACKey)?
+            .algorithm;
+
+        let alg = match algorithm {
+            Algorithm::Hmac(hmac_alg) => CoseAlg::from_hmac(hmac_alg),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            err!(
+                AlgorithmUnsupported,
+                "key 0x{:04x} ({:?}) can't be used with COSE_Mac0",
+                key_id,
+                algorithm
+            )
+        })?;
+
+        let (protected, unprotected) = headers(alg, self.kid.as_deref());
+
+        let to_be_maced = Value::Array(vec![
+            Value::Text(MAC0_CONTEXT.to_owned()),
+            Value::Bytes(protected.clone()),
+            Value::Bytes(self.external_aad.clone()),
+            Value::Bytes(self.payload.clone()),
+        ])
+        .to_vec();
+
+        let tag = client.hmac(key_id, to_be_maced)?.into_vec();
+
+        let mac0 = Value::Tag(
+            COSE_MAC0_TAG,
+            Box::new(Value::Array(vec![
+                Value::Bytes(protected),
+                unprotected,
+                Value::Bytes(self.payload.clone()),
+                Value::Bytes(tag),
+            ])),
+        );
+
+        Ok(CoseMac0(mac0.to_vec()))
+    }
+}
+
+/// CBOR-encoded `COSE_Mac0_Tagged` structure
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoseMac0(Vec<u8>);
+
+impl CoseMac0 {
+    /// Borrow the CBOR encoding of this structure
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Unwrap the CBOR encoding of this structure
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for CoseMac0 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<CoseMac0> for Vec<u8> {
+    fn from(mac0: CoseMac0) -> Vec<u8> {
+        mac0.0
+    }
+}
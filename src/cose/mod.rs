@@ -0,0 +1,116 @@
+//! CBOR Object Signing and Encryption (COSE, RFC 8152) with keys stored in
+//! the HSM: `COSE_Sign1` signatures with Ed25519 and ECDSA keys, `COSE_Mac0`
+//! tags with HMAC keys, and `COSE_Key` encodings of public keys.
+//!
+//! The COSE algorithm is determined by the key: `EdDSA` for Ed25519 keys,
+//! `ES256`/`ES384`/`ES512` for P-256/P-384/P-521 keys, and `HMAC 256/256`,
+//! `HMAC 384/384`, or `HMAC 512/512` for HMAC-SHA256/384/512 keys.
+//!
+//! Structures are emitted in their tagged forms (e.g. `COSE_Sign1_Tagged`),
+//! with the algorithm in the protected header and the key ID (if any) in the
+//! unprotected header.
+
+mod cbor;
+mod error;
+mod key;
+mod mac0;
+mod sign1;
+
+pub use self::error::{CoseError, CoseErrorKind};
+pub use self::key::CoseKey;
+pub use self::mac0::{CoseMac0, Mac0Builder};
+pub use self::sign1::{CoseSign1, Sign1Builder};
+
+use self::cbor::Value;
+use {AsymmetricAlg, HmacAlg};
+
+/// Header parameter label for the algorithm
+const HEADER_ALG: i64 = 1;
+
+/// Header parameter label for the key ID
+const HEADER_KID: i64 = 4;
+
+/// COSE algorithms supported by the HSM
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CoseAlg {
+    /// EdDSA (Ed25519)
+    EdDSA,
+
+    /// ECDSA with P-256 and SHA-256
+    ES256,
+
+    /// ECDSA with P-384 and SHA-384
+    ES384,
+
+    /// ECDSA with P-521 and SHA-512
+    ES512,
+
+    /// HMAC with SHA-256 (256-bit tag)
+    HMAC256,
+
+    /// HMAC with SHA-384 (384-bit tag)
+    HMAC384,
+
+    /// HMAC with SHA-512 (512-bit tag)
+    HMAC512,
+}
+
+impl CoseAlg {
+    /// Get the algorithm's value in the COSE Algorithms registry
+    pub fn value(self) -> i64 {
+        match self {
+            CoseAlg::EdDSA => -8,
+            CoseAlg::ES256 => -7,
+            CoseAlg::ES384 => -35,
+            CoseAlg::ES512 => -36,
+            CoseAlg::HMAC256 => 5,
+            CoseAlg::HMAC384 => 6,
+            CoseAlg::HMAC512 => 7,
+        }
+    }
+
+    /// Get the signature algorithm for keys with the given algorithm
+    pub fn from_asymmetric(algorithm: AsymmetricAlg) -> Option<Self> {
+        match algorithm {
+            AsymmetricAlg::Ed25519 => Some(CoseAlg::EdDSA),
+            AsymmetricAlg::EC_P256 => Some(CoseAlg::ES256),
+            AsymmetricAlg::EC_P384 => Some(CoseAlg::ES384),
+            AsymmetricAlg::EC_P521 => Some(CoseAlg::ES512),
+            _ => None,
+        }
+    }
+
+    /// Get the curve of keys for ECDSA algorithms, or `None` for others
+    fn ecdsa_curve(self) -> Option<AsymmetricAlg> {
+        match self {
+            CoseAlg::ES256 => Some(AsymmetricAlg::EC_P256),
+            CoseAlg::ES384 => Some(AsymmetricAlg::EC_P384),
+            CoseAlg::ES512 => Some(AsymmetricAlg::EC_P521),
+            _ => None,
+        }
+    }
+
+    /// Get the MAC algorithm for HMAC keys with the given algorithm
+    pub fn from_hmac(algorithm: HmacAlg) -> Option<Self> {
+        match algorithm {
+            HmacAlg::SHA256 => Some(CoseAlg::HMAC256),
+            HmacAlg::SHA384 => Some(CoseAlg::HMAC384),
+            HmacAlg::SHA512 => Some(CoseAlg::HMAC512),
+            HmacAlg::SHA1 => None,
+        }
+    }
+}
+
+/// Encode the protected header (as a byte string's contents) and the
+/// unprotected header
+fn headers(alg: CoseAlg, kid: Option<&[u8]>) -> (Vec<u8>, Value) {
+    let protected = Value::Map(vec![(Value::Int(HEADER_ALG), Value::Int(alg.value()))]);
+
+    let unprotected = Value::Map(
+        kid.into_iter()
+            .map(|kid| (Value::Int(HEADER_KID), Value::Bytes(kid.to_vec())))
+            .collect(),
+    );
+
+    (protected.to_vec(), unprotected)
+}
@@ -0,0 +1,143 @@
+//! `COSE_Sign1`: signatures with a single signer
+
+use super::cbor::Value;
+use super::{headers, CoseAlg, CoseError, CoseErrorKind::*};
+use client::ClientErrorKind;
+use {Algorithm, Client, ObjectId, ObjectType};
+
+/// CBOR tag for `COSE_Sign1_Tagged`
+const COSE_SIGN1_TAG: u64 = 18;
+
+/// Context string for `Sig_structure`s of `COSE_Sign1`
+const SIGNATURE1_CONTEXT: &str = "Signature1";
+
+/// Builder for `COSE_Sign1` structures signed by an Ed25519 or ECDSA key
+/// stored in the HSM
+#[derive(Clone, Debug)]
+pub struct Sign1Builder {
+    /// Payload to sign
+    payload: Vec<u8>,
+
+    /// Key ID to include in the unprotected header
+    kid: Option<Vec<u8>>,
+
+    /// Externally supplied data to authenticate along with the payload
+    external_aad: Vec<u8>,
+}
+
+impl Sign1Builder {
+    /// Create a builder for signing the given payload
+    pub fn new<P: Into<Vec<u8>>>(payload: P) -> Self {
+        Self {
+            payload: payload.into(),
+            kid: None,
+            external_aad: vec![],
+        }
+    }
+
+    /// Include the given key ID in the unprotected header
+    pub fn kid<K: Into<Vec<u8>>>(mut self, kid: K) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    /// Authenticate the given external data along with the payload (it
+    /// isn't included in the `COSE_Sign1`)
+    pub fn external_aad<A: Into<Vec<u8>>>(mut self, external_aad: A) -> Self {
+        self.external_aad = external_aad.into();
+        self
+    }
+
+    /// Sign with the given key
+    pub fn sign(&self, client: &mut Client, key_id: ObjectId) -> Result<CoseSign1, CoseError> {
+        let algorithm = client
+            .get_object_info(key_id, ObjectType::AsymmetricKey)?
+            .algorithm;
+
+        let alg = match algorithm {
+            Algorithm::Asymmetric(curve) => CoseAlg::from_asymmetric(curve),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            err!(
+                AlgorithmUnsupported,
+                "key 0x{:04x} ({:?}) can't be used with COSE_Sign1",
+                key_id,
+                algorithm
+            )
+        })?;
+
+        let (protected, unprotected) = headers(alg, self.kid.as_deref());
+
+        let to_be_signed = Value::Array(vec![
+            Value::Text(SIGNATURE1_CONTEXT.to_owned()),
+            Value::Bytes(protected.clone()),
+            Value::Bytes(self.external_aad.clone()),
+            Value::Bytes(self.payload.clone()),
+        ])
+        .to_vec();
+
+        let signature = match alg.ecdsa_curve() {
+            // COSE uses fixed-width r || s rather than DER
+            Some(curve) => {
+                // The key's algorithm is already known, so hash locally rather
+                // than having `sign_ecdsa_message` look it up again
+                let digest = curve
+                    .ecdsa_alg()
+                    .and_then(|ecdsa_alg| ecdsa_alg.digest(&to_be_signed))
+                    .ok_or_else(|| err!(AlgorithmUnsupported, "no digest for {:?}", curve))?;
+
+                client
+                    .sign_ecdsa(key_id, digest)?
+                    .to_p1363(curve)
+                    .ok_or_else(|| {
+                        let kind = ClientError {
+                            kind: ClientErrorKind::ProtocolError,
+                        };
+                        err!(kind, "malformed ECDSA signature from HSM")
+                    })?
+            }
+            None => client.sign_ed25519(key_id, to_be_signed)?.0.to_vec(),
+        };
+
+        let sign1 = Value::Tag(
+            COSE_SIGN1_TAG,
+            Box::new(Value::Array(vec![
+                Value::Bytes(protected),
+                unprotected,
+                Value::Bytes(self.payload.clone()),
+                Value::Bytes(signature),
+            ])),
+        );
+
+        Ok(CoseSign1(sign1.to_vec()))
+    }
+}
+
+/// CBOR-encoded `COSE_Sign1_Tagged` structure
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoseSign1(Vec<u8>);
+
+impl CoseSign1 {
+    /// Borrow the CBOR encoding of this structure
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Unwrap the CBOR encoding of this structure
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for CoseSign1 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<CoseSign1> for Vec<u8> {
+    fn from(sign1: CoseSign1) -> Vec<u8> {
+        sign1.0
+    }
+}
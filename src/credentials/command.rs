@@ -0,0 +1,71 @@
+//! Load credentials by running an external helper command
+
+use std::process::{Command, Stdio};
+
+use super::{source, CredentialSource, Credentials, CredentialsError, CredentialsErrorKind::*};
+use object::ObjectId;
+
+/// Derives credentials from a password printed by an external command
+/// (similar to git credential helpers), e.g. a command which fetches the
+/// password from a secret store.
+///
+/// The first line the command writes to stdout is used as the password.
+/// The command's stderr is inherited, so it can prompt the user if needed.
+#[derive(Clone, Debug)]
+pub struct CommandSource {
+    /// Auth key to authenticate with
+    auth_key_id: ObjectId,
+
+    /// Program to run
+    program: String,
+
+    /// Arguments to pass to the program
+    args: Vec<String>,
+}
+
+impl CommandSource {
+    /// Create a new `CommandSource` which runs the given program (without
+    /// arguments) to obtain the password for the given auth key
+    pub fn new<P: Into<String>>(auth_key_id: ObjectId, program: P) -> Self {
+        Self {
+            auth_key_id,
+            program: program.into(),
+            args: vec![],
+        }
+    }
+
+    /// Add an argument to pass to the program
+    pub fn arg<A: Into<String>>(mut self, arg: A) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+}
+
+impl CredentialSource for CommandSource {
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|e| err!(LoadFailed, "couldn't run {}: {}", self.program, e))?;
+
+        ensure!(
+            output.status.success(),
+            LoadFailed,
+            "{} failed: {}",
+            self.program,
+            output.status
+        );
+
+        let mut password = String::from_utf8(output.stdout)
+            .map_err(|_| err!(LoadFailed, "{} printed a non-UTF-8 password", self.program))?;
+
+        // Only the first line is the password
+        if let Some(newline) = password.find('\n') {
+            password.truncate(newline + 1);
+        }
+
+        Ok(source::from_password(self.auth_key_id, password))
+    }
+}
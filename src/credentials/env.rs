@@ -0,0 +1,36 @@
+//! Load credentials from an environment variable
+
+use std::env;
+
+use super::{source, CredentialSource, Credentials, CredentialsError, CredentialsErrorKind::*};
+use object::ObjectId;
+
+/// Derives credentials from a password stored in an environment variable
+#[derive(Clone, Debug)]
+pub struct EnvSource {
+    /// Auth key to authenticate with
+    auth_key_id: ObjectId,
+
+    /// Name of the environment variable containing the password
+    var: String,
+}
+
+impl EnvSource {
+    /// Create a new `EnvSource` which reads the password for the given auth
+    /// key from the given environment variable
+    pub fn new<V: Into<String>>(auth_key_id: ObjectId, var: V) -> Self {
+        Self {
+            auth_key_id,
+            var: var.into(),
+        }
+    }
+}
+
+impl CredentialSource for EnvSource {
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        let password = env::var(&self.var)
+            .map_err(|e| err!(LoadFailed, "couldn't read {}: {}", self.var, e))?;
+
+        Ok(source::from_password(self.auth_key_id, password))
+    }
+}
@@ -0,0 +1,26 @@
+//! Errors which occur when loading credentials
+
+use std::io;
+
+use error::Error;
+
+/// Credential loading errors
+pub type CredentialsError = Error<CredentialsErrorKind>;
+
+/// Credential loading error kinds
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum CredentialsErrorKind {
+    /// Credentials couldn't be loaded from their source
+    #[fail(display = "couldn't load credentials")]
+    LoadFailed,
+
+    /// Credentials file is accessible by users besides its owner
+    #[fail(display = "insecure permissions on credentials file")]
+    PermissionsInvalid,
+}
+
+impl From<io::Error> for CredentialsError {
+    fn from(err: io::Error) -> Self {
+        err!(CredentialsErrorKind::LoadFailed, err.to_string())
+    }
+}
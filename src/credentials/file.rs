@@ -0,0 +1,75 @@
+//! Load credentials from a file
+
+use std::fs::File;
+use std::io::Read;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use super::{source, CredentialSource, Credentials, CredentialsError, CredentialsErrorKind::*};
+use object::ObjectId;
+
+/// Derives credentials from a password stored in a file.
+///
+/// On Unix the file must not be accessible by anyone besides its owner
+/// (i.e. mode `0600` or stricter), similar to SSH private keys.
+#[derive(Clone, Debug)]
+pub struct FileSource {
+    /// Auth key to authenticate with
+    auth_key_id: ObjectId,
+
+    /// Path to the file containing the password
+    path: PathBuf,
+}
+
+impl FileSource {
+    /// Create a new `FileSource` which reads the password for the given auth
+    /// key from the file at the given path
+    pub fn new<P: AsRef<Path>>(auth_key_id: ObjectId, path: P) -> Self {
+        Self {
+            auth_key_id,
+            path: path.as_ref().to_owned(),
+        }
+    }
+
+    /// Ensure the opened file isn't accessible to other users.
+    ///
+    /// The metadata of the open handle is checked (rather than the path), so
+    /// the permissions checked are those of the file actually read, even if
+    /// the path is a symlink or is replaced in the meantime.
+    #[cfg(unix)]
+    fn check_permissions(&self, file: &File) -> Result<(), CredentialsError> {
+        let mode = file.metadata()?.permissions().mode();
+
+        ensure!(
+            mode & 0o077 == 0,
+            PermissionsInvalid,
+            "{} is accessible by other users (mode {:o})",
+            self.path.display(),
+            mode & 0o777
+        );
+
+        Ok(())
+    }
+
+    /// Permissions aren't checked on platforms without Unix file modes
+    #[cfg(not(unix))]
+    fn check_permissions(&self, _file: &File) -> Result<(), CredentialsError> {
+        Ok(())
+    }
+}
+
+impl CredentialSource for FileSource {
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        let mut file = File::open(&self.path)
+            .map_err(|e| err!(LoadFailed, "couldn't open {}: {}", self.path.display(), e))?;
+
+        self.check_permissions(&file)?;
+
+        let mut password = String::new();
+        file.read_to_string(&mut password)
+            .map_err(|e| err!(LoadFailed, "couldn't read {}: {}", self.path.display(), e))?;
+
+        Ok(source::from_password(self.auth_key_id, password))
+    }
+}
@@ -0,0 +1,150 @@
+#[cfg(feature = "passwords")]
+mod command;
+#[cfg(feature = "passwords")]
+mod env;
+mod error;
+#[cfg(feature = "passwords")]
+mod file;
+#[cfg(all(feature = "passwords", feature = "rpassword"))]
+mod prompt;
+mod source;
+
+#[cfg(feature = "passwords")]
+pub use self::command::CommandSource;
+#[cfg(feature = "passwords")]
+pub use self::env::EnvSource;
+pub use self::error::{CredentialsError, CredentialsErrorKind};
+#[cfg(feature = "passwords")]
+pub use self::file::FileSource;
+#[cfg(all(feature = "passwords", feature = "rpassword"))]
+pub use self::prompt::PromptSource;
+pub use self::source::CredentialSource;
+use algorithm::AuthAlg;
+#[cfg(feature = "asymmetric-auth")]
+use asymmetric_auth_key::AsymmetricAuthKey;
+use auth_key::AuthKey;
+use object::ObjectId;
+#[cfg(feature = "passwords")]
+use password_kdf::PasswordKdf;
+
+/// Default auth key ID slot
+pub const DEFAULT_AUTH_KEY_ID: ObjectId = 1;
+
+/// Credentials used to establish a session with the HSM
+#[derive(Clone)]
+pub struct Credentials {
+    /// Key ID to authenticate with
+    pub auth_key_id: ObjectId,
+
+    /// How to authenticate with the key (and the key itself)
+    pub auth_method: AuthMethod,
+}
+
+/// Methods of authenticating to the HSM, along with their keys.
+///
+/// More methods may be added (e.g. by enabling crate features), so matches
+/// on this need a wildcard arm.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum AuthMethod {
+    /// Symmetric (SCP03) authentication with a pre-shared `AuthKey`
+    Symmetric(AuthKey),
+
+    /// Asymmetric authentication with an EC P-256 key, where the HSM only
+    /// stores the corresponding public key
+    #[cfg(feature = "asymmetric-auth")]
+    Asymmetric {
+        /// Secret key to authenticate with
+        auth_key: AsymmetricAuthKey,
+
+        /// Expected public key of the HSM's device key (`x || y`, as returned
+        /// by `Client::device_public_key`).
+        ///
+        /// If `None`, whichever key is returned when the session is opened is
+        /// trusted (i.e. trust on first use), so anything which can tamper
+        /// with the connection to the HSM (e.g. `yubihsm-connector`) can
+        /// impersonate it.
+        device_public_key: Option<Vec<u8>>,
+    },
+}
+
+impl Credentials {
+    /// Create new symmetric `Credentials` (auth key ID + `AuthKey`)
+    pub fn new(auth_key_id: ObjectId, auth_key: AuthKey) -> Self {
+        Self {
+            auth_key_id,
+            auth_method: AuthMethod::Symmetric(auth_key),
+        }
+    }
+
+    /// Create new asymmetric `Credentials` (auth key ID + `AsymmetricAuthKey`)
+    /// which only open sessions with an HSM whose device public key
+    /// (`x || y`, see `Client::device_public_key`) matches the given one
+    #[cfg(feature = "asymmetric-auth")]
+    pub fn asymmetric(
+        auth_key_id: ObjectId,
+        auth_key: AsymmetricAuthKey,
+        device_public_key: &[u8],
+    ) -> Self {
+        Self {
+            auth_key_id,
+            auth_method: AuthMethod::Asymmetric {
+                auth_key,
+                device_public_key: Some(device_public_key.to_vec()),
+            },
+        }
+    }
+
+    /// Create new asymmetric `Credentials` which trust whichever device
+    /// public key the HSM presents when each session is opened.
+    ///
+    /// The device public key is fetched without authentication, so anything
+    /// which can tamper with the connection can impersonate the HSM. Prefer
+    /// `asymmetric` unless the connection is trusted.
+    #[cfg(feature = "asymmetric-auth")]
+    pub fn asymmetric_unpinned(auth_key_id: ObjectId, auth_key: AsymmetricAuthKey) -> Self {
+        Self {
+            auth_key_id,
+            auth_method: AuthMethod::Asymmetric {
+                auth_key,
+                device_public_key: None,
+            },
+        }
+    }
+
+    /// Create a set of credentials from the given auth key and password
+    /// Uses the same password-based key derivation method as yubihsm-shell
+    /// (PBKDF2 + static salt), which is not particularly strong, so use
+    /// of a long, random password is recommended.
+    #[cfg(feature = "passwords")]
+    pub fn from_password(auth_key_id: ObjectId, password: &[u8]) -> Self {
+        Self::new(auth_key_id, AuthKey::derive_from_password(password))
+    }
+
+    /// Create a set of credentials from the given auth key and password,
+    /// deriving the auth key with the given `PasswordKdf`
+    #[cfg(feature = "passwords")]
+    pub fn from_password_with_kdf(
+        auth_key_id: ObjectId,
+        password: &[u8],
+        kdf: &PasswordKdf,
+    ) -> Self {
+        Self::new(auth_key_id, kdf.derive(password))
+    }
+
+    /// Get the algorithm of the auth key these credentials authenticate with
+    pub fn auth_alg(&self) -> AuthAlg {
+        match self.auth_method {
+            AuthMethod::Symmetric(_) => AuthAlg::YUBICO_AES,
+            #[cfg(feature = "asymmetric-auth")]
+            AuthMethod::Asymmetric { .. } => AuthAlg::EC_P256_YUBICO,
+        }
+    }
+}
+
+#[cfg(feature = "passwords")]
+impl Default for Credentials {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUTH_KEY_ID, AuthKey::default())
+    }
+}
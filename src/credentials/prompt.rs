@@ -0,0 +1,46 @@
+//! Prompt for credentials on the terminal
+
+use rpassword;
+
+use super::{source, CredentialSource, Credentials, CredentialsError, CredentialsErrorKind::*};
+use object::ObjectId;
+
+/// Derives credentials from a password typed in at the terminal (without
+/// echoing it). The prompt is shown every time a session is opened.
+#[derive(Clone, Debug)]
+pub struct PromptSource {
+    /// Auth key to authenticate with
+    auth_key_id: ObjectId,
+
+    /// Prompt to display
+    prompt: String,
+}
+
+impl PromptSource {
+    /// Create a new `PromptSource` for the given auth key, displaying a
+    /// default prompt
+    pub fn new(auth_key_id: ObjectId) -> Self {
+        Self::with_prompt(
+            auth_key_id,
+            format!("Password for auth key 0x{:04x}: ", auth_key_id),
+        )
+    }
+
+    /// Create a new `PromptSource` for the given auth key which displays the
+    /// given prompt
+    pub fn with_prompt<P: Into<String>>(auth_key_id: ObjectId, prompt: P) -> Self {
+        Self {
+            auth_key_id,
+            prompt: prompt.into(),
+        }
+    }
+}
+
+impl CredentialSource for PromptSource {
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        let password = rpassword::read_password_from_tty(Some(&self.prompt))
+            .map_err(|e| err!(LoadFailed, "couldn't read password: {}", e))?;
+
+        Ok(source::from_password(self.auth_key_id, password))
+    }
+}
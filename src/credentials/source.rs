@@ -0,0 +1,45 @@
+//! Sources which `Credentials` are loaded from when opening sessions
+
+#[cfg(feature = "passwords")]
+use zeroize::Zeroize;
+
+use super::{Credentials, CredentialsError};
+#[cfg(feature = "passwords")]
+use object::ObjectId;
+
+/// Sources of `Credentials`.
+///
+/// A `Client` asks its source for credentials every time it opens a session
+/// (including when reconnecting), so sources which load credentials from
+/// outside the process (e.g. `FileSource`) avoid holding them in memory
+/// between sessions.
+pub trait CredentialSource: Send + Sync {
+    /// Load the credentials to open a session with
+    fn credentials(&self) -> Result<Credentials, CredentialsError>;
+}
+
+/// In-memory credentials are their own source
+impl CredentialSource for Credentials {
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        Ok(self.clone())
+    }
+}
+
+/// Derive credentials from a password loaded by a source, ignoring a
+/// trailing newline and wiping the password afterward
+#[cfg(feature = "passwords")]
+pub(super) fn from_password(auth_key_id: ObjectId, password: String) -> Credentials {
+    let mut password = password.into_bytes();
+
+    let len = if password.ends_with(b"\r\n") {
+        password.len() - 2
+    } else if password.ends_with(b"\n") {
+        password.len() - 1
+    } else {
+        password.len()
+    };
+
+    let credentials = Credentials::from_password(auth_key_id, &password[..len]);
+    password.as_mut_slice().zeroize();
+    credentials
+}
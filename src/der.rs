@@ -0,0 +1,267 @@
+//! Minimal DER encoder for the ASN.1 structures this crate emits
+//! (e.g. `SubjectPublicKeyInfo`), along with a parser for splitting
+//! encoded structures (e.g. CSRs) into their elements
+//!
+//! Only definite-length encodings of the handful of universal types we
+//! need are supported.
+
+/// ASN.1 BOOLEAN
+#[cfg(feature = "x509")]
+pub(crate) const BOOLEAN: u8 = 0x01;
+
+/// ASN.1 INTEGER
+pub(crate) const INTEGER: u8 = 0x02;
+
+/// ASN.1 BIT STRING
+pub(crate) const BIT_STRING: u8 = 0x03;
+
+/// ASN.1 OCTET STRING
+#[cfg(feature = "x509")]
+pub(crate) const OCTET_STRING: u8 = 0x04;
+
+/// ASN.1 NULL
+pub(crate) const NULL: u8 = 0x05;
+
+/// ASN.1 OBJECT IDENTIFIER
+pub(crate) const OBJECT_IDENTIFIER: u8 = 0x06;
+
+/// ASN.1 UTF8String
+#[cfg(feature = "x509")]
+pub(crate) const UTF8_STRING: u8 = 0x0c;
+
+/// ASN.1 UTCTime
+#[cfg(feature = "x509")]
+pub(crate) const UTC_TIME: u8 = 0x17;
+
+/// ASN.1 GeneralizedTime
+#[cfg(feature = "x509")]
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+
+/// ASN.1 PrintableString
+#[cfg(feature = "x509")]
+pub(crate) const PRINTABLE_STRING: u8 = 0x13;
+
+/// ASN.1 SEQUENCE (constructed)
+pub(crate) const SEQUENCE: u8 = 0x30;
+
+/// ASN.1 SET (constructed)
+#[cfg(feature = "x509")]
+pub(crate) const SET: u8 = 0x31;
+
+/// Flag for context-specific tags
+#[cfg(feature = "x509")]
+pub(crate) const CONTEXT_SPECIFIC: u8 = 0x80;
+
+/// Flag for constructed (as opposed to primitive) encodings
+#[cfg(feature = "x509")]
+pub(crate) const CONSTRUCTED: u8 = 0x20;
+
+/// Encode a tag-length-value triple
+pub(crate) fn tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.len() + 6);
+    out.push(tag);
+    encode_length(&mut out, value.len());
+    out.extend_from_slice(value);
+    out
+}
+
+/// Encode a SEQUENCE containing the given (already encoded) elements
+pub(crate) fn sequence(elements: &[&[u8]]) -> Vec<u8> {
+    tlv(SEQUENCE, &elements.concat())
+}
+
+/// Encode a SET containing the given (already encoded) elements, sorting
+/// them as required for SET OF in DER
+#[cfg(feature = "x509")]
+pub(crate) fn set(elements: &[&[u8]]) -> Vec<u8> {
+    let mut elements = elements.to_vec();
+    elements.sort();
+    tlv(SET, &elements.concat())
+}
+
+/// Encode a BOOLEAN
+#[cfg(feature = "x509")]
+pub(crate) fn boolean(value: bool) -> Vec<u8> {
+    tlv(BOOLEAN, &[if value { 0xff } else { 0 }])
+}
+
+/// Encode an unsigned big endian integer as an INTEGER
+pub(crate) fn unsigned_integer(bytes: &[u8]) -> Vec<u8> {
+    // Strip redundant leading zeroes, keeping at least one byte
+    let start = bytes
+        .iter()
+        .position(|&b| b != 0)
+        .unwrap_or_else(|| bytes.len().saturating_sub(1));
+
+    let bytes = &bytes[start..];
+    let mut value = Vec::with_capacity(bytes.len() + 1);
+
+    // Add a leading zero if the high bit is set so it isn't read as negative
+    if bytes.is_empty() || bytes[0] & 0x80 != 0 {
+        value.push(0);
+    }
+
+    value.extend_from_slice(bytes);
+    tlv(INTEGER, &value)
+}
+
+/// Encode a BIT STRING with no unused bits
+pub(crate) fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    let mut value = Vec::with_capacity(bytes.len() + 1);
+    value.push(0);
+    value.extend_from_slice(bytes);
+    tlv(BIT_STRING, &value)
+}
+
+/// Encode an OCTET STRING
+#[cfg(feature = "x509")]
+pub(crate) fn octet_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(OCTET_STRING, bytes)
+}
+
+/// Encode a UTF8String
+#[cfg(feature = "x509")]
+pub(crate) fn utf8_string(s: &str) -> Vec<u8> {
+    tlv(UTF8_STRING, s.as_bytes())
+}
+
+/// Encode a PrintableString
+#[cfg(feature = "x509")]
+pub(crate) fn printable_string(s: &str) -> Vec<u8> {
+    tlv(PRINTABLE_STRING, s.as_bytes())
+}
+
+/// Encode a context-specific primitive value with an implicit tag `[n]`
+#[cfg(feature = "x509")]
+pub(crate) fn implicit(n: u8, value: &[u8]) -> Vec<u8> {
+    tlv(CONTEXT_SPECIFIC | n, value)
+}
+
+/// Encode a context-specific constructed value `[n]` (i.e. an explicit tag,
+/// or an implicitly tagged SEQUENCE or SET)
+#[cfg(feature = "x509")]
+pub(crate) fn constructed(n: u8, value: &[u8]) -> Vec<u8> {
+    tlv(CONTEXT_SPECIFIC | CONSTRUCTED | n, value)
+}
+
+/// Encode NULL
+pub(crate) fn null() -> Vec<u8> {
+    tlv(NULL, &[])
+}
+
+/// Encode an OBJECT IDENTIFIER from its arcs (e.g. `&[1, 3, 101, 112]`)
+pub(crate) fn oid(arcs: &[u64]) -> Vec<u8> {
+    assert!(arcs.len() >= 2, "OIDs must have at least two arcs");
+
+    let mut value = vec![];
+    encode_base128(&mut value, arcs[0] * 40 + arcs[1]);
+
+    for &arc in &arcs[2..] {
+        encode_base128(&mut value, arc);
+    }
+
+    tlv(OBJECT_IDENTIFIER, &value)
+}
+
+/// An element parsed from a DER encoding
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Element<'a> {
+    /// Tag byte
+    pub tag: u8,
+
+    /// Contents (i.e. without the tag and length)
+    pub value: &'a [u8],
+
+    /// The whole element (tag, length, and contents)
+    #[cfg(feature = "x509")]
+    pub encoded: &'a [u8],
+}
+
+/// Parse the first element of the given bytes, returning it along with
+/// the remaining bytes, or `None` if it's malformed
+pub(crate) fn parse(bytes: &[u8]) -> Option<(Element, &[u8])> {
+    // Multi-byte tags aren't used by any structure we parse
+    if bytes.len() < 2 || bytes[0] & 0x1f == 0x1f {
+        return None;
+    }
+
+    let (header_len, len) = if bytes[1] < 0x80 {
+        (2, bytes[1] as usize)
+    } else {
+        // Long form lengths (indefinite lengths aren't allowed in DER)
+        let len_bytes = (bytes[1] & 0x7f) as usize;
+
+        if len_bytes == 0 || len_bytes > 4 || bytes.len() < 2 + len_bytes {
+            return None;
+        }
+
+        let len = bytes[2..2 + len_bytes]
+            .iter()
+            .fold(0, |len, &byte| len << 8 | byte as usize);
+
+        (2 + len_bytes, len)
+    };
+
+    if bytes.len() - header_len < len {
+        return None;
+    }
+
+    let end = header_len + len;
+
+    let element = Element {
+        tag: bytes[0],
+        value: &bytes[header_len..end],
+        #[cfg(feature = "x509")]
+        encoded: &bytes[..end],
+    };
+
+    Some((element, &bytes[end..]))
+}
+
+/// Parse the contents of a constructed value (e.g. a SEQUENCE) into its
+/// elements, returning `None` if any are malformed
+pub(crate) fn elements(mut bytes: &[u8]) -> Option<Vec<Element>> {
+    let mut elements = vec![];
+
+    while !bytes.is_empty() {
+        let (element, rest) = parse(bytes)?;
+        elements.push(element);
+        bytes = rest;
+    }
+
+    Some(elements)
+}
+
+/// Encode a length in short or long form
+fn encode_length(out: &mut Vec<u8>, len: usize) {
+    if len < 0x80 {
+        out.push(len as u8);
+        return;
+    }
+
+    let mut bytes = vec![];
+    let mut remaining = len;
+
+    while remaining > 0 {
+        bytes.push(remaining as u8);
+        remaining >>= 8;
+    }
+
+    bytes.reverse();
+    out.push(0x80 | bytes.len() as u8);
+    out.extend_from_slice(&bytes);
+}
+
+/// Encode an OID arc as base 128 with continuation bits
+fn encode_base128(out: &mut Vec<u8>, mut n: u64) {
+    let mut digits = vec![(n & 0x7f) as u8];
+    n >>= 7;
+
+    while n > 0 {
+        digits.push(0x80 | (n & 0x7f) as u8);
+        n >>= 7;
+    }
+
+    digits.reverse();
+    out.extend_from_slice(&digits);
+}
@@ -1,9 +1,13 @@
 // Apparently bitflags isn't clippy-safe
 #![allow(missing_docs)]
-#![cfg_attr(clippy, allow(clippy::redundant_field_names, clippy::suspicious_arithmetic_impl))]
+#![cfg_attr(
+    clippy,
+    allow(clippy::redundant_field_names, clippy::suspicious_arithmetic_impl)
+)]
 
 use failure::Error;
 use std::fmt;
+use std::str::FromStr;
 
 use serde::de::{self, Deserialize, Deserializer, Visitor};
 use serde::ser::{Serialize, Serializer};
@@ -59,12 +63,49 @@ impl Domain {
     /// e.g. `Domain::at(1)` returns `Domain::DOM1`.
     pub fn at(index: usize) -> Result<Self, Error> {
         match index {
-            1...16 => Ok(DOMAINS[index - 1]),
+            1..=16 => Ok(DOMAINS[index - 1]),
             _ => bail!("invalid domain: {} (valid domains are 1-16)", index),
         }
     }
 }
 
+/// Errors parsing domains
+pub type DomainError = ::error::Error<DomainErrorKind>;
+
+/// Kinds of errors parsing domains
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum DomainErrorKind {
+    /// Domain isn't a number from 1 to 16
+    #[fail(display = "invalid domain")]
+    DomainInvalid,
+}
+
+impl FromStr for Domain {
+    type Err = DomainError;
+
+    /// Parse a comma-separated list of domain numbers (e.g. `1,2,3`) or `all`
+    fn from_str(s: &str) -> Result<Self, DomainError> {
+        if s.trim() == "all" {
+            return Ok(Domain::all());
+        }
+
+        let mut domains = Domain::empty();
+
+        for index in s.split(',').map(str::trim) {
+            match index.parse::<usize>() {
+                Ok(i @ 1..=16) => domains |= DOMAINS[i - 1],
+                _ => fail!(
+                    DomainErrorKind::DomainInvalid,
+                    "invalid domain: {:?} (valid domains are 1-16)",
+                    index
+                ),
+            }
+        }
+
+        Ok(domains)
+    }
+}
+
 impl Serialize for Domain {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -33,6 +33,11 @@ where
     pub fn kind(&self) -> T {
         *self.inner.get_context()
     }
+
+    /// Obtain the error's description (without its kind), if it has one
+    pub(crate) fn message(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 }
 
 impl<T> Display for Error<T>
@@ -42,11 +47,13 @@ where
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.description {
             None => Display::fmt(&self.inner, f),
-            Some(ref desc) => if desc == NO_DESCRIPTION {
-                Display::fmt(&self.inner, f)
-            } else {
-                write!(f, "{}: {}", &self.inner, desc)
-            },
+            Some(ref desc) => {
+                if desc == NO_DESCRIPTION {
+                    Display::fmt(&self.inner, f)
+                } else {
+                    write!(f, "{}: {}", &self.inner, desc)
+                }
+            }
         }
     }
 }
@@ -242,10 +249,10 @@ macro_rules! err {
 /// Create and return an error with a formatted message
 macro_rules! fail {
     ($kind:path, $msg:expr) => {
-        return Err(err!($kind, $msg).into());
+        return Err(err!($kind, $msg).into())
     };
     ($kind:path, $fmt:expr, $($arg:tt)+) => {
-        fail!($kind, &format!($fmt, $($arg)+));
+        fail!($kind, &format!($fmt, $($arg)+))
     };
 }
 
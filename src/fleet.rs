@@ -0,0 +1,196 @@
+//! Manage several YubiHSM2s at once, keyed by their serial numbers
+
+use std::collections::btree_map::{self, BTreeMap};
+
+use client::{device_info::DeviceInfoResponse, storage_status::StorageStatusResponse};
+use client::{Client, ClientError, ClientErrorKind::ConfigError};
+#[cfg(feature = "usb")]
+use connector::usb::{Devices, UsbConfig, UsbConnector};
+#[cfg(feature = "usb")]
+use credentials::Credentials;
+use object::ObjectId;
+use serial_number::SerialNumber;
+
+/// A set of `Client`s for several HSMs, each identified by its serial number.
+///
+/// Operations can be routed to a particular HSM either by serial number, or
+/// by key ID after registering which HSM holds a given key with
+/// `route_key()`. Fan-out operations (e.g. `device_info_all()`) are run
+/// against each HSM in turn, in serial number order.
+///
+/// Clients can use any `Connector`, so a fleet may mix HSMs attached via
+/// USB with ones reached through `yubihsm-connector`.
+#[derive(Default)]
+pub struct HsmFleet {
+    /// Clients for each HSM in the fleet
+    clients: BTreeMap<SerialNumber, Client>,
+
+    /// Which HSM to use for operations on a particular key
+    key_routes: BTreeMap<ObjectId, SerialNumber>,
+}
+
+impl HsmFleet {
+    /// Create a new, empty fleet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a `Client` for every YubiHSM2 attached via USB, obtaining the
+    /// credentials for each from the given function.
+    ///
+    /// Note that enumerating the USB bus resets every attached YubiHSM2.
+    #[cfg(feature = "usb")]
+    pub fn open_usb<F>(credentials: F) -> Result<Self, ClientError>
+    where
+        F: Fn(SerialNumber) -> Credentials,
+    {
+        let mut fleet = Self::new();
+
+        for serial_number in Devices::serial_numbers()? {
+            let config = UsbConfig {
+                serial: Some(serial_number),
+                ..UsbConfig::default()
+            };
+
+            let client = Client::open(
+                UsbConnector::new(&config)?,
+                credentials(serial_number),
+                true,
+            )?;
+
+            fleet.insert(serial_number, client)?;
+        }
+
+        Ok(fleet)
+    }
+
+    /// Add a `Client` to the fleet, identifying it by the serial number
+    /// reported by its `Connector`
+    pub fn add(&mut self, client: Client) -> Result<SerialNumber, ClientError> {
+        let serial_number = client.serial_number()?;
+        self.insert(serial_number, client)?;
+        Ok(serial_number)
+    }
+
+    /// Add a `Client` to the fleet under the given serial number
+    pub fn insert(
+        &mut self,
+        serial_number: SerialNumber,
+        client: Client,
+    ) -> Result<(), ClientError> {
+        match self.clients.entry(serial_number) {
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(client);
+                Ok(())
+            }
+            btree_map::Entry::Occupied(_) => fail!(
+                ConfigError,
+                "HSM with serial #{} is already in the fleet",
+                serial_number
+            ),
+        }
+    }
+
+    /// Remove an HSM from the fleet, along with any keys routed to it
+    pub fn remove(&mut self, serial_number: SerialNumber) -> Option<Client> {
+        let client = self.clients.remove(&serial_number)?;
+
+        let routed_keys: Vec<ObjectId> = self
+            .key_routes
+            .iter()
+            .filter(|&(_, sn)| *sn == serial_number)
+            .map(|(key_id, _)| *key_id)
+            .collect();
+
+        for key_id in routed_keys {
+            self.key_routes.remove(&key_id);
+        }
+
+        Some(client)
+    }
+
+    /// Serial numbers of all HSMs in the fleet
+    pub fn serial_numbers(&self) -> Vec<SerialNumber> {
+        self.clients.keys().cloned().collect()
+    }
+
+    /// Number of HSMs in the fleet
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Is the fleet empty?
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Get the `Client` for the HSM with the given serial number
+    pub fn client(&mut self, serial_number: SerialNumber) -> Result<&mut Client, ClientError> {
+        self.clients.get_mut(&serial_number).ok_or_else(|| {
+            err!(
+                ConfigError,
+                "no HSM with serial #{} in fleet",
+                serial_number
+            )
+        })
+    }
+
+    /// Route operations on the given key ID to the HSM with the given serial number
+    pub fn route_key(
+        &mut self,
+        key_id: ObjectId,
+        serial_number: SerialNumber,
+    ) -> Result<(), ClientError> {
+        ensure!(
+            self.clients.contains_key(&serial_number),
+            ConfigError,
+            "no HSM with serial #{} in fleet",
+            serial_number
+        );
+
+        self.key_routes.insert(key_id, serial_number);
+        Ok(())
+    }
+
+    /// Get the `Client` for the HSM which holds the given key ID
+    pub fn client_for_key(&mut self, key_id: ObjectId) -> Result<&mut Client, ClientError> {
+        let serial_number = *self
+            .key_routes
+            .get(&key_id)
+            .ok_or_else(|| err!(ConfigError, "no HSM routed for key ID: {}", key_id))?;
+
+        self.client(serial_number)
+    }
+
+    /// Iterate over the HSMs in the fleet
+    pub fn iter_mut(&mut self) -> btree_map::IterMut<SerialNumber, Client> {
+        self.clients.iter_mut()
+    }
+
+    /// Run the given operation against every HSM in the fleet, collecting
+    /// the result from each. A failure on one HSM doesn't stop the operation
+    /// from being attempted on the others.
+    pub fn for_each<F, T>(&mut self, mut op: F) -> BTreeMap<SerialNumber, Result<T, ClientError>>
+    where
+        F: FnMut(&mut Client) -> Result<T, ClientError>,
+    {
+        self.clients
+            .iter_mut()
+            .map(|(serial_number, client)| (*serial_number, op(client)))
+            .collect()
+    }
+
+    /// Get information about every HSM in the fleet
+    pub fn device_info_all(
+        &mut self,
+    ) -> BTreeMap<SerialNumber, Result<DeviceInfoResponse, ClientError>> {
+        self.for_each(Client::device_info)
+    }
+
+    /// Get storage status for every HSM in the fleet
+    pub fn storage_status_all(
+        &mut self,
+    ) -> BTreeMap<SerialNumber, Result<StorageStatusResponse, ClientError>> {
+        self.for_each(Client::storage_status)
+    }
+}
@@ -0,0 +1,41 @@
+//! Minimal hexadecimal encoding and decoding
+
+/// Lowercase hex digits
+#[cfg(any(feature = "backup", feature = "passwords", feature = "testing"))]
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encode bytes as a lowercase hex string
+#[cfg(any(feature = "backup", feature = "passwords", feature = "testing"))]
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 2);
+
+    for byte in bytes {
+        result.push(HEX_CHARS[(byte >> 4) as usize] as char);
+        result.push(HEX_CHARS[(byte & 0xf) as usize] as char);
+    }
+
+    result
+}
+
+/// Decode a hex string (either case), returning `None` if it's malformed
+pub(crate) fn decode(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.as_bytes();
+
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    hex.chunks(2)
+        .map(|pair| Some(decode_nibble(pair[0])? << 4 | decode_nibble(pair[1])?))
+        .collect()
+}
+
+/// Decode a single hex digit
+fn decode_nibble(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
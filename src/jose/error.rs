@@ -0,0 +1,38 @@
+//! Errors that occur when signing JOSE structures
+
+use client::{ClientError, ClientErrorKind};
+use error::Error;
+
+/// JOSE errors
+pub type JoseError = Error<JoseErrorKind>;
+
+/// Kinds of JOSE errors
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum JoseErrorKind {
+    /// The key can't be used with the requested JWS algorithm
+    #[fail(display = "invalid algorithm")]
+    AlgorithmInvalid,
+
+    /// The key's algorithm has no JWK representation
+    #[fail(display = "unsupported algorithm")]
+    AlgorithmUnsupported,
+
+    /// Claims couldn't be serialized as JSON
+    #[fail(display = "invalid claims")]
+    ClaimsInvalid,
+
+    /// Errors communicating with the HSM
+    #[fail(display = "client error: {}", kind)]
+    ClientError {
+        /// Client error kind
+        kind: ClientErrorKind,
+    },
+}
+
+// TODO: capture causes?
+impl From<ClientError> for JoseError {
+    fn from(err: ClientError) -> Self {
+        let kind = JoseErrorKind::ClientError { kind: err.kind() };
+        Error::new(kind, err.message().map(str::to_owned))
+    }
+}
@@ -0,0 +1,150 @@
+//! JSON Web Keys for the public keys of HSM keys
+
+use serde_json;
+
+use super::{base64url, JoseError, JoseErrorKind::AlgorithmUnsupported};
+use client::get_pubkey::PublicKey;
+use AsymmetricAlg;
+
+/// RSA public exponent used by the HSM (65537), as big endian bytes
+const RSA_PUBLIC_EXPONENT: [u8; 3] = [0x01, 0x00, 0x01];
+
+/// JSON Web Key (RFC 7517) for a public key stored in the HSM
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct Jwk {
+    /// Key type (`EC`, `OKP`, or `RSA`)
+    pub kty: String,
+
+    /// Curve (EC and OKP keys)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+
+    /// X coordinate (EC keys) or public key (OKP keys)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+
+    /// Y coordinate (EC keys)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+
+    /// Modulus (RSA keys)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+
+    /// Public exponent (RSA keys)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+
+    /// Algorithm the key is used with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+
+    /// Key ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+}
+
+impl Jwk {
+    /// Create a JWK for the given public key (as returned by
+    /// `Client::get_pubkey`)
+    pub fn from_public_key(public_key: &PublicKey) -> Result<Self, JoseError> {
+        let bytes = &public_key.bytes;
+
+        let curve = match public_key.algorithm {
+            AsymmetricAlg::EC_P256 => "P-256",
+            AsymmetricAlg::EC_P384 => "P-384",
+            AsymmetricAlg::EC_P521 => "P-521",
+            AsymmetricAlg::EC_K256 => "secp256k1",
+            AsymmetricAlg::Ed25519 => {
+                return Ok(Jwk {
+                    kty: "OKP".to_owned(),
+                    crv: Some("Ed25519".to_owned()),
+                    x: Some(base64url(bytes)),
+                    ..Default::default()
+                })
+            }
+            AsymmetricAlg::RSA_2048 | AsymmetricAlg::RSA_3072 | AsymmetricAlg::RSA_4096 => {
+                return Ok(Jwk {
+                    kty: "RSA".to_owned(),
+                    n: Some(base64url(bytes)),
+                    e: Some(base64url(&RSA_PUBLIC_EXPONENT)),
+                    ..Default::default()
+                })
+            }
+            other => fail!(AlgorithmUnsupported, "no JWK curve for {:?}", other),
+        };
+
+        // The HSM returns EC public keys as x || y
+        let (x, y) = bytes.split_at(bytes.len() / 2);
+
+        Ok(Jwk {
+            kty: "EC".to_owned(),
+            crv: Some(curve.to_owned()),
+            x: Some(base64url(x)),
+            y: Some(base64url(y)),
+            ..Default::default()
+        })
+    }
+
+    /// Serialize this JWK as JSON
+    pub fn to_json(&self) -> String {
+        // Serializing can't fail (the JWK only contains strings)
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// P-256 public key (`x || y`) from RFC 7515 Appendix A.3
+    const P256_PUBLIC_KEY: [u8; 64] = [
+        0x7f, 0xcd, 0xce, 0x27, 0x70, 0xf6, 0xc4, 0x5d, 0x41, 0x83, 0xcb, 0xee, 0x6f, 0xdb, 0x4b,
+        0x7b, 0x58, 0x07, 0x33, 0x35, 0x7b, 0xe9, 0xef, 0x13, 0xba, 0xcf, 0x6e, 0x3c, 0x7b, 0xd1,
+        0x54, 0x45, 0xc7, 0xf1, 0x44, 0xcd, 0x18, 0xc7, 0x37, 0x49, 0xde, 0xfb, 0x69, 0x29, 0x0e,
+        0xd5, 0x93, 0xfe, 0x01, 0x26, 0x91, 0x15, 0x8c, 0xb1, 0x5c, 0xf1, 0xcf, 0xf1, 0x0e, 0xf8,
+        0x29, 0x28, 0x53, 0x9c,
+    ];
+
+    #[test]
+    fn ec_p256_jwk() {
+        let public_key = PublicKey {
+            algorithm: AsymmetricAlg::EC_P256,
+            bytes: P256_PUBLIC_KEY.to_vec(),
+        };
+
+        assert_eq!(
+            Jwk::from_public_key(&public_key).unwrap().to_json(),
+            r#"{"kty":"EC","crv":"P-256","x":"f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU","y":"x_FEzRjHN0ne-2kpDtWT_gEmkRWMsVzxz_EO-CkoU5w"}"#
+        );
+    }
+
+    #[test]
+    fn rsa_jwk() {
+        let public_key = PublicKey {
+            algorithm: AsymmetricAlg::RSA_2048,
+            bytes: vec![0xc5; 256],
+        };
+
+        assert_eq!(
+            Jwk::from_public_key(&public_key).unwrap().to_json(),
+            format!(
+                r#"{{"kty":"RSA","n":"{}xQ","e":"AQAB"}}"#,
+                "xcXF".repeat(85)
+            )
+        );
+    }
+
+    #[test]
+    fn unsupported_curve() {
+        let public_key = PublicKey {
+            algorithm: AsymmetricAlg::EC_BP256,
+            bytes: P256_PUBLIC_KEY.to_vec(),
+        };
+
+        assert_eq!(
+            Jwk::from_public_key(&public_key).unwrap_err().kind(),
+            AlgorithmUnsupported
+        );
+    }
+}
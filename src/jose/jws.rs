@@ -0,0 +1,321 @@
+//! Compact JWS and JWT signing
+
+use serde::Serialize;
+use serde_json;
+use std::fmt::{self, Display};
+
+use super::{base64url, JoseError, JoseErrorKind::*, Jwk};
+use client::ClientErrorKind;
+use {Algorithm, AsymmetricAlg, Client, HmacAlg, ObjectId, ObjectType};
+
+/// JWS algorithms (the `alg` header parameter) supported by the HSM
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum JwsAlg {
+    /// ECDSA using P-256 and SHA-256
+    ES256,
+
+    /// ECDSA using P-384 and SHA-384
+    ES384,
+
+    /// ECDSA using P-521 and SHA-512
+    ES512,
+
+    /// Ed25519
+    EdDSA,
+
+    /// RSASSA-PKCS1-v1_5 using SHA-256
+    #[cfg(feature = "rsa")]
+    RS256,
+
+    /// RSASSA-PSS using SHA-256 and MGF1 with SHA-256
+    #[cfg(feature = "rsa")]
+    PS256,
+
+    /// HMAC using SHA-256
+    HS256,
+
+    /// HMAC using SHA-384
+    HS384,
+
+    /// HMAC using SHA-512
+    HS512,
+}
+
+impl JwsAlg {
+    /// Get the name of this algorithm, as used in the `alg` header parameter
+    pub fn name(self) -> &'static str {
+        match self {
+            JwsAlg::ES256 => "ES256",
+            JwsAlg::ES384 => "ES384",
+            JwsAlg::ES512 => "ES512",
+            JwsAlg::EdDSA => "EdDSA",
+            #[cfg(feature = "rsa")]
+            JwsAlg::RS256 => "RS256",
+            #[cfg(feature = "rsa")]
+            JwsAlg::PS256 => "PS256",
+            JwsAlg::HS256 => "HS256",
+            JwsAlg::HS384 => "HS384",
+            JwsAlg::HS512 => "HS512",
+        }
+    }
+
+    /// Type of object which can sign with this algorithm
+    fn object_type(self) -> ObjectType {
+        match self {
+            JwsAlg::HS256 | JwsAlg::HS384 | JwsAlg::HS512 => ObjectType::HMACKey,
+            _ => ObjectType::AsymmetricKey,
+        }
+    }
+
+    /// Can keys with the given algorithm sign with this algorithm?
+    fn accepts(self, algorithm: Algorithm) -> bool {
+        match (self, algorithm) {
+            (JwsAlg::ES256, Algorithm::Asymmetric(AsymmetricAlg::EC_P256))
+            | (JwsAlg::ES384, Algorithm::Asymmetric(AsymmetricAlg::EC_P384))
+            | (JwsAlg::ES512, Algorithm::Asymmetric(AsymmetricAlg::EC_P521))
+            | (JwsAlg::EdDSA, Algorithm::Asymmetric(AsymmetricAlg::Ed25519))
+            | (JwsAlg::HS256, Algorithm::Hmac(HmacAlg::SHA256))
+            | (JwsAlg::HS384, Algorithm::Hmac(HmacAlg::SHA384))
+            | (JwsAlg::HS512, Algorithm::Hmac(HmacAlg::SHA512)) => true,
+            #[cfg(feature = "rsa")]
+            (JwsAlg::RS256, Algorithm::Asymmetric(alg))
+            | (JwsAlg::PS256, Algorithm::Asymmetric(alg)) => matches!(
+                alg,
+                AsymmetricAlg::RSA_2048 | AsymmetricAlg::RSA_3072 | AsymmetricAlg::RSA_4096
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl Display for JwsAlg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// JOSE header
+#[derive(Serialize)]
+struct Header<'a> {
+    /// Signature algorithm
+    alg: &'static str,
+
+    /// Media type of the complete JWS (i.e. `JWT`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    typ: Option<&'static str>,
+
+    /// Key ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<&'a str>,
+}
+
+/// Signs compact JWS and JWTs with a key stored in the HSM
+#[derive(Clone, Debug)]
+pub struct JwsSigner {
+    /// ID of the signing key
+    key_id: ObjectId,
+
+    /// Signature algorithm
+    alg: JwsAlg,
+
+    /// Key ID to include in the header (`kid`)
+    kid: Option<String>,
+}
+
+impl JwsSigner {
+    /// Create a signer for the given key, ensuring it can be used with `alg`
+    pub fn new(client: &mut Client, key_id: ObjectId, alg: JwsAlg) -> Result<Self, JoseError> {
+        let info = client.get_object_info(key_id, alg.object_type())?;
+
+        ensure!(
+            alg.accepts(info.algorithm),
+            AlgorithmInvalid,
+            "key 0x{:04x} ({:?}) can't sign {}",
+            key_id,
+            info.algorithm,
+            alg
+        );
+
+        Ok(Self {
+            key_id,
+            alg,
+            kid: None,
+        })
+    }
+
+    /// Include the given key ID (`kid`) in headers and JWKs
+    pub fn kid<S: Into<String>>(mut self, kid: S) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    /// Get the ID of the signing key
+    pub fn key_id(&self) -> ObjectId {
+        self.key_id
+    }
+
+    /// Get the signature algorithm
+    pub fn alg(&self) -> JwsAlg {
+        self.alg
+    }
+
+    /// Sign the given payload, returning a compact JWS
+    pub fn sign(&self, client: &mut Client, payload: &[u8]) -> Result<String, JoseError> {
+        self.sign_compact(client, None, payload)
+    }
+
+    /// Sign the given claims (e.g. a struct deriving `Serialize`), returning
+    /// a JWT
+    pub fn sign_jwt<C: Serialize>(
+        &self,
+        client: &mut Client,
+        claims: &C,
+    ) -> Result<String, JoseError> {
+        let payload = serde_json::to_vec(claims).map_err(|e| err!(ClaimsInvalid, "{}", e))?;
+        self.sign_compact(client, Some("JWT"), &payload)
+    }
+
+    /// Get the JWK for the signing key's public key. HMAC keys are secret,
+    /// so they don't have one.
+    pub fn jwk(&self, client: &mut Client) -> Result<Jwk, JoseError> {
+        ensure!(
+            self.alg.object_type() == ObjectType::AsymmetricKey,
+            AlgorithmUnsupported,
+            "{} keys don't have public keys",
+            self.alg
+        );
+
+        let mut jwk = Jwk::from_public_key(&client.get_pubkey(self.key_id)?)?;
+        jwk.alg = Some(self.alg.name().to_owned());
+        jwk.kid = self.kid.clone();
+        Ok(jwk)
+    }
+
+    /// Sign `header.payload`, returning `header.payload.signature`
+    fn sign_compact(
+        &self,
+        client: &mut Client,
+        typ: Option<&'static str>,
+        payload: &[u8],
+    ) -> Result<String, JoseError> {
+        let header = Header {
+            alg: self.alg.name(),
+            typ,
+            kid: self.kid.as_deref(),
+        };
+
+        // Serializing the header can't fail (it only contains strings)
+        let header_json = serde_json::to_vec(&header).unwrap();
+
+        let mut jws = base64url(&header_json);
+        jws.push('.');
+        jws.push_str(&base64url(payload));
+
+        let signature = self.sign_input(client, jws.as_bytes())?;
+
+        jws.push('.');
+        jws.push_str(&base64url(&signature));
+        Ok(jws)
+    }
+
+    /// Compute the JWS signature of the given signing input
+    fn sign_input(&self, client: &mut Client, input: &[u8]) -> Result<Vec<u8>, JoseError> {
+        let curve = match self.alg {
+            JwsAlg::ES256 => AsymmetricAlg::EC_P256,
+            JwsAlg::ES384 => AsymmetricAlg::EC_P384,
+            JwsAlg::ES512 => AsymmetricAlg::EC_P521,
+            JwsAlg::EdDSA => return Ok(client.sign_ed25519(self.key_id, input)?.0.to_vec()),
+            #[cfg(feature = "rsa")]
+            JwsAlg::RS256 => {
+                return Ok(client
+                    .sign_rsa_pkcs1v15_sha256(self.key_id, input)?
+                    .into_vec())
+            }
+            #[cfg(feature = "rsa")]
+            JwsAlg::PS256 => return Ok(client.sign_rsa_pss_sha256(self.key_id, input)?.into_vec()),
+            JwsAlg::HS256 | JwsAlg::HS384 | JwsAlg::HS512 => {
+                return Ok(client.hmac(self.key_id, input)?.into_vec())
+            }
+        };
+
+        // JWS uses fixed-width r || s rather than DER
+        client
+            .sign_ecdsa_message(self.key_id, input)?
+            .to_p1363(curve)
+            .ok_or_else(|| {
+                let kind = ClientError {
+                    kind: ClientErrorKind::ProtocolError,
+                };
+                err!(kind, "malformed ECDSA signature from HSM")
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_encoding() {
+        let header = Header {
+            alg: JwsAlg::ES256.name(),
+            typ: Some("JWT"),
+            kid: Some("key-1"),
+        };
+
+        assert_eq!(
+            base64url(&serde_json::to_vec(&header).unwrap()),
+            "eyJhbGciOiJFUzI1NiIsInR5cCI6IkpXVCIsImtpZCI6ImtleS0xIn0"
+        );
+
+        let header = Header {
+            alg: JwsAlg::ES384.name(),
+            typ: None,
+            kid: None,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&header).unwrap(),
+            r#"{"alg":"ES384"}"#
+        );
+    }
+
+    #[test]
+    fn ecdsa_algorithms_accept_matching_curves() {
+        let curves = [
+            (JwsAlg::ES256, AsymmetricAlg::EC_P256),
+            (JwsAlg::ES384, AsymmetricAlg::EC_P384),
+            (JwsAlg::ES512, AsymmetricAlg::EC_P521),
+        ];
+
+        for &(alg, curve) in &curves {
+            for &(_, other_curve) in &curves {
+                assert_eq!(alg.accepts(other_curve.into()), curve == other_curve);
+            }
+
+            assert!(!alg.accepts(AsymmetricAlg::EC_K256.into()));
+            assert_eq!(alg.object_type(), ObjectType::AsymmetricKey);
+        }
+    }
+
+    #[cfg(feature = "rsa")]
+    #[test]
+    fn rsa_algorithms_accept_rsa_keys() {
+        for &alg in &[JwsAlg::RS256, JwsAlg::PS256] {
+            assert!(alg.accepts(AsymmetricAlg::RSA_2048.into()));
+            assert!(alg.accepts(AsymmetricAlg::RSA_4096.into()));
+            assert!(!alg.accepts(AsymmetricAlg::EC_P256.into()));
+        }
+
+        let header = Header {
+            alg: JwsAlg::PS256.name(),
+            typ: None,
+            kid: None,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&header).unwrap(),
+            r#"{"alg":"PS256"}"#
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! JSON Object Signing and Encryption (JOSE) with keys stored in the HSM:
+//! compact JWS (RFC 7515) and JWT (RFC 7519) signing, and JWKs (RFC 7517)
+//! for publishing the corresponding public keys.
+//!
+//! Signatures are computed by the HSM, so the signing keys never leave it:
+//!
+//! - `ES256`/`ES384`/`ES512`: ECDSA with P-256/P-384/P-521 keys
+//! - `EdDSA`: Ed25519 keys
+//! - `RS256`/`PS256`: RSA keys (requires the `rsa` cargo feature)
+//! - `HS256`/`HS384`/`HS512`: HMAC keys
+
+mod error;
+mod jwk;
+mod jws;
+
+pub use self::error::{JoseError, JoseErrorKind};
+pub use self::jwk::Jwk;
+pub use self::jws::{JwsAlg, JwsSigner};
+
+use base64;
+
+/// Encode bytes as unpadded base64url, as used throughout JOSE
+fn base64url(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
@@ -2,7 +2,7 @@
 //!
 //! ## Prerequisites
 //!
-//! This crate builds on Rust 1.27+ and by default uses SIMD features
+//! This crate builds on Rust 1.65+ and by default uses SIMD features
 //! which require the following `RUSTFLAGS`:
 //!
 //! `RUSTFLAGS=-Ctarget-feature=+aes`
@@ -53,7 +53,6 @@
 
 #![crate_name = "yubihsm"]
 #![crate_type = "rlib"]
-#![cfg_attr(clippy, feature(tool_lints))]
 #![deny(warnings, missing_docs, trivial_casts, trivial_numeric_casts)]
 #![deny(unsafe_code, unused_import_braces, unused_qualifications)]
 #![doc(
@@ -62,17 +61,25 @@
 )]
 
 extern crate aes;
+#[cfg(feature = "base64")]
+extern crate base64;
 #[macro_use]
 extern crate bitflags;
 extern crate block_modes;
 extern crate byteorder;
 extern crate cmac;
+#[cfg(feature = "ecdsa")]
+extern crate ecdsa;
+#[cfg(feature = "ed25519")]
+extern crate ed25519;
 #[macro_use]
 extern crate failure;
 #[macro_use]
 extern crate failure_derive;
 #[cfg(feature = "hmac")]
 extern crate hmac;
+#[cfg(feature = "k256")]
+extern crate k256;
 #[cfg(feature = "usb")]
 #[macro_use]
 extern crate lazy_static;
@@ -80,18 +87,32 @@ extern crate lazy_static;
 extern crate libusb;
 #[macro_use]
 extern crate log;
+#[cfg(feature = "p256")]
+extern crate p256;
+#[cfg(feature = "p384")]
+extern crate p384;
 #[cfg(feature = "pbkdf2")]
 extern crate pbkdf2;
 extern crate rand;
-#[cfg(feature = "mockhsm")]
+#[cfg(feature = "ring")]
 extern crate ring;
+#[cfg(feature = "rpassword")]
+extern crate rpassword;
+#[cfg(feature = "scrypt")]
+extern crate scrypt;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
 #[cfg(feature = "sha2")]
 extern crate sha2;
+#[cfg(feature = "signature")]
+extern crate signature;
 extern crate subtle;
-#[cfg(feature = "mockhsm")]
+#[cfg(feature = "toml")]
+extern crate toml;
+#[cfg(feature = "untrusted")]
 extern crate untrusted;
 extern crate uuid;
 extern crate zeroize;
@@ -107,12 +128,20 @@ mod serialization;
 /// Cryptographic algorithms supported by the HSM
 pub mod algorithm;
 
+/// EC P-256 keys for asymmetric authentication to the HSM.
+#[cfg(feature = "asymmetric-auth")]
+pub mod asymmetric_auth_key;
+
 /// Auditing options (for use with the `get_option` and `put_option` command)
 pub(crate) mod audit;
 
 /// Authentication keys used to establish encrypted sessions with the HSM
 pub mod auth_key;
 
+/// Whole-device backup and restore using wrap keys.
+#[cfg(feature = "backup")]
+pub mod backup;
+
 /// Object attributes specifying which operations are allowed to be performed
 pub mod capability;
 
@@ -141,54 +170,103 @@ pub mod command;
 /// [MockHsm]: https://docs.rs/yubihsm/latest/yubihsm/mockhsm/struct.MockHsm.html
 pub mod connector;
 
-/// Credentials used to authenticate to the HSM (key ID + `AuthKey`).
+/// COSE signatures and MACs with HSM keys, and `COSE_Key`s for their public keys.
+#[cfg(feature = "cose")]
+pub mod cose;
+
+/// DER encoding of ASN.1 structures (e.g. public keys).
+mod der;
+
+/// Credentials used to authenticate to the HSM (key ID + `AuthKey`), and
+/// sources they can be loaded from.
 pub mod credentials;
 
 /// Logical partitions within the HSM, allowing several applications to share the device.
 pub mod domain;
 
+/// Hexadecimal encoding (used for connector transcripts and manifests).
+mod hex;
+
+/// Managing several HSMs at once, e.g. multiple YubiHSM2s attached to the same host.
+pub mod fleet;
+
+/// JWS/JWT signing with HSM keys, and JWKs for their public keys.
+#[cfg(feature = "jose")]
+pub mod jose;
+
 /// Simulation of the HSM for integration testing.
 #[cfg(feature = "mockhsm")]
 pub mod mockhsm;
 
+/// Declarative provisioning of HSMs from a manifest.
+pub mod provision;
+
+/// Copying keys between HSMs which share a wrap key.
+pub mod replication;
+
 /// Authenticated/encrypted sessions with the HSM.
 ///
 /// For more information, see:
 /// <https://developers.yubico.com/YubiHSM2/Concepts/Session.html>
 pub mod session;
 
+/// Hooks for observing commands sent to the HSM (e.g. to collect metrics).
+pub mod observer;
+
 /// Objects stored in the HSM.
 ///
 /// For more information, see:
 /// <https://developers.yubico.com/YubiHSM2/Concepts/Object.html>
 pub mod object;
 
+/// Configurable key derivation for deriving auth keys from passwords
+#[cfg(feature = "passwords")]
+pub mod password_kdf;
+
+/// PEM encoding of DER structures (e.g. public keys and certificates).
+#[cfg(feature = "base64")]
+pub mod pem;
+
 /// Responses to command sent from the HSM.
 pub mod response;
 
 /// HSM serial numbers.
 mod serial_number;
 
+/// Signers for HSM keys implementing the `signature::Signer` trait.
+#[cfg(feature = "signer")]
+pub mod signer;
+
 /// Object wrapping support, i.e. encrypt objects from one HSM to another.
 pub mod wrap;
 
+/// X.509 certificate signing requests and a certificate authority for keys
+/// stored in the HSM.
+#[cfg(feature = "x509")]
+pub mod x509;
+
 pub use algorithm::*;
+#[cfg(feature = "asymmetric-auth")]
+pub use asymmetric_auth_key::AsymmetricAuthKey;
 pub use audit::AuditOption;
 pub use auth_key::{AuthKey, AUTH_KEY_SIZE};
 pub use capability::Capability;
-pub use client::{Client, ClientError};
+pub use client::{Client, ClientBuilder, ClientError, RetryPolicy};
 pub use command::CommandCode;
 #[cfg(feature = "http")]
 pub use connector::http::{HttpConfig, HttpConnector};
 #[cfg(feature = "usb")]
 pub use connector::usb::{UsbConfig, UsbConnector};
-pub use connector::{Connection, ConnectionError, Connector};
-pub use credentials::Credentials;
+pub use connector::{Connection, ConnectionError, Connector, ConnectorConfig};
+pub use credentials::{AuthMethod, CredentialSource, Credentials};
 pub use domain::Domain;
 pub use error::*;
+pub use fleet::HsmFleet;
 #[cfg(feature = "mockhsm")]
 pub use mockhsm::MockHsm;
 pub use object::*;
+#[cfg(feature = "passwords")]
+pub use password_kdf::PasswordKdf;
 pub use response::ResponseCode;
 pub use serial_number::SerialNumber;
 pub use session::SessionId;
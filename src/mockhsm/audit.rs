@@ -17,6 +17,7 @@ pub const DEFAULT_COMMAND_AUDIT_OPTIONS: &[AuditCommand] = &[
     AuditCommand(CommandCode::DeviceInfo, AuditOption::Off),
     AuditCommand(CommandCode::BSL, AuditOption::Off),
     AuditCommand(CommandCode::Command9, AuditOption::Off),
+    AuditCommand(CommandCode::GetDevicePubKey, AuditOption::Off),
     AuditCommand(CommandCode::Reset, AuditOption::On),
     AuditCommand(CommandCode::CloseSession, AuditOption::On),
     AuditCommand(CommandCode::StorageStatus, AuditOption::On),
@@ -62,6 +63,7 @@ pub const DEFAULT_COMMAND_AUDIT_OPTIONS: &[AuditCommand] = &[
     AuditCommand(CommandCode::UnwrapData, AuditOption::On),
     AuditCommand(CommandCode::SignDataEdDSA, AuditOption::On),
     AuditCommand(CommandCode::Blink, AuditOption::On),
+    AuditCommand(CommandCode::ChangeAuthKey, AuditOption::On),
 ];
 
 /// Per-command auditing settings
@@ -10,6 +10,7 @@ use algorithm::*;
 use audit::{AuditCommand, AuditOption, AuditTag};
 use client::{
     blink::BlinkResponse,
+    change_auth_key::{ChangeAuthKeyCommand, ChangeAuthKeyResponse},
     delete_object::{DeleteObjectCommand, DeleteObjectResponse},
     device_info::DeviceInfoResponse,
     echo::EchoResponse,
@@ -27,7 +28,7 @@ use client::{
     import_wrapped::{ImportWrappedCommand, ImportWrappedResponse},
     list_objects::{ListObjectsCommand, ListObjectsEntry, ListObjectsResponse},
     put_asymmetric_key::{PutAsymmetricKeyCommand, PutAsymmetricKeyResponse},
-    put_auth_key::{PutAuthKeyCommand, PutAuthKeyResponse},
+    put_auth_key::{PutAsymmetricAuthKeyCommand, PutAuthKeyResponse},
     put_hmac_key::{PutHMACKeyCommand, PutHMACKeyResponse},
     put_opaque::{PutOpaqueCommand, PutOpaqueResponse},
     put_option::{PutOptionCommand, PutOptionResponse},
@@ -41,12 +42,23 @@ use client::{
 use command::{CommandCode, CommandMessage};
 use connector::ConnectionError;
 use error::HsmErrorKind;
+#[cfg(feature = "asymmetric-auth")]
+use object::ObjectId;
 use response::{Response, ResponseMessage};
 use serialization::deserialize;
 use session::command::{
     close::CloseSessionResponse,
     create::{CreateSessionCommand, CreateSessionResponse},
 };
+#[cfg(feature = "asymmetric-auth")]
+use session::command::{
+    create::{CreateAsymmetricSessionCommand, CreateAsymmetricSessionResponse},
+    device_pubkey::GetDevicePubKeyResponse,
+};
+#[cfg(feature = "asymmetric-auth")]
+use session::securechannel::CHALLENGE_SIZE;
+#[cfg(feature = "asymmetric-auth")]
+use std::mem::size_of;
 use subtle::ConstantTimeEq;
 use {Capability, ObjectType, SessionId, WrapMessage, WrapNonce};
 
@@ -58,6 +70,15 @@ pub(crate) fn create_session(
     state: &mut State,
     cmd_message: &CommandMessage,
 ) -> Result<Vec<u8>, ConnectionError> {
+    // Asymmetric sessions are created by sending an ephemeral public key
+    // instead of a challenge
+    #[cfg(feature = "asymmetric-auth")]
+    {
+        if cmd_message.data.len() > size_of::<ObjectId>() + CHALLENGE_SIZE {
+            return create_asymmetric_session(state, cmd_message);
+        }
+    }
+
     let cmd: CreateSessionCommand = deserialize(cmd_message.data.as_ref())
         .unwrap_or_else(|e| panic!("error parsing CreateSession command data: {:?}", e));
 
@@ -66,12 +87,51 @@ pub(crate) fn create_session(
     let mut response = CreateSessionResponse {
         card_challenge: *session.card_challenge(),
         card_cryptogram: session.card_cryptogram(),
-    }.serialize();
+    }
+    .serialize();
 
     response.session_id = Some(session.id);
     Ok(response.into())
 }
 
+/// Create a new HSM session using asymmetric authentication
+#[cfg(feature = "asymmetric-auth")]
+fn create_asymmetric_session(
+    state: &mut State,
+    cmd_message: &CommandMessage,
+) -> Result<Vec<u8>, ConnectionError> {
+    let cmd: CreateAsymmetricSessionCommand = deserialize(cmd_message.data.as_ref())
+        .unwrap_or_else(|e| panic!("error parsing CreateSession command data: {:?}", e));
+
+    let (session_id, card_public_key, receipt) =
+        match state.create_asymmetric_session(cmd.auth_key_id, &cmd.host_public_key) {
+            Some(session) => session,
+            None => {
+                debug!(
+                    "no asymmetric auth key with ID {:?} to create session with",
+                    cmd.auth_key_id
+                );
+                return Ok(ResponseMessage::from(HsmErrorKind::AuthFail).into());
+            }
+        };
+
+    let mut response = CreateAsymmetricSessionResponse::new(&card_public_key, &receipt).serialize();
+    response.session_id = Some(session_id);
+    Ok(response.into())
+}
+
+/// Get the public key of the device key used for asymmetric authentication
+#[cfg(feature = "asymmetric-auth")]
+pub(crate) fn get_device_pubkey(state: &State) -> Result<Vec<u8>, ConnectionError> {
+    let response = GetDevicePubKeyResponse {
+        algorithm: AsymmetricAlg::EC_P256,
+        public_key: state.device_key.public_key().to_vec(),
+    }
+    .serialize();
+
+    Ok(response.into())
+}
+
 /// Authenticate an HSM session
 pub(crate) fn authenticate_session(
     state: &mut State,
@@ -107,6 +167,7 @@ pub(crate) fn session_message(
 
     let response = match command.command_type {
         CommandCode::Blink => BlinkResponse {}.serialize(),
+        CommandCode::ChangeAuthKey => change_auth_key(state, session_id, &command.data),
         CommandCode::CloseSession => return close_session(state, session_id),
         CommandCode::DeleteObject => delete_object(state, &command.data),
         CommandCode::DeviceInfo => device_info(),
@@ -144,6 +205,32 @@ pub(crate) fn session_message(
         .into())
 }
 
+/// Change the secret of the auth key the session was created with
+fn change_auth_key(state: &mut State, session_id: SessionId, cmd_data: &[u8]) -> ResponseMessage {
+    let ChangeAuthKeyCommand {
+        key_id, auth_key, ..
+    } = deserialize(cmd_data)
+        .unwrap_or_else(|e| panic!("error parsing CommandCode::ChangeAuthKey: {:?}", e));
+
+    let session_auth_key_id = state.get_session(session_id).unwrap().auth_key_id;
+
+    if key_id != session_auth_key_id {
+        debug!(
+            "can't change auth key {:?} from session authenticated with {:?}",
+            key_id, session_auth_key_id
+        );
+        return HsmErrorKind::PermissionInvalid.into();
+    }
+
+    match state.objects.get_mut(key_id, ObjectType::AuthKey) {
+        Some(object) => {
+            object.payload = Payload::AuthKey(auth_key);
+            ChangeAuthKeyResponse { key_id }.serialize()
+        }
+        None => HsmErrorKind::ObjectNotFound.into(),
+    }
+}
+
 /// Close an active session
 fn close_session(state: &mut State, session_id: SessionId) -> Result<Vec<u8>, ConnectionError> {
     let response = state
@@ -229,7 +316,8 @@ fn device_info() -> ResponseMessage {
             Algorithm::Asymmetric(AsymmetricAlg::Ed25519),
             Algorithm::Asymmetric(AsymmetricAlg::EC_P224),
         ],
-    }.serialize()
+    }
+    .serialize()
 }
 
 /// Echo a message back to the host
@@ -277,7 +365,8 @@ fn gen_asymmetric_key(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
 
     GenAsymmetricKeyResponse {
         key_id: command.key_id,
-    }.serialize()
+    }
+    .serialize()
 }
 
 /// Generate a new random HMAC key
@@ -297,7 +386,8 @@ fn gen_hmac_key(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
 
     GenHMACKeyResponse {
         key_id: command.key_id,
-    }.serialize()
+    }
+    .serialize()
 }
 
 /// Generate a new random wrap (i.e. AES-CCM) key
@@ -320,7 +410,8 @@ fn gen_wrap_key(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
 
     GenWrapKeyResponse {
         key_id: params.key_id,
-    }.serialize()
+    }
+    .serialize()
 }
 
 /// Get mock log information
@@ -331,7 +422,8 @@ fn get_logs() -> ResponseMessage {
         unlogged_auth_events: 0,
         num_entries: 0,
         entries: vec![],
-    }.serialize()
+    }
+    .serialize()
 }
 
 /// Get detailed info about a specific object
@@ -397,7 +489,8 @@ fn get_pubkey(state: &State, cmd_data: &[u8]) -> ResponseMessage {
         PublicKey {
             algorithm: obj.algorithm().asymmetric().unwrap(),
             bytes: obj.payload.public_key_bytes().unwrap(),
-        }.serialize()
+        }
+        .serialize()
     } else {
         debug!("no such object ID: {:?}", command.key_id);
         HsmErrorKind::ObjectNotFound.into()
@@ -439,7 +532,8 @@ fn import_wrapped(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
         Ok(obj) => ImportWrappedResponse {
             object_type: obj.object_type,
             object_id: obj.object_id,
-        }.serialize(),
+        }
+        .serialize(),
         Err(e) => {
             debug!("error unwrapping object: {}", e);
             HsmErrorKind::CommandInvalid.into()
@@ -460,7 +554,8 @@ fn list_objects(state: &State, cmd_data: &[u8]) -> ResponseMessage {
             object_id: object.object_info.object_id,
             object_type: object.object_info.object_type,
             sequence: object.object_info.sequence,
-        }).collect();
+        })
+        .collect();
 
     ListObjectsResponse(list_entries).serialize()
 }
@@ -486,10 +581,12 @@ fn put_asymmetric_key(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
 
 /// Put a new authentication key into the HSM
 fn put_auth_key(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
-    let PutAuthKeyCommand {
+    // Symmetric and asymmetric auth keys only differ in their key data, so
+    // parse both as the latter (which takes the remaining bytes as the key)
+    let PutAsymmetricAuthKeyCommand {
         params,
         delegated_capabilities,
-        auth_key,
+        public_key: key_data,
     } = deserialize(cmd_data)
         .unwrap_or_else(|e| panic!("error parsing CommandCode::PutAuthKey: {:?}", e));
 
@@ -501,7 +598,7 @@ fn put_auth_key(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
         params.capabilities,
         delegated_capabilities,
         params.domains,
-        &auth_key.0,
+        &key_data,
     );
 
     PutAuthKeyResponse { key_id: params.id }.serialize()
@@ -544,7 +641,8 @@ fn put_opaque(state: &mut State, cmd_data: &[u8]) -> ResponseMessage {
 
     PutOpaqueResponse {
         object_id: params.id,
-    }.serialize()
+    }
+    .serialize()
 }
 
 /// Change an HSM auditing setting
@@ -639,7 +737,8 @@ fn storage_status() -> ResponseMessage {
         total_pages: 1024,
         free_pages: 1024,
         page_size: 126,
-    }.serialize()
+    }
+    .serialize()
 }
 
 /// Verify the HMAC tag for the given data
@@ -29,6 +29,8 @@ impl Connection for MockConnection {
         match command.command_type {
             CommandCode::CreateSession => command::create_session(&mut state, &command),
             CommandCode::AuthSession => command::authenticate_session(&mut state, &command),
+            #[cfg(feature = "asymmetric-auth")]
+            CommandCode::GetDevicePubKey => command::get_device_pubkey(&state),
             CommandCode::SessionMessage => command::session_message(&mut state, command),
             unsupported => fail!(ConnectionFailed, "unsupported command: {:?}", unsupported),
         }
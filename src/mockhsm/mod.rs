@@ -41,7 +41,7 @@ impl MockHsm {
 
 impl Connector for MockHsm {
     /// Create a new connection with a clone of the MockHsm state
-    fn connect(&self) -> Result<Box<Connection>, ConnectionError> {
+    fn connect(&self) -> Result<Box<dyn Connection>, ConnectionError> {
         Ok(Box::new(MockConnection::new(self)))
     }
 
@@ -62,8 +62,8 @@ impl Default for MockHsm {
     }
 }
 
-impl Into<Box<Connector>> for MockHsm {
-    fn into(self) -> Box<Connector> {
-        Box::new(self)
+impl From<MockHsm> for Box<dyn Connector> {
+    fn from(connector: MockHsm) -> Box<dyn Connector> {
+        Box::new(connector)
     }
 }
@@ -109,6 +109,11 @@ impl Objects {
         self.0.get(&ObjectHandle::new(object_id, object_type))
     }
 
+    /// Get a mutable reference to an object
+    pub fn get_mut(&mut self, object_id: ObjectId, object_type: ObjectType) -> Option<&mut Object> {
+        self.0.get_mut(&ObjectHandle::new(object_id, object_type))
+    }
+
     /// Put a new object in the MockHsm
     pub fn put(
         &mut self,
@@ -170,7 +175,8 @@ impl Objects {
             WrapAlg::AES128_CCM => SealingKey::new(&AES_128_GCM, wrap_key.payload.as_ref()),
             WrapAlg::AES256_CCM => SealingKey::new(&AES_256_GCM, wrap_key.payload.as_ref()),
             unsupported => bail!("unsupported wrap key algorithm: {:?}", unsupported),
-        }.unwrap();
+        }
+        .unwrap();
 
         let object_to_wrap = match self.get(object_id, object_type) {
             Some(o) => o,
@@ -200,7 +206,8 @@ impl Objects {
         let mut wrapped_object = serialize(&WrappedObject {
             object_info,
             data: object_to_wrap.payload.as_ref().into(),
-        }).unwrap();
+        })
+        .unwrap();
 
         // Make room for the MAC
         wrapped_object.extend_from_slice(&[0u8; WRAPPED_DATA_MAC_SIZE]);
@@ -211,7 +218,8 @@ impl Objects {
             b"",
             &mut wrapped_object,
             WRAPPED_DATA_MAC_SIZE,
-        ).unwrap();
+        )
+        .unwrap();
 
         Ok(wrapped_object)
     }
@@ -228,7 +236,8 @@ impl Objects {
                 WrapAlg::AES128_CCM => OpeningKey::new(&AES_128_GCM, k.payload.as_ref()),
                 WrapAlg::AES256_CCM => OpeningKey::new(&AES_256_GCM, k.payload.as_ref()),
                 unsupported => bail!("unsupported wrap key algorithm: {:?}", unsupported),
-            }.unwrap(),
+            }
+            .unwrap(),
             None => bail!("no such wrap key: {:?}", wrap_key_id),
         };
 
@@ -240,7 +249,8 @@ impl Objects {
             b"",
             0,
             &mut wrapped_data,
-        ).is_err()
+        )
+        .is_err()
         {
             bail!("error decrypting wrapped object!");
         }
@@ -17,6 +17,9 @@ pub(crate) enum Payload {
     /// Authentication keys
     AuthKey(AuthKey),
 
+    /// Asymmetric authentication keys (X and Y coordinates of a P-256 point)
+    AsymmetricAuthKey(Vec<u8>),
+
     /// Ed25519 signing keys
     Ed25519KeyPair([u8; ED25519_SEED_SIZE]),
 
@@ -44,6 +47,10 @@ impl Payload {
             }
             Algorithm::Hmac(alg) => Payload::HmacKey(alg, data.into()),
             Algorithm::Opaque(alg) => Payload::Opaque(alg, data.into()),
+            Algorithm::Auth(AuthAlg::EC_P256_YUBICO) => {
+                assert_eq!(data.len(), AuthAlg::EC_P256_YUBICO.key_len());
+                Payload::AsymmetricAuthKey(data.into())
+            }
             Algorithm::Auth(_) => Payload::AuthKey(AuthKey::from_slice(data).unwrap()),
             _ => panic!("MockHsm does not support putting {:?} objects", algorithm),
         }
@@ -86,6 +93,7 @@ impl Payload {
     pub fn algorithm(&self) -> Algorithm {
         match *self {
             Payload::AuthKey(_) => Algorithm::Auth(AuthAlg::YUBICO_AES),
+            Payload::AsymmetricAuthKey(_) => Algorithm::Auth(AuthAlg::EC_P256_YUBICO),
             Payload::Ed25519KeyPair(_) => Algorithm::Asymmetric(AsymmetricAlg::Ed25519),
             Payload::HmacKey(alg, _) => alg.into(),
             Payload::Opaque(alg, _) => alg.into(),
@@ -97,6 +105,7 @@ impl Payload {
     pub fn len(&self) -> u16 {
         let l = match *self {
             Payload::AuthKey(_) => AUTH_KEY_SIZE,
+            Payload::AsymmetricAuthKey(ref data) => data.len(),
             Payload::Ed25519KeyPair(_) => ED25519_SEED_SIZE,
             Payload::HmacKey(_, ref data) => data.len(),
             Payload::Opaque(_, ref data) => data.len(),
@@ -125,12 +134,22 @@ impl Payload {
             _ => None,
         }
     }
+
+    /// If this payload is an asymmetric auth key, return its public key
+    #[cfg(feature = "asymmetric-auth")]
+    pub fn asymmetric_auth_key(&self) -> Option<&[u8]> {
+        match *self {
+            Payload::AsymmetricAuthKey(ref public_key) => Some(public_key),
+            _ => None,
+        }
+    }
 }
 
 impl AsRef<[u8]> for Payload {
     fn as_ref(&self) -> &[u8] {
         match *self {
             Payload::AuthKey(ref k) => k.0.as_ref(),
+            Payload::AsymmetricAuthKey(ref data) => data,
             Payload::Ed25519KeyPair(ref k) => k.as_ref(),
             Payload::HmacKey(_, ref data) => data,
             Payload::Opaque(_, ref data) => data,
@@ -3,6 +3,7 @@
 use std::fmt::{self, Debug};
 
 use command::CommandMessage;
+use object::ObjectId;
 use response::ResponseMessage;
 use session::{
     securechannel::{Challenge, Cryptogram, SecureChannel},
@@ -14,6 +15,9 @@ pub(crate) struct HsmSession {
     /// ID of the session
     pub id: SessionId,
 
+    /// ID of the auth key the session was created with
+    pub auth_key_id: ObjectId,
+
     /// Card challenge for this session
     pub card_challenge: Challenge,
 
@@ -23,9 +27,15 @@ pub(crate) struct HsmSession {
 
 impl HsmSession {
     /// Create a new session
-    pub fn new(id: SessionId, card_challenge: Challenge, channel: SecureChannel) -> Self {
+    pub fn new(
+        id: SessionId,
+        auth_key_id: ObjectId,
+        card_challenge: Challenge,
+        channel: SecureChannel,
+    ) -> Self {
         Self {
             id,
+            auth_key_id,
             card_challenge,
             channel,
         }
@@ -3,9 +3,13 @@
 
 use std::collections::BTreeMap;
 
+#[cfg(feature = "asymmetric-auth")]
+use asymmetric_auth_key::AsymmetricAuthKey;
 use audit::AuditOption;
 use connector::{ConnectionError, ConnectionErrorKind};
 use object::{ObjectId, ObjectType};
+#[cfg(feature = "asymmetric-auth")]
+use session::{asymmetric, asymmetric::RECEIPT_SIZE, securechannel::CHALLENGE_SIZE};
 use session::{
     securechannel::{Challenge, SecureChannel},
     SessionId,
@@ -28,6 +32,10 @@ pub(crate) struct State {
 
     /// Objects within the MockHsm (i.e. keys)
     pub(super) objects: Objects,
+
+    /// Device key used for asymmetric authentication (kept across resets)
+    #[cfg(feature = "asymmetric-auth")]
+    pub(super) device_key: AsymmetricAuthKey,
 }
 
 impl State {
@@ -38,6 +46,8 @@ impl State {
             force_audit: AuditOption::Off,
             sessions: BTreeMap::new(),
             objects: Objects::default(),
+            #[cfg(feature = "asymmetric-auth")]
+            device_key: AsymmetricAuthKey::generate(),
         }
     }
 
@@ -50,12 +60,7 @@ impl State {
         // Generate a random card challenge to send back to the client
         let card_challenge = Challenge::random();
 
-        let session_id = self
-            .sessions
-            .keys()
-            .max()
-            .map(|id| id.succ().expect("session count exceeded"))
-            .unwrap_or_else(|| SessionId::new(0).unwrap());
+        let session_id = self.next_session_id();
 
         let channel = {
             let auth_key_obj = self
@@ -71,12 +76,43 @@ impl State {
             )
         };
 
-        let session = HsmSession::new(session_id, card_challenge, channel);
+        let session = HsmSession::new(session_id, auth_key_id, card_challenge, channel);
         assert!(self.sessions.insert(session_id, session).is_none());
 
         self.get_session(session_id).unwrap()
     }
 
+    /// Create a new session with the MockHsm using asymmetric authentication,
+    /// returning the session ID along with the card's ephemeral public key
+    /// and receipt, or `None` if there's no asymmetric auth key with the
+    /// given ID
+    #[cfg(feature = "asymmetric-auth")]
+    pub fn create_asymmetric_session(
+        &mut self,
+        auth_key_id: ObjectId,
+        host_public_key: &[u8],
+    ) -> Option<(SessionId, Vec<u8>, [u8; RECEIPT_SIZE])> {
+        let auth_key = self
+            .objects
+            .get(auth_key_id, ObjectType::AuthKey)?
+            .payload
+            .asymmetric_auth_key()?
+            .to_vec();
+
+        let session_id = self.next_session_id();
+
+        let (channel, card_public_key, receipt) =
+            asymmetric::accept_channel(session_id, &self.device_key, &auth_key, host_public_key)
+                .unwrap();
+
+        // Asymmetric sessions don't use challenges
+        let card_challenge = Challenge::from_bytes([0u8; CHALLENGE_SIZE]);
+        let session = HsmSession::new(session_id, auth_key_id, card_challenge, channel);
+        assert!(self.sessions.insert(session_id, session).is_none());
+
+        Some((session_id, card_public_key, receipt))
+    }
+
     /// Obtain the channel for a session by its ID
     pub fn get_session(&mut self, id: SessionId) -> Result<&mut HsmSession, ConnectionError> {
         self.sessions.get_mut(&id).ok_or_else(|| {
@@ -92,6 +128,15 @@ impl State {
         assert!(self.sessions.remove(&id).is_some());
     }
 
+    /// Allocate the ID for a new session
+    fn next_session_id(&self) -> SessionId {
+        self.sessions
+            .keys()
+            .max()
+            .map(|id| id.succ().expect("session count exceeded"))
+            .unwrap_or_else(|| SessionId::new(0).unwrap())
+    }
+
     /// Reset the internal HSM state, closing all connections
     pub fn reset(&mut self) {
         self.command_audit_options = CommandAuditOptions::default();
@@ -64,7 +64,7 @@ impl<'de> Deserialize<'de> for Origin {
             where
                 E: de::Error,
             {
-                Origin::from_u8(value).or_else(|e| Err(E::custom(format!("{}", e))))
+                Origin::from_u8(value).map_err(|e| E::custom(format!("{}", e)))
             }
         }
 
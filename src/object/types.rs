@@ -4,6 +4,7 @@ use serde::{
     ser::{Serialize, Serializer},
 };
 use std::fmt;
+use std::str::FromStr;
 
 /// Types of objects
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -51,6 +52,25 @@ impl Type {
     }
 }
 
+impl FromStr for Type {
+    type Err = Error;
+
+    /// Parse an object type from its name as used by `yubihsm-shell`
+    /// (e.g. `asymmetric-key`)
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s.to_lowercase().replace('_', "-").as_ref() {
+            "opaque" => Type::Opaque,
+            "authentication-key" | "auth-key" => Type::AuthKey,
+            "asymmetric-key" => Type::AsymmetricKey,
+            "wrap-key" => Type::WrapKey,
+            "hmac-key" => Type::HMACKey,
+            "template" => Type::Template,
+            "otp-aead-key" => Type::OTPAEADKey,
+            _ => bail!("invalid object type: {}", s),
+        })
+    }
+}
+
 impl Serialize for Type {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serializer.serialize_u8(self.to_u8())
@@ -69,12 +89,12 @@ impl<'de> Deserialize<'de> for Type {
             }
 
             fn visit_u8<E: de::Error>(self, value: u8) -> Result<Type, E> {
-                Type::from_u8(value).or_else(|e| Err(E::custom(format!("{}", e))))
+                Type::from_u8(value).map_err(|e| E::custom(format!("{}", e)))
             }
 
             fn visit_u64<E: de::Error>(self, value: u64) -> Result<Type, E> {
                 assert!(value < 255);
-                Type::from_u8(value as u8).or_else(|e| Err(E::custom(format!("{}", e))))
+                Type::from_u8(value as u8).map_err(|e| E::custom(format!("{}", e)))
             }
         }
 
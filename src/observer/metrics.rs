@@ -0,0 +1,129 @@
+//! In-memory per-command metrics
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{CommandEvent, ConnectEvent, Observer};
+use command::CommandCode;
+
+/// Upper bounds of the latency histogram buckets in milliseconds. Latencies
+/// above the last bound are only counted in the overall total.
+pub const LATENCY_BUCKETS_MS: &[u64] = &[1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10_000];
+
+/// Metrics for a particular type of command
+#[derive(Clone, Debug, Default)]
+pub struct CommandMetrics {
+    /// Number of times the command was sent
+    pub count: u64,
+
+    /// Number of times the command failed
+    pub errors: u64,
+
+    /// Total bytes sent to the HSM
+    pub bytes_sent: u64,
+
+    /// Total bytes received from the HSM
+    pub bytes_received: u64,
+
+    /// Sum of all latencies
+    pub latency_sum: Duration,
+
+    /// Number of commands whose latency was at most the corresponding
+    /// bound in `LATENCY_BUCKETS_MS` (i.e. cumulative counts)
+    pub latency_buckets: Vec<u64>,
+}
+
+impl CommandMetrics {
+    /// Add a command to these metrics
+    fn record(&mut self, event: &CommandEvent) {
+        if self.latency_buckets.is_empty() {
+            self.latency_buckets = vec![0; LATENCY_BUCKETS_MS.len()];
+        }
+
+        self.count += 1;
+        self.bytes_sent += event.bytes_sent as u64;
+        self.bytes_received += event.bytes_received as u64;
+        self.latency_sum += event.latency;
+
+        if event.error.is_some() {
+            self.errors += 1;
+        }
+
+        let latency_ms = event.latency.as_secs() * 1000
+            + u64::from(event.latency.subsec_nanos()).saturating_add(999_999) / 1_000_000;
+
+        for (bucket, bound) in self.latency_buckets.iter_mut().zip(LATENCY_BUCKETS_MS) {
+            if latency_ms <= *bound {
+                *bucket += 1;
+            }
+        }
+    }
+}
+
+/// Metrics for opening sessions
+#[derive(Clone, Debug, Default)]
+pub struct ConnectMetrics {
+    /// Number of sessions opened successfully
+    pub sessions_opened: u64,
+
+    /// Number of sessions opened after a previous session was lost
+    pub reconnects: u64,
+
+    /// Number of failed attempts to open a session
+    pub failures: u64,
+}
+
+/// `Observer` which keeps counters and latency histograms in memory, which
+/// can be read at any time with `command_metrics` and `connect_metrics`.
+#[derive(Debug, Default)]
+pub struct MetricsObserver {
+    /// Metrics for each type of command
+    commands: Mutex<BTreeMap<CommandCode, CommandMetrics>>,
+
+    /// Metrics for opening sessions
+    connects: Mutex<ConnectMetrics>,
+}
+
+impl MetricsObserver {
+    /// Create a new `MetricsObserver` with all metrics at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a snapshot of the metrics for each type of command sent so far
+    pub fn command_metrics(&self) -> BTreeMap<CommandCode, CommandMetrics> {
+        self.commands.lock().unwrap().clone()
+    }
+
+    /// Get a snapshot of the metrics for opening sessions
+    pub fn connect_metrics(&self) -> ConnectMetrics {
+        self.connects.lock().unwrap().clone()
+    }
+}
+
+impl Observer for MetricsObserver {
+    fn on_command(&self, event: &CommandEvent) {
+        self.commands
+            .lock()
+            .unwrap()
+            .entry(event.command)
+            .or_insert_with(CommandMetrics::default)
+            .record(event);
+    }
+
+    fn on_connect(&self, event: &ConnectEvent) {
+        let mut connects = self.connects.lock().unwrap();
+
+        if event.error.is_some() {
+            connects.failures += 1;
+            return;
+        }
+
+        connects.sessions_opened += 1;
+
+        if event.reconnect {
+            connects.reconnects += 1;
+        }
+    }
+}
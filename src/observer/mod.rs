@@ -0,0 +1,73 @@
+//! Hooks for observing the commands sent to the HSM, e.g. to collect metrics
+//!
+//! An `Observer` can be registered with a `Client` (via `set_observer` or
+//! `ClientBuilder::observer`), after which it's notified about every command
+//! sent over an encrypted session and every attempt to open a session.
+//! `MetricsObserver` is a ready-made implementation which keeps per-command
+//! counters and latency histograms in memory.
+
+mod metrics;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+
+pub use self::metrics::{CommandMetrics, ConnectMetrics, MetricsObserver, LATENCY_BUCKETS_MS};
+
+use std::time::Duration;
+
+use client::ClientErrorKind;
+use command::CommandCode;
+use response::ResponseCode;
+use session::{SessionErrorKind, SessionId};
+
+/// Receives notifications about the commands a `Client` sends to the HSM.
+///
+/// Observers are called synchronously on the thread sending the command, so
+/// they should return quickly and never block on the `Client`.
+pub trait Observer: Send + Sync {
+    /// Called after every command sent over an encrypted session
+    fn on_command(&self, event: &CommandEvent);
+
+    /// Called after every attempt to open a session with the HSM
+    fn on_connect(&self, _event: &ConnectEvent) {}
+}
+
+/// A command sent over an encrypted session
+#[derive(Clone, Debug)]
+pub struct CommandEvent {
+    /// Type of command which was sent
+    pub command: CommandCode,
+
+    /// Session the command was sent over
+    pub session_id: SessionId,
+
+    /// Time from encrypting the command to decrypting its response
+    pub latency: Duration,
+
+    /// Response code from the HSM (if a response was received and decrypted)
+    pub response_code: Option<ResponseCode>,
+
+    /// Number of bytes sent to the HSM (including encryption overhead)
+    pub bytes_sent: usize,
+
+    /// Number of bytes received from the HSM (including encryption overhead)
+    pub bytes_received: usize,
+
+    /// Kind of error the command failed with (if it failed)
+    pub error: Option<SessionErrorKind>,
+}
+
+/// An attempt to open a session with the HSM
+#[derive(Clone, Debug)]
+pub struct ConnectEvent {
+    /// ID of the new session (if it was opened successfully)
+    pub session_id: Option<SessionId>,
+
+    /// Was a session already opened previously by this `Client`?
+    pub reconnect: bool,
+
+    /// Time taken to open (or fail to open) the session
+    pub duration: Duration,
+
+    /// Kind of error opening the session failed with (if it failed)
+    pub error: Option<ClientErrorKind>,
+}
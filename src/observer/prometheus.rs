@@ -0,0 +1,154 @@
+//! Prometheus text exposition format for `MetricsObserver`
+//!
+//! <https://prometheus.io/docs/instrumenting/exposition_formats/>
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use super::{MetricsObserver, LATENCY_BUCKETS_MS};
+use command::CommandCode;
+
+impl MetricsObserver {
+    /// Render the current metrics in the Prometheus text exposition format,
+    /// e.g. for serving from a `/metrics` endpoint
+    pub fn to_prometheus(&self) -> String {
+        let commands = self.command_metrics();
+        let connects = self.connect_metrics();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "yubihsm_commands_total",
+            "counter",
+            "Commands sent to the HSM",
+        );
+        for (code, metrics) in &commands {
+            sample(&mut out, "yubihsm_commands_total", *code, metrics.count);
+        }
+
+        header(
+            &mut out,
+            "yubihsm_command_errors_total",
+            "counter",
+            "Commands which failed",
+        );
+        for (code, metrics) in &commands {
+            sample(
+                &mut out,
+                "yubihsm_command_errors_total",
+                *code,
+                metrics.errors,
+            );
+        }
+
+        header(
+            &mut out,
+            "yubihsm_command_sent_bytes_total",
+            "counter",
+            "Bytes sent to the HSM",
+        );
+        for (code, metrics) in &commands {
+            sample(
+                &mut out,
+                "yubihsm_command_sent_bytes_total",
+                *code,
+                metrics.bytes_sent,
+            );
+        }
+
+        header(
+            &mut out,
+            "yubihsm_command_received_bytes_total",
+            "counter",
+            "Bytes received from the HSM",
+        );
+        for (code, metrics) in &commands {
+            sample(
+                &mut out,
+                "yubihsm_command_received_bytes_total",
+                *code,
+                metrics.bytes_received,
+            );
+        }
+
+        header(
+            &mut out,
+            "yubihsm_command_duration_seconds",
+            "histogram",
+            "Command latency",
+        );
+        for (code, metrics) in &commands {
+            for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(&metrics.latency_buckets) {
+                writeln!(
+                    out,
+                    "yubihsm_command_duration_seconds_bucket{{command=\"{:?}\",le=\"{}\"}} {}",
+                    code,
+                    *bound as f64 / 1000.0,
+                    count
+                )
+                .unwrap();
+            }
+
+            writeln!(
+                out,
+                "yubihsm_command_duration_seconds_bucket{{command=\"{:?}\",le=\"+Inf\"}} {}",
+                code, metrics.count
+            )
+            .unwrap();
+
+            writeln!(
+                out,
+                "yubihsm_command_duration_seconds_sum{{command=\"{:?}\"}} {}",
+                code,
+                as_secs_f64(metrics.latency_sum)
+            )
+            .unwrap();
+
+            sample(
+                &mut out,
+                "yubihsm_command_duration_seconds_count",
+                *code,
+                metrics.count,
+            );
+        }
+
+        for &(name, help, value) in &[
+            (
+                "yubihsm_sessions_opened_total",
+                "Sessions opened with the HSM",
+                connects.sessions_opened,
+            ),
+            (
+                "yubihsm_reconnects_total",
+                "Sessions reopened after a previous session was lost",
+                connects.reconnects,
+            ),
+            (
+                "yubihsm_session_open_failures_total",
+                "Failed attempts to open a session",
+                connects.failures,
+            ),
+        ] {
+            header(&mut out, name, "counter", help);
+            writeln!(out, "{} {}", name, value).unwrap();
+        }
+
+        out
+    }
+}
+
+/// Write the `HELP` and `TYPE` lines for a metric
+fn header(out: &mut String, name: &str, metric_type: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} {}", name, metric_type).unwrap();
+}
+
+/// Write a sample labeled with a command
+fn sample(out: &mut String, name: &str, command: CommandCode, value: u64) {
+    writeln!(out, "{}{{command=\"{:?}\"}} {}", name, command, value).unwrap();
+}
+
+/// Convert a `Duration` to fractional seconds
+fn as_secs_f64(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9
+}
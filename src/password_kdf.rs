@@ -0,0 +1,431 @@
+//! Configurable key derivation for deriving `AuthKey`s from passwords
+//!
+//! `AuthKey::derive_from_password` uses the same parameters as yubihsm-shell
+//! (PBKDF2-SHA256, static salt, 10,000 iterations). Where compatibility with
+//! yubihsm-shell isn't needed, a `PasswordKdf` with a random salt and more
+//! iterations derives much stronger keys.
+//!
+//! For resistance to GPU/ASIC attacks, scrypt (with the `scrypt` cargo
+//! feature) is offered as the memory-hard KDF. Argon2 isn't supported, to
+//! keep the number of KDFs (and dependencies) to review small.
+//!
+//! The parameters can be encoded as a portable `$`-delimited string, with
+//! the KDF, its comma-separated parameters, and a hex-encoded salt, e.g.:
+//!
+//! ```text
+//! $pbkdf2-sha256$i=10000$59756269636f
+//! $scrypt$ln=15,r=8,p=1$0123456789abcdef0123456789abcdef
+//! ```
+
+use hmac::Hmac;
+use pbkdf2::pbkdf2;
+use rand::{OsRng, RngCore};
+#[cfg(feature = "scrypt")]
+use scrypt::{scrypt, ScryptParams};
+use sha2::Sha256;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use auth_key::{AuthKey, AUTH_KEY_SIZE, DEFAULT_PBKDF2_ITERATIONS, DEFAULT_PBKDF2_SALT};
+use error::Error;
+use hex;
+
+/// Size of salts generated by `PasswordKdf::random_salt`
+pub const RANDOM_SALT_SIZE: usize = 16;
+
+/// Identifier for PBKDF2-SHA256 in encoded parameter strings
+const PBKDF2_ID: &str = "pbkdf2-sha256";
+
+/// Identifier for scrypt in encoded parameter strings
+#[cfg(feature = "scrypt")]
+const SCRYPT_ID: &str = "scrypt";
+
+/// Maximum PBKDF2 iterations (a few seconds' work), since parameter strings
+/// may come from untrusted sources
+pub const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// Maximum scrypt `log_n` (i.e. N = 2^20), since parameter strings may come
+/// from untrusted sources
+#[cfg(feature = "scrypt")]
+pub const MAX_SCRYPT_LOG_N: u8 = 20;
+
+/// Maximum scrypt block size (which, with `MAX_SCRYPT_LOG_N`, limits memory
+/// use to 2 GiB)
+#[cfg(feature = "scrypt")]
+pub const MAX_SCRYPT_R: u32 = 16;
+
+/// Maximum scrypt parallelization
+#[cfg(feature = "scrypt")]
+pub const MAX_SCRYPT_P: u32 = 16;
+
+/// Password-based key derivation functions (and their parameters) for
+/// deriving `AuthKey`s
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PasswordKdf {
+    /// PBKDF2 with HMAC-SHA256
+    Pbkdf2 {
+        /// Salt
+        salt: Vec<u8>,
+
+        /// Number of iterations
+        iterations: u32,
+    },
+
+    /// scrypt (memory-hard)
+    #[cfg(feature = "scrypt")]
+    Scrypt {
+        /// Salt
+        salt: Vec<u8>,
+
+        /// Base 2 logarithm of the CPU/memory cost parameter (N)
+        log_n: u8,
+
+        /// Block size parameter
+        r: u32,
+
+        /// Parallelization parameter
+        p: u32,
+    },
+}
+
+impl PasswordKdf {
+    /// Configure PBKDF2-SHA256 with the given salt and number of iterations,
+    /// which mustn't exceed `MAX_PBKDF2_ITERATIONS`
+    pub fn pbkdf2<S: Into<Vec<u8>>>(salt: S, iterations: u32) -> Result<Self, PasswordKdfError> {
+        ensure!(
+            iterations > 0,
+            PasswordKdfErrorKind::ParamsInvalid,
+            "PBKDF2 iterations must be nonzero"
+        );
+
+        ensure!(
+            iterations <= MAX_PBKDF2_ITERATIONS,
+            PasswordKdfErrorKind::ParamsInvalid,
+            "too many PBKDF2 iterations: {} (max: {})",
+            iterations,
+            MAX_PBKDF2_ITERATIONS
+        );
+
+        Ok(PasswordKdf::Pbkdf2 {
+            salt: salt.into(),
+            iterations,
+        })
+    }
+
+    /// Configure scrypt with the given salt and cost parameters, which
+    /// mustn't exceed `MAX_SCRYPT_LOG_N`, `MAX_SCRYPT_R`, and `MAX_SCRYPT_P`
+    #[cfg(feature = "scrypt")]
+    pub fn scrypt<S: Into<Vec<u8>>>(
+        salt: S,
+        log_n: u8,
+        r: u32,
+        p: u32,
+    ) -> Result<Self, PasswordKdfError> {
+        ensure!(
+            log_n <= MAX_SCRYPT_LOG_N && r <= MAX_SCRYPT_R && p <= MAX_SCRYPT_P,
+            PasswordKdfErrorKind::ParamsInvalid,
+            "scrypt parameters too large: ln={}, r={}, p={} (max: ln={}, r={}, p={})",
+            log_n,
+            r,
+            p,
+            MAX_SCRYPT_LOG_N,
+            MAX_SCRYPT_R,
+            MAX_SCRYPT_P
+        );
+
+        ScryptParams::new(log_n, r, p).map_err(|_| {
+            err!(
+                PasswordKdfErrorKind::ParamsInvalid,
+                "invalid scrypt parameters: ln={}, r={}, p={}",
+                log_n,
+                r,
+                p
+            )
+        })?;
+
+        Ok(PasswordKdf::Scrypt {
+            salt: salt.into(),
+            log_n,
+            r,
+            p,
+        })
+    }
+
+    /// Generate a random salt using `OsRng`
+    pub fn random_salt() -> Vec<u8> {
+        let mut rng = OsRng::new().expect("RNG failure!");
+        let mut salt = vec![0u8; RANDOM_SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+        salt
+    }
+
+    /// Borrow the salt
+    pub fn salt(&self) -> &[u8] {
+        match *self {
+            PasswordKdf::Pbkdf2 { ref salt, .. } => salt,
+            #[cfg(feature = "scrypt")]
+            PasswordKdf::Scrypt { ref salt, .. } => salt,
+        }
+    }
+
+    /// Derive an `AuthKey` from the given password
+    pub fn derive(&self, password: &[u8]) -> AuthKey {
+        let mut kdf_output = [0u8; AUTH_KEY_SIZE];
+
+        match *self {
+            PasswordKdf::Pbkdf2 {
+                ref salt,
+                iterations,
+            } => pbkdf2::<Hmac<Sha256>>(password, salt, iterations as usize, &mut kdf_output),
+            #[cfg(feature = "scrypt")]
+            PasswordKdf::Scrypt {
+                ref salt,
+                log_n,
+                r,
+                p,
+            } => {
+                // Parameters are validated when the `PasswordKdf` is created
+                let params = ScryptParams::new(log_n, r, p).unwrap();
+                scrypt(password, salt, &params, &mut kdf_output).unwrap();
+            }
+        }
+
+        AuthKey::new(kdf_output)
+    }
+}
+
+/// PBKDF2 with the static salt and iterations used by yubihsm-shell
+impl Default for PasswordKdf {
+    fn default() -> Self {
+        PasswordKdf::Pbkdf2 {
+            salt: DEFAULT_PBKDF2_SALT.to_vec(),
+            iterations: DEFAULT_PBKDF2_ITERATIONS as u32,
+        }
+    }
+}
+
+impl Display for PasswordKdf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PasswordKdf::Pbkdf2 {
+                ref salt,
+                iterations,
+            } => write!(f, "${}$i={}${}", PBKDF2_ID, iterations, hex::encode(salt)),
+            #[cfg(feature = "scrypt")]
+            PasswordKdf::Scrypt {
+                ref salt,
+                log_n,
+                r,
+                p,
+            } => write!(
+                f,
+                "${}$ln={},r={},p={}${}",
+                SCRYPT_ID,
+                log_n,
+                r,
+                p,
+                hex::encode(salt)
+            ),
+        }
+    }
+}
+
+impl FromStr for PasswordKdf {
+    type Err = PasswordKdfError;
+
+    fn from_str(s: &str) -> Result<Self, PasswordKdfError> {
+        let fields: Vec<&str> = s.split('$').collect();
+
+        ensure!(
+            fields.len() == 4 && fields[0].is_empty(),
+            PasswordKdfErrorKind::FormatInvalid,
+            "expected $<kdf>$<params>$<salt>: {}",
+            s
+        );
+
+        let salt = hex::decode(fields[3]).ok_or_else(|| {
+            err!(
+                PasswordKdfErrorKind::FormatInvalid,
+                "malformed salt: {}",
+                fields[3]
+            )
+        })?;
+
+        let params = parse_params(fields[2])?;
+
+        match fields[1] {
+            PBKDF2_ID => {
+                check_param_names(&params, &["i"])?;
+                Self::pbkdf2(salt, param(&params, "i")?)
+            }
+            #[cfg(feature = "scrypt")]
+            SCRYPT_ID => {
+                check_param_names(&params, &["ln", "r", "p"])?;
+                Self::scrypt(
+                    salt,
+                    param(&params, "ln")?,
+                    param(&params, "r")?,
+                    param(&params, "p")?,
+                )
+            }
+            other => fail!(
+                PasswordKdfErrorKind::FormatInvalid,
+                "unsupported KDF: {}",
+                other
+            ),
+        }
+    }
+}
+
+/// Parse comma-separated `name=value` parameters
+fn parse_params(params: &str) -> Result<Vec<(&str, &str)>, PasswordKdfError> {
+    params
+        .split(',')
+        .map(|param| {
+            let mut parts = param.splitn(2, '=');
+
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) => Ok((name, value)),
+                _ => Err(err!(
+                    PasswordKdfErrorKind::FormatInvalid,
+                    "malformed parameter: {}",
+                    param
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Ensure every parameter is one of the given names, and appears only once
+fn check_param_names(params: &[(&str, &str)], names: &[&str]) -> Result<(), PasswordKdfError> {
+    for (i, &(name, _)) in params.iter().enumerate() {
+        ensure!(
+            names.contains(&name),
+            PasswordKdfErrorKind::FormatInvalid,
+            "unknown parameter: {}",
+            name
+        );
+
+        ensure!(
+            params[..i].iter().all(|&(other, _)| other != name),
+            PasswordKdfErrorKind::FormatInvalid,
+            "duplicate parameter: {}",
+            name
+        );
+    }
+
+    Ok(())
+}
+
+/// Find and parse the parameter with the given name
+fn param<T: FromStr>(params: &[(&str, &str)], name: &str) -> Result<T, PasswordKdfError> {
+    let value = params
+        .iter()
+        .find(|&&(n, _)| n == name)
+        .map(|&(_, value)| value)
+        .ok_or_else(|| {
+            err!(
+                PasswordKdfErrorKind::FormatInvalid,
+                "missing parameter: {}",
+                name
+            )
+        })?;
+
+    value.parse().map_err(|_| {
+        err!(
+            PasswordKdfErrorKind::FormatInvalid,
+            "invalid value for {}: {}",
+            name,
+            value
+        )
+    })
+}
+
+/// `PasswordKdf`-related errors
+pub type PasswordKdfError = Error<PasswordKdfErrorKind>;
+
+/// Kinds of `PasswordKdf`-related errors
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum PasswordKdfErrorKind {
+    /// Encoded parameter string is malformed
+    #[fail(display = "invalid format")]
+    FormatInvalid,
+
+    /// KDF parameters are out of range
+    #[fail(display = "invalid parameters")]
+    ParamsInvalid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matches_derive_from_password() {
+        let password = b"password";
+
+        assert_eq!(
+            PasswordKdf::default().derive(password).as_secret_slice(),
+            AuthKey::derive_from_password(password).as_secret_slice()
+        );
+    }
+
+    #[test]
+    fn pbkdf2_encoding_round_trip() {
+        let kdf = PasswordKdf::default();
+        let encoded = kdf.to_string();
+
+        assert_eq!(encoded, "$pbkdf2-sha256$i=10000$59756269636f");
+        assert_eq!(encoded.parse::<PasswordKdf>().unwrap(), kdf);
+    }
+
+    #[cfg(feature = "scrypt")]
+    #[test]
+    fn scrypt_encoding_round_trip() {
+        let kdf = PasswordKdf::scrypt(vec![0x01, 0x23], 10, 8, 1).unwrap();
+        let encoded = kdf.to_string();
+
+        assert_eq!(encoded, "$scrypt$ln=10,r=8,p=1$0123");
+        assert_eq!(encoded.parse::<PasswordKdf>().unwrap(), kdf);
+    }
+
+    #[test]
+    fn rejects_malformed_strings() {
+        for s in &[
+            "pbkdf2-sha256$i=10000$00",
+            "$pbkdf2-sha256$i=10000",
+            "$pbkdf2-sha256$i=0$00",
+            "$pbkdf2-sha256$iterations=10000$00",
+            "$pbkdf2-sha256$i=10000$zz",
+            "$pbkdf2-sha256$i=1,foo=2$00",
+            "$pbkdf2-sha256$i=1,i=2$00",
+            "$argon2id$m=65536$00",
+        ] {
+            assert!(s.parse::<PasswordKdf>().is_err(), "parsed: {}", s);
+        }
+    }
+
+    #[test]
+    fn rejects_expensive_pbkdf2_params() {
+        for s in &[
+            "$pbkdf2-sha256$i=10000001$00",
+            "$pbkdf2-sha256$i=4294967295$00",
+        ] {
+            let err = s.parse::<PasswordKdf>().err().unwrap();
+            assert_eq!(err.kind(), PasswordKdfErrorKind::ParamsInvalid);
+        }
+    }
+
+    #[cfg(feature = "scrypt")]
+    #[test]
+    fn rejects_expensive_scrypt_params() {
+        for s in &[
+            "$scrypt$ln=21,r=8,p=1$00",
+            "$scrypt$ln=15,r=17,p=1$00",
+            "$scrypt$ln=15,r=8,p=17$00",
+            "$scrypt$ln=15,r=8,p=1,ln=16$00",
+        ] {
+            assert!(s.parse::<PasswordKdf>().is_err(), "parsed: {}", s);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! PEM encoding (RFC 7468)
+
+use base64;
+
+/// Number of base64 characters per line
+const LINE_WIDTH: usize = 64;
+
+/// Encode DER bytes as PEM with the given label (e.g. `CERTIFICATE`)
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let encoded = base64::encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+
+    for line in encoded.as_bytes().chunks(LINE_WIDTH) {
+        // base64 output is always ASCII
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
@@ -0,0 +1,30 @@
+//! Errors that occur while provisioning an HSM
+
+use client::{ClientError, ClientErrorKind};
+use error::Error;
+
+/// Provisioning errors
+pub type ProvisionError = Error<ProvisionErrorKind>;
+
+/// Kinds of provisioning errors
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum ProvisionErrorKind {
+    /// The manifest couldn't be read or describes an invalid object
+    #[fail(display = "invalid manifest")]
+    ManifestInvalid,
+
+    /// Errors communicating with the HSM
+    #[fail(display = "client error: {}", kind)]
+    ClientError {
+        /// Client error kind
+        kind: ClientErrorKind,
+    },
+}
+
+// TODO: capture causes?
+impl From<ClientError> for ProvisionError {
+    fn from(err: ClientError) -> Self {
+        let kind = ProvisionErrorKind::ClientError { kind: err.kind() };
+        Error::new(kind, err.message().map(str::to_owned))
+    }
+}
@@ -0,0 +1,394 @@
+//! Manifests describing the desired contents of an HSM
+
+use std::collections::BTreeSet;
+use std::fmt;
+#[cfg(feature = "toml")]
+use std::fs::File;
+#[cfg(feature = "toml")]
+use std::io::Read;
+#[cfg(feature = "toml")]
+use std::path::Path;
+#[cfg(feature = "toml")]
+use toml;
+
+use super::{ProvisionError, ProvisionErrorKind::ManifestInvalid};
+#[cfg(feature = "passwords")]
+use auth_key::AuthKey;
+use hex;
+use {
+    Algorithm, AsymmetricAlg, AuthAlg, Capability, Domain, HmacAlg, ObjectId, ObjectLabel,
+    ObjectType, WrapAlg,
+};
+
+/// Desired contents of an HSM.
+///
+/// Manifests are usually loaded from TOML (with the `toml` cargo feature),
+/// with one array of tables per type of object:
+///
+/// ```toml
+/// [[auth_key]]
+/// id = 2
+/// label = "signer"
+/// domains = "1"
+/// capabilities = "sign-eddsa"
+/// password = "correct horse battery staple"
+///
+/// [[asymmetric_key]]
+/// id = 100
+/// label = "signing key"
+/// domains = "1"
+/// capabilities = "sign-eddsa,exportable-under-wrap"
+/// algorithm = "ed25519"
+/// ```
+///
+/// Domains are given as a comma-separated list of domain numbers (or `all`),
+/// capabilities as a comma-separated list of capability names (as used by
+/// `yubihsm-shell`), and key material (if any) as hex. Keys without key
+/// material are generated on the HSM.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Delete objects which aren't in the manifest (auth keys are never deleted)
+    #[serde(default)]
+    pub prune: bool,
+
+    /// Delete and recreate objects whose algorithm, domains, or capabilities
+    /// differ from the manifest, destroying the existing keys. Objects which
+    /// only differ in their label, and the auth key used to apply the plan,
+    /// are never replaced.
+    #[serde(default)]
+    pub allow_replace: bool,
+
+    /// Auth keys
+    #[serde(default, rename = "auth_key")]
+    pub auth_keys: Vec<ManifestEntry>,
+
+    /// Wrap keys
+    #[serde(default, rename = "wrap_key")]
+    pub wrap_keys: Vec<ManifestEntry>,
+
+    /// Asymmetric keys
+    #[serde(default, rename = "asymmetric_key")]
+    pub asymmetric_keys: Vec<ManifestEntry>,
+
+    /// HMAC keys
+    #[serde(default, rename = "hmac_key")]
+    pub hmac_keys: Vec<ManifestEntry>,
+}
+
+/// An object in a `Manifest`, as written in the manifest file
+#[derive(Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestEntry {
+    /// Object ID
+    pub id: ObjectId,
+
+    /// Object label
+    #[serde(default)]
+    pub label: String,
+
+    /// Comma-separated domain numbers, or `all`
+    pub domains: String,
+
+    /// Comma-separated capability names
+    pub capabilities: String,
+
+    /// Comma-separated capability names which objects imported/exported with
+    /// this key may have (auth and wrap keys only)
+    #[serde(default)]
+    pub delegated_capabilities: Option<String>,
+
+    /// Algorithm name (defaults to `yubico-aes-auth`, `aes256-ccm-wrap`, and
+    /// `hmac-sha256` for auth, wrap, and HMAC keys respectively)
+    #[serde(default)]
+    pub algorithm: Option<String>,
+
+    /// Hex-encoded key material to import instead of generating a key
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// Password to derive an auth key from
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+// Don't leak key material or passwords into logs
+impl fmt::Debug for ManifestEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ManifestEntry")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .field("domains", &self.domains)
+            .field("capabilities", &self.capabilities)
+            .field("delegated_capabilities", &self.delegated_capabilities)
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+/// Where the key material for an object comes from
+#[derive(Clone)]
+pub enum KeyMaterial {
+    /// Generate the key on the HSM
+    Generate,
+
+    /// Import the given key
+    Import(Vec<u8>),
+}
+
+impl fmt::Debug for KeyMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KeyMaterial::Generate => write!(f, "Generate"),
+            KeyMaterial::Import(_) => write!(f, "Import(...)"),
+        }
+    }
+}
+
+/// A validated object from a `Manifest`
+#[derive(Clone, Debug)]
+pub struct ObjectSpec {
+    /// Object ID
+    pub object_id: ObjectId,
+
+    /// Type of object
+    pub object_type: ObjectType,
+
+    /// Object label
+    pub label: ObjectLabel,
+
+    /// Domains the object is in
+    pub domains: Domain,
+
+    /// Capabilities of the object
+    pub capabilities: Capability,
+
+    /// Delegated capabilities of the object
+    pub delegated_capabilities: Capability,
+
+    /// Algorithm of the object
+    pub algorithm: Algorithm,
+
+    /// Key material for the object
+    pub key: KeyMaterial,
+}
+
+impl Manifest {
+    /// Parse a manifest from a TOML string
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(manifest: &str) -> Result<Self, ProvisionError> {
+        toml::from_str(manifest).map_err(|e| err!(ManifestInvalid, "{}", e))
+    }
+
+    /// Load a manifest from a TOML file
+    #[cfg(feature = "toml")]
+    pub fn load_toml<P: AsRef<Path>>(path: P) -> Result<Self, ProvisionError> {
+        let path = path.as_ref();
+        let mut manifest = String::new();
+
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut manifest))
+            .map_err(|e| err!(ManifestInvalid, "couldn't read {}: {}", path.display(), e))?;
+
+        Self::from_toml_str(&manifest)
+    }
+
+    /// Validate the manifest, returning the objects it describes
+    pub fn objects(&self) -> Result<Vec<ObjectSpec>, ProvisionError> {
+        let mut objects = vec![];
+        let mut seen = BTreeSet::new();
+
+        for &(object_type, entries) in &[
+            (ObjectType::AuthKey, &self.auth_keys),
+            (ObjectType::WrapKey, &self.wrap_keys),
+            (ObjectType::AsymmetricKey, &self.asymmetric_keys),
+            (ObjectType::HMACKey, &self.hmac_keys),
+        ] {
+            for entry in entries.iter() {
+                let spec = entry.to_spec(object_type)?;
+
+                ensure!(
+                    seen.insert((spec.object_type, spec.object_id)),
+                    ManifestInvalid,
+                    "duplicate {:?} 0x{:04x}",
+                    spec.object_type,
+                    spec.object_id
+                );
+
+                objects.push(spec);
+            }
+        }
+
+        Ok(objects)
+    }
+}
+
+impl ManifestEntry {
+    /// Validate this entry as an object of the given type
+    fn to_spec(&self, object_type: ObjectType) -> Result<ObjectSpec, ProvisionError> {
+        let context = format!("{:?} 0x{:04x}", object_type, self.id);
+
+        ensure!(
+            self.id != 0,
+            ManifestInvalid,
+            "{}: object IDs must be given explicitly",
+            context
+        );
+
+        let label = ObjectLabel::new(self.label.as_bytes())
+            .map_err(|e| err!(ManifestInvalid, "{}: {}", context, e))?;
+
+        let domains = self
+            .domains
+            .parse()
+            .map_err(|e| err!(ManifestInvalid, "{}: {}", context, e))?;
+
+        let capabilities = parse_capabilities(&self.capabilities, &context)?;
+
+        let delegated_capabilities = match self.delegated_capabilities {
+            Some(ref delegated) => {
+                ensure!(
+                    object_type == ObjectType::AuthKey || object_type == ObjectType::WrapKey,
+                    ManifestInvalid,
+                    "{}: only auth and wrap keys have delegated capabilities",
+                    context
+                );
+
+                parse_capabilities(delegated, &context)?
+            }
+            None => Capability::empty(),
+        };
+
+        let algorithm = self.algorithm(object_type, &context)?;
+        let key = self.key_material(object_type, &context)?;
+
+        if let KeyMaterial::Import(ref bytes) = key {
+            let expected_len = match algorithm {
+                Algorithm::Asymmetric(alg) => Some(alg.key_len()),
+                Algorithm::Auth(alg) => Some(alg.key_len()),
+                Algorithm::Wrap(alg) => Some(alg.key_len()),
+                _ => None,
+            };
+
+            if let Some(expected_len) = expected_len {
+                ensure!(
+                    bytes.len() == expected_len,
+                    ManifestInvalid,
+                    "{}: key must be {} bytes (got {})",
+                    context,
+                    expected_len,
+                    bytes.len()
+                );
+            }
+        }
+
+        Ok(ObjectSpec {
+            object_id: self.id,
+            object_type,
+            label,
+            domains,
+            capabilities,
+            delegated_capabilities,
+            algorithm,
+            key,
+        })
+    }
+
+    /// Parse the algorithm, or pick the default for the object type
+    fn algorithm(
+        &self,
+        object_type: ObjectType,
+        context: &str,
+    ) -> Result<Algorithm, ProvisionError> {
+        let name = self.algorithm.as_deref();
+
+        let result = match object_type {
+            ObjectType::AuthKey => match name {
+                None | Some("yubico-aes-auth") => Ok(AuthAlg::YUBICO_AES.into()),
+                Some(other) => fail!(
+                    ManifestInvalid,
+                    "{}: invalid auth algorithm: {}",
+                    context,
+                    other
+                ),
+            },
+            ObjectType::WrapKey => name
+                .unwrap_or("aes256-ccm-wrap")
+                .parse::<WrapAlg>()
+                .map(Algorithm::from),
+            ObjectType::HMACKey => name
+                .unwrap_or("hmac-sha256")
+                .parse::<HmacAlg>()
+                .map(Algorithm::from),
+            ObjectType::AsymmetricKey => match name {
+                Some(name) => name.parse::<AsymmetricAlg>().map(Algorithm::from),
+                None => fail!(ManifestInvalid, "{}: missing algorithm", context),
+            },
+            other => fail!(ManifestInvalid, "unsupported object type: {:?}", other),
+        };
+
+        result.map_err(|e| err!(ManifestInvalid, "{}: {}", context, e))
+    }
+
+    /// Decode the key material (or password) for this entry
+    fn key_material(
+        &self,
+        object_type: ObjectType,
+        context: &str,
+    ) -> Result<KeyMaterial, ProvisionError> {
+        match (&self.key, &self.password) {
+            (&Some(_), &Some(_)) => fail!(
+                ManifestInvalid,
+                "{}: only one of key or password may be given",
+                context
+            ),
+            (&Some(ref key), &None) => hex::decode(key)
+                .map(KeyMaterial::Import)
+                .ok_or_else(|| err!(ManifestInvalid, "{}: key isn't valid hex", context)),
+            (&None, &Some(ref password)) => {
+                ensure!(
+                    object_type == ObjectType::AuthKey,
+                    ManifestInvalid,
+                    "{}: only auth keys can be derived from passwords",
+                    context
+                );
+
+                derive_auth_key(password, context)
+            }
+            (&None, &None) => {
+                ensure!(
+                    object_type != ObjectType::AuthKey,
+                    ManifestInvalid,
+                    "{}: auth keys need a key or password",
+                    context
+                );
+
+                Ok(KeyMaterial::Generate)
+            }
+        }
+    }
+}
+
+/// Parse a comma-separated list of capabilities
+fn parse_capabilities(capabilities: &str, context: &str) -> Result<Capability, ProvisionError> {
+    capabilities
+        .parse()
+        .map_err(|e| err!(ManifestInvalid, "{}: {}", context, e))
+}
+
+/// Derive an auth key from a password
+#[cfg(feature = "passwords")]
+fn derive_auth_key(password: &str, _context: &str) -> Result<KeyMaterial, ProvisionError> {
+    let auth_key = AuthKey::derive_from_password(password.as_bytes());
+    Ok(KeyMaterial::Import(auth_key.as_secret_slice().to_vec()))
+}
+
+/// Deriving auth keys from passwords requires the `passwords` feature
+#[cfg(not(feature = "passwords"))]
+fn derive_auth_key(_password: &str, context: &str) -> Result<KeyMaterial, ProvisionError> {
+    fail!(
+        ManifestInvalid,
+        "{}: passwords require the `passwords` cargo feature",
+        context
+    );
+}
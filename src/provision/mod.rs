@@ -0,0 +1,20 @@
+//! Declarative provisioning: bring an HSM in line with a `Manifest`
+//! describing the auth keys, wrap keys, asymmetric keys, and HMAC keys it
+//! should contain.
+//!
+//! Provisioning happens in two steps, so changes can be reviewed first:
+//!
+//! - `Plan::new` compares the manifest against the objects in the HSM and
+//!   works out which objects need to be created, replaced, or deleted.
+//!   Existing objects are only replaced if the manifest sets `allow_replace`;
+//!   otherwise differences are reported as drift.
+//! - `Plan::apply` makes those changes with the `generate_*`, `put_*`, and
+//!   `delete_object` commands
+
+mod error;
+mod manifest;
+mod plan;
+
+pub use self::error::{ProvisionError, ProvisionErrorKind};
+pub use self::manifest::{KeyMaterial, Manifest, ManifestEntry, ObjectSpec};
+pub use self::plan::{Change, Plan};
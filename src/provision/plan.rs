@@ -0,0 +1,314 @@
+//! Plans for bringing an HSM in line with a `Manifest`
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::{
+    KeyMaterial, Manifest, ObjectSpec, ProvisionError, ProvisionErrorKind::ManifestInvalid,
+};
+use auth_key::AuthKey;
+use {Algorithm, Client, ObjectId, ObjectInfo, ObjectType};
+
+/// A change to make to the HSM
+#[derive(Clone, Debug)]
+pub enum Change {
+    /// Create an object which doesn't exist yet
+    Create(ObjectSpec),
+
+    /// Delete an existing object with differing attributes and recreate it
+    /// (only if `allow_replace` is set). Note that this destroys the existing
+    /// key!
+    Replace {
+        /// Desired object
+        spec: ObjectSpec,
+
+        /// Names of the attributes which differ
+        differences: Vec<&'static str>,
+    },
+
+    /// Delete an object which isn't in the manifest (if `prune` is set)
+    Delete {
+        /// ID of the object to delete
+        object_id: ObjectId,
+
+        /// Type of the object to delete
+        object_type: ObjectType,
+    },
+}
+
+/// Changes needed to bring an HSM in line with a `Manifest`, computed by
+/// comparing the manifest against `list_objects` and `get_object_info`.
+///
+/// Display a plan (e.g. with `println!("{}", plan)`) to review it before
+/// calling `apply`.
+#[derive(Clone, Debug, Default)]
+pub struct Plan {
+    /// Changes to make, in order
+    changes: Vec<Change>,
+
+    /// Objects which already match the manifest
+    unchanged: Vec<(ObjectType, ObjectId)>,
+
+    /// Objects which differ from the manifest but won't be replaced, along
+    /// with the names of the attributes which differ
+    drifted: Vec<(ObjectType, ObjectId, Vec<&'static str>)>,
+}
+
+impl Plan {
+    /// Compare the manifest against the objects in the HSM
+    pub fn new(client: &mut Client, manifest: &Manifest) -> Result<Self, ProvisionError> {
+        let specs = manifest.objects()?;
+
+        let existing: BTreeSet<(ObjectType, ObjectId)> = client
+            .list_objects()?
+            .iter()
+            .map(|entry| (entry.object_type, entry.object_id))
+            .collect();
+
+        let session_auth_key_id = client.session()?.auth_key_id();
+        let mut plan = Self::default();
+
+        if manifest.prune {
+            let desired: BTreeSet<_> = specs
+                .iter()
+                .map(|spec| (spec.object_type, spec.object_id))
+                .collect();
+
+            for &(object_type, object_id) in existing.difference(&desired) {
+                // Never delete auth keys, so we can't lock ourselves out
+                if object_type != ObjectType::AuthKey {
+                    plan.changes.push(Change::Delete {
+                        object_id,
+                        object_type,
+                    });
+                }
+            }
+        }
+
+        for spec in specs {
+            if !existing.contains(&(spec.object_type, spec.object_id)) {
+                plan.changes.push(Change::Create(spec));
+                continue;
+            }
+
+            let info = client.get_object_info(spec.object_id, spec.object_type)?;
+            let differences = differences(&spec, &info);
+
+            // Labels can't be changed without recreating the object, which
+            // isn't worth destroying a key over. Never replace the auth key
+            // we're authenticated with either, so we can't lock ourselves out.
+            let replaceable = manifest.allow_replace
+                && differences.iter().any(|&difference| difference != "label")
+                && !(spec.object_type == ObjectType::AuthKey
+                    && spec.object_id == session_auth_key_id);
+
+            if differences.is_empty() {
+                plan.unchanged.push((spec.object_type, spec.object_id));
+            } else if replaceable {
+                plan.changes.push(Change::Replace { spec, differences });
+            } else {
+                plan.drifted
+                    .push((spec.object_type, spec.object_id, differences));
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Changes this plan would make
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Objects which already match the manifest
+    pub fn unchanged(&self) -> &[(ObjectType, ObjectId)] {
+        &self.unchanged
+    }
+
+    /// Objects which differ from the manifest but won't be replaced, along
+    /// with the names of the attributes which differ
+    pub fn drifted(&self) -> &[(ObjectType, ObjectId, Vec<&'static str>)] {
+        &self.drifted
+    }
+
+    /// Does this plan delete and recreate existing objects?
+    pub fn has_replacements(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| matches!(*change, Change::Replace { .. }))
+    }
+
+    /// Are there no changes to make? (Objects may still have drifted)
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Make the planned changes, stopping at the first error
+    pub fn apply(&self, client: &mut Client) -> Result<(), ProvisionError> {
+        for change in &self.changes {
+            match *change {
+                Change::Create(ref spec) => create(client, spec)?,
+                Change::Replace { ref spec, .. } => {
+                    client.delete_object(spec.object_id, spec.object_type)?;
+                    create(client, spec)?;
+                }
+                Change::Delete {
+                    object_id,
+                    object_type,
+                } => client.delete_object(object_id, object_type)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(object_type, object_id) in &self.unchanged {
+            writeln!(f, "  {:?} 0x{:04x}: unchanged", object_type, object_id)?;
+        }
+
+        for &(object_type, object_id, ref differences) in &self.drifted {
+            writeln!(
+                f,
+                "~ {:?} 0x{:04x}: differs in {} (not replaced)",
+                object_type,
+                object_id,
+                differences.join(", ")
+            )?;
+        }
+
+        for change in &self.changes {
+            match *change {
+                Change::Create(ref spec) => writeln!(
+                    f,
+                    "+ {:?} 0x{:04x}: create {:?} ({:?})",
+                    spec.object_type, spec.object_id, spec.label, spec.algorithm
+                )?,
+                Change::Replace {
+                    ref spec,
+                    ref differences,
+                } => writeln!(
+                    f,
+                    "! {:?} 0x{:04x}: replace (differs in {})",
+                    spec.object_type,
+                    spec.object_id,
+                    differences.join(", ")
+                )?,
+                Change::Delete {
+                    object_id,
+                    object_type,
+                } => writeln!(f, "- {:?} 0x{:04x}: delete", object_type, object_id)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the attributes of an existing object which differ from the manifest
+fn differences(spec: &ObjectSpec, info: &ObjectInfo) -> Vec<&'static str> {
+    let mut differences = vec![];
+
+    if spec.label != info.label {
+        differences.push("label");
+    }
+
+    if spec.domains != info.domains {
+        differences.push("domains");
+    }
+
+    if spec.capabilities != info.capabilities {
+        differences.push("capabilities");
+    }
+
+    if spec.delegated_capabilities != info.delegated_capabilities {
+        differences.push("delegated_capabilities");
+    }
+
+    if spec.algorithm != info.algorithm {
+        differences.push("algorithm");
+    }
+
+    differences
+}
+
+/// Create an object with the `generate_*`/`put_*` command for its type
+fn create(client: &mut Client, spec: &ObjectSpec) -> Result<(), ProvisionError> {
+    let id = spec.object_id;
+    let label = spec.label.clone();
+
+    match (spec.algorithm, &spec.key) {
+        (Algorithm::Auth(alg), &KeyMaterial::Import(ref key)) => {
+            let auth_key = AuthKey::from_slice(key).map_err(|e| err!(ManifestInvalid, "{}", e))?;
+
+            client.put_auth_key(
+                id,
+                label,
+                spec.domains,
+                spec.capabilities,
+                spec.delegated_capabilities,
+                alg,
+                auth_key,
+            )?;
+        }
+        (Algorithm::Wrap(alg), &KeyMaterial::Generate) => {
+            client.generate_wrap_key(
+                id,
+                label,
+                spec.domains,
+                spec.capabilities,
+                spec.delegated_capabilities,
+                alg,
+            )?;
+        }
+        (Algorithm::Wrap(alg), &KeyMaterial::Import(ref key)) => {
+            client.put_wrap_key(
+                id,
+                label,
+                spec.domains,
+                spec.capabilities,
+                spec.delegated_capabilities,
+                alg,
+                key.as_slice(),
+            )?;
+        }
+        (Algorithm::Asymmetric(alg), &KeyMaterial::Generate) => {
+            client.generate_asymmetric_key(id, label, spec.domains, spec.capabilities, alg)?;
+        }
+        (Algorithm::Asymmetric(alg), &KeyMaterial::Import(ref key)) => {
+            client.put_asymmetric_key(
+                id,
+                label,
+                spec.domains,
+                spec.capabilities,
+                alg,
+                key.as_slice(),
+            )?;
+        }
+        (Algorithm::Hmac(alg), &KeyMaterial::Generate) => {
+            client.generate_hmac_key(id, label, spec.domains, spec.capabilities, alg)?;
+        }
+        (Algorithm::Hmac(alg), &KeyMaterial::Import(ref key)) => {
+            client.put_hmac_key(
+                id,
+                label,
+                spec.domains,
+                spec.capabilities,
+                alg,
+                key.as_slice(),
+            )?;
+        }
+        (algorithm, key) => fail!(
+            ManifestInvalid,
+            "{:?} 0x{:04x}: can't create object with {:?} from {:?}",
+            spec.object_type,
+            id,
+            algorithm,
+            key
+        ),
+    }
+
+    Ok(())
+}
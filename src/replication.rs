@@ -0,0 +1,229 @@
+//! Replicate keys from one HSM to another (e.g. to keep identical signing
+//! keys on several YubiHSM2s) by exporting them under a wrap key which both
+//! HSMs share, and check the copies match the originals afterward.
+
+use std::collections::BTreeSet;
+
+use {Client, ClientError, ObjectId, ObjectInfo, ObjectOrigin, ObjectType};
+
+/// An object which couldn't be replicated
+#[derive(Clone, Debug)]
+pub struct ReplicationFailure {
+    /// Type of the object
+    pub object_type: ObjectType,
+
+    /// ID of the object
+    pub object_id: ObjectId,
+
+    /// Why the object couldn't be replicated
+    pub reason: String,
+}
+
+/// Result of `replicate`
+#[derive(Clone, Debug, Default)]
+pub struct ReplicationReport {
+    /// Objects which were copied to the destination and verified
+    pub replicated: Vec<(ObjectType, ObjectId)>,
+
+    /// Objects which already existed on the destination with the same
+    /// sequence number
+    pub skipped: Vec<(ObjectType, ObjectId)>,
+
+    /// Objects which couldn't be copied, or whose copies didn't match
+    pub failed: Vec<ReplicationFailure>,
+}
+
+impl ReplicationReport {
+    /// Were all of the selected objects replicated (or already present)?
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Copy the selected objects from `source` to `destination`, exporting them
+/// under the wrap key with the given ID (which must exist on both HSMs).
+///
+/// Each copy is checked against the original by comparing `ObjectInfo`
+/// (whose origin should become `WrappedGenerated`/`WrappedImported`), and
+/// for asymmetric keys, the public key. Copies which don't match are deleted
+/// from the destination again. Objects which already exist on the
+/// destination with the same sequence number are checked the same way, and
+/// skipped if they match.
+///
+/// Errors replicating individual objects are recorded in the report's
+/// `failed` list. Only errors listing the objects on the destination are
+/// returned.
+pub fn replicate(
+    source: &mut Client,
+    destination: &mut Client,
+    wrap_key_id: ObjectId,
+    objects: &[(ObjectType, ObjectId)],
+) -> Result<ReplicationReport, ClientError> {
+    let existing: BTreeSet<(ObjectType, ObjectId)> = destination
+        .list_objects()?
+        .iter()
+        .map(|entry| (entry.object_type, entry.object_id))
+        .collect();
+
+    let mut report = ReplicationReport::default();
+
+    for &(object_type, object_id) in objects {
+        let exists = existing.contains(&(object_type, object_id));
+
+        match replicate_object(
+            source,
+            destination,
+            wrap_key_id,
+            object_type,
+            object_id,
+            exists,
+        ) {
+            Ok(true) => report.replicated.push((object_type, object_id)),
+            Ok(false) => report.skipped.push((object_type, object_id)),
+            Err(reason) => report.failed.push(ReplicationFailure {
+                object_type,
+                object_id,
+                reason,
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Replicate a single object, returning whether it was copied (as opposed
+/// to already being present on the destination)
+fn replicate_object(
+    source: &mut Client,
+    destination: &mut Client,
+    wrap_key_id: ObjectId,
+    object_type: ObjectType,
+    object_id: ObjectId,
+    exists: bool,
+) -> Result<bool, String> {
+    let original = source
+        .get_object_info(object_id, object_type)
+        .map_err(|e| format!("couldn't get object info from source: {}", e))?;
+
+    if exists {
+        let info = destination
+            .get_object_info(object_id, object_type)
+            .map_err(|e| format!("couldn't get object info from destination: {}", e))?;
+
+        if info.sequence != original.sequence {
+            return Err(format!(
+                "already exists on destination with sequence {} (source has {})",
+                info.sequence, original.sequence
+            ));
+        }
+
+        // Check copies left over from earlier runs before skipping them
+        verify_copy(source, destination, &original, &info)?;
+        return Ok(false);
+    }
+
+    let wrapped = source
+        .export_wrapped(wrap_key_id, object_type, object_id)
+        .map_err(|e| format!("export failed: {}", e))?;
+
+    let imported = destination
+        .import_wrapped(wrap_key_id, wrapped)
+        .map_err(|e| format!("import failed: {}", e))?;
+
+    if imported.object_type != object_type || imported.object_id != object_id {
+        return Err(format!(
+            "imported unexpected object: {:?} 0x{:04x}",
+            imported.object_type, imported.object_id
+        ));
+    }
+
+    let result = destination
+        .get_object_info(object_id, object_type)
+        .map_err(|e| format!("couldn't get object info from destination: {}", e))
+        .and_then(|copy| verify_copy(source, destination, &original, &copy));
+
+    // Don't leave copies which failed verification on the destination
+    if let Err(reason) = result {
+        return match destination.delete_object(object_id, object_type) {
+            Ok(()) => Err(format!("{} (deleted copy)", reason)),
+            Err(e) => Err(format!("{} (couldn't delete copy: {})", reason, e)),
+        };
+    }
+
+    Ok(true)
+}
+
+/// Check a copy on the destination matches the original on the source
+fn verify_copy(
+    source: &mut Client,
+    destination: &mut Client,
+    original: &ObjectInfo,
+    copy: &ObjectInfo,
+) -> Result<(), String> {
+    let differences = differences(original, copy);
+
+    if !differences.is_empty() {
+        return Err(format!("copy differs in {}", differences.join(", ")));
+    }
+
+    if original.object_type == ObjectType::AsymmetricKey {
+        let source_pubkey = source
+            .get_pubkey(original.object_id)
+            .map_err(|e| format!("couldn't get public key from source: {}", e))?;
+
+        let destination_pubkey = destination
+            .get_pubkey(original.object_id)
+            .map_err(|e| format!("couldn't get public key from destination: {}", e))?;
+
+        if source_pubkey.bytes != destination_pubkey.bytes {
+            return Err("public keys differ".to_owned());
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the attributes of a copied object which differ from the original
+fn differences(original: &ObjectInfo, copy: &ObjectInfo) -> Vec<&'static str> {
+    let mut differences = vec![];
+
+    if copy.label != original.label {
+        differences.push("label");
+    }
+
+    if copy.domains != original.domains {
+        differences.push("domains");
+    }
+
+    if copy.capabilities != original.capabilities {
+        differences.push("capabilities");
+    }
+
+    if copy.delegated_capabilities != original.delegated_capabilities {
+        differences.push("delegated_capabilities");
+    }
+
+    if copy.algorithm != original.algorithm {
+        differences.push("algorithm");
+    }
+
+    if copy.length != original.length {
+        differences.push("length");
+    }
+
+    if copy.sequence != original.sequence {
+        differences.push("sequence");
+    }
+
+    let expected_origin = match original.origin {
+        ObjectOrigin::Generated => ObjectOrigin::WrappedGenerated,
+        ObjectOrigin::Imported => ObjectOrigin::WrappedImported,
+        origin => origin,
+    };
+
+    if copy.origin != expected_origin {
+        differences.push("origin");
+    }
+
+    differences
+}
@@ -106,7 +106,7 @@ impl ResponseCode {
         let code = i16::from(byte).checked_sub(0x80).unwrap() as i8;
 
         Ok(match code {
-            0...0x7F => ResponseCode::Success(CommandCode::from_u8(code as u8)?),
+            0..=0x7F => ResponseCode::Success(CommandCode::from_u8(code as u8)?),
             -1 => ResponseCode::MemoryError,
             -2 => ResponseCode::InitError,
             -3 => ResponseCode::NetError,
@@ -180,10 +180,7 @@ impl ResponseCode {
 
     /// Is this a successful response?
     pub fn is_success(self) -> bool {
-        match self {
-            ResponseCode::Success(_) => true,
-            _ => false,
-        }
+        matches!(self, ResponseCode::Success(_))
     }
 
     /// Is this an error response?
@@ -210,6 +207,6 @@ impl<'de> Deserialize<'de> for ResponseCode {
 
         ResponseCode::from_u8(value)
             .or_else(|_| ResponseCode::from_u8(ResponseCode::DeviceOK.to_u8() - value))
-            .or_else(|e| Err(D::Error::custom(format!("{}", e))))
+            .map_err(|e| D::Error::custom(format!("{}", e)))
     }
 }
@@ -134,10 +134,7 @@ impl ResponseMessage {
 
     /// Did an error occur?
     pub fn is_err(&self) -> bool {
-        match self.code {
-            ResponseCode::Success(_) => false,
-            _ => true,
-        }
+        !matches!(self.code, ResponseCode::Success(_))
     }
 
     /// Get the command being responded to
@@ -172,20 +169,22 @@ impl From<HsmErrorKind> for ResponseMessage {
 }
 
 #[cfg(feature = "mockhsm")]
-impl Into<Vec<u8>> for ResponseMessage {
+impl From<ResponseMessage> for Vec<u8> {
     /// Serialize this response, consuming it and producing a Vec<u8>
-    fn into(mut self) -> Vec<u8> {
-        let mut result = Vec::with_capacity(3 + self.len());
-        result.push(self.code.to_u8());
-        result.write_u16::<BigEndian>(self.len() as u16).unwrap();
+    fn from(mut response: ResponseMessage) -> Vec<u8> {
+        let mut result = Vec::with_capacity(3 + response.len());
+        result.push(response.code.to_u8());
+        result
+            .write_u16::<BigEndian>(response.len() as u16)
+            .unwrap();
 
-        if let Some(session_id) = self.session_id {
+        if let Some(session_id) = response.session_id {
             result.push(session_id.to_u8());
         }
 
-        result.append(&mut self.data);
+        result.append(&mut response.data);
 
-        if let Some(mac) = self.mac {
+        if let Some(mac) = response.mac {
             result.extend_from_slice(mac.as_slice());
         }
 
@@ -195,22 +194,14 @@ impl Into<Vec<u8>> for ResponseMessage {
 
 /// Do responses with the given code include a session ID?
 fn has_session_id(code: ResponseCode) -> bool {
-    match code {
-        ResponseCode::Success(cmd_type) => match cmd_type {
-            CommandCode::CreateSession | CommandCode::SessionMessage => true,
-            _ => false,
-        },
-        _ => false,
-    }
+    matches!(
+        code,
+        ResponseCode::Success(CommandCode::CreateSession)
+            | ResponseCode::Success(CommandCode::SessionMessage)
+    )
 }
 
 /// Do responses with the given code have a Response-MAC (R-MAC) value?
 fn has_rmac(code: ResponseCode) -> bool {
-    match code {
-        ResponseCode::Success(cmd_type) => match cmd_type {
-            CommandCode::SessionMessage => true,
-            _ => false,
-        },
-        _ => false,
-    }
+    matches!(code, ResponseCode::Success(CommandCode::SessionMessage))
 }
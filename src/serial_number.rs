@@ -53,7 +53,7 @@ impl FromStr for SerialNumber {
 
         for char in s.chars() {
             match char {
-                '0'...'9' => (),
+                '0'..='9' => (),
                 _ => {
                     return Err(err!(
                         AddrInvalid,
@@ -0,0 +1,167 @@
+//! Asymmetric authentication: establishes SCP03 session keys from EC P-256
+//! keys instead of a pre-shared `AuthKey`
+//!
+//! Both the host and the card contribute an ephemeral key pair and a static
+//! key pair (the host's `AsymmetricAuthKey` and the HSM's device key). The
+//! session keys are derived from both ECDH shared secrets using the ANSI
+//! X9.63 KDF (SHA-256), and the card proves it derived the same keys by
+//! sending a CMAC "receipt" over both ephemeral public keys. This replaces
+//! the card/host cryptogram exchange used with symmetric auth keys.
+
+use aes::Aes128;
+use byteorder::{BigEndian, ByteOrder};
+use cmac::{crypto_mac::Mac, Cmac};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use super::securechannel::{SecureChannel, KEY_SIZE};
+use super::{SessionError, SessionErrorKind::*, SessionId};
+use asymmetric_auth_key::{AsymmetricAuthKey, SHARED_SECRET_SIZE};
+
+/// Size of the receipt the card sends to prove it derived the session keys
+pub(crate) const RECEIPT_SIZE: usize = 16;
+
+/// "SharedInfo" input to the X9.63 KDF: key usage, key type (AES), and
+/// key length (128-bits), as specified for SCP11
+const KDF_SHARED_INFO: [u8; 3] = [0x3c, 0x88, 0x10];
+
+/// Keys derived from the ECDH shared secrets: the receipt key, followed
+/// by the SCP03 session keys
+pub(crate) struct SessionKeys {
+    /// Key used to compute the receipt
+    pub receipt_key: [u8; KEY_SIZE],
+
+    /// Session encryption key (S-ENC)
+    pub enc_key: [u8; KEY_SIZE],
+
+    /// Session Command MAC key (S-MAC)
+    pub mac_key: [u8; KEY_SIZE],
+
+    /// Session Response MAC key (S-RMAC)
+    pub rmac_key: [u8; KEY_SIZE],
+}
+
+impl SessionKeys {
+    /// Derive session keys from the ephemeral-ephemeral and static-static
+    /// ECDH shared secrets
+    fn derive(
+        ephemeral_secret: &[u8; SHARED_SECRET_SIZE],
+        static_secret: &[u8; SHARED_SECRET_SIZE],
+    ) -> Self {
+        let mut output = [0u8; KEY_SIZE * 4];
+
+        for (i, chunk) in output.chunks_mut(Sha256::output_size()).enumerate() {
+            let mut counter = [0u8; 4];
+            BigEndian::write_u32(&mut counter, i as u32 + 1);
+
+            let mut hasher = Sha256::new();
+            hasher.input(ephemeral_secret);
+            hasher.input(static_secret);
+            hasher.input(counter);
+            hasher.input(KDF_SHARED_INFO);
+            chunk.copy_from_slice(&hasher.result()[..chunk.len()]);
+        }
+
+        let keys = {
+            let key = |n: usize| {
+                let mut key = [0u8; KEY_SIZE];
+                key.copy_from_slice(&output[KEY_SIZE * n..KEY_SIZE * (n + 1)]);
+                key
+            };
+
+            SessionKeys {
+                receipt_key: key(0),
+                enc_key: key(1),
+                mac_key: key(2),
+                rmac_key: key(3),
+            }
+        };
+
+        output.zeroize();
+
+        keys
+    }
+
+    /// Compute the receipt over the card's and host's ephemeral public keys
+    fn receipt(&self, card_public_key: &[u8], host_public_key: &[u8]) -> [u8; RECEIPT_SIZE] {
+        let mut mac = Cmac::<Aes128>::new_varkey(&self.receipt_key).unwrap();
+        mac.input(card_public_key);
+        mac.input(host_public_key);
+
+        let mut receipt = [0u8; RECEIPT_SIZE];
+        receipt.copy_from_slice(mac.result().code().as_slice());
+        receipt
+    }
+}
+
+impl Drop for SessionKeys {
+    fn drop(&mut self) {
+        self.receipt_key.zeroize();
+        self.enc_key.zeroize();
+        self.mac_key.zeroize();
+        self.rmac_key.zeroize();
+    }
+}
+
+/// Compute ECDH, mapping errors (i.e. invalid public keys) to `ProtocolError`
+fn ecdh(
+    secret_key: &AsymmetricAuthKey,
+    public_key: &[u8],
+) -> Result<[u8; SHARED_SECRET_SIZE], SessionError> {
+    secret_key
+        .diffie_hellman(public_key)
+        .map_err(|e| err!(ProtocolError, "ECDH failed: {}", e))
+}
+
+/// Finish the handshake on the host side: derive session keys from the
+/// card's response and verify its receipt
+pub(crate) fn open_channel(
+    id: SessionId,
+    auth_key: &AsymmetricAuthKey,
+    host_ephemeral_key: &AsymmetricAuthKey,
+    device_public_key: &[u8],
+    card_public_key: &[u8],
+    card_receipt: &[u8],
+) -> Result<SecureChannel, SessionError> {
+    let mut ephemeral_secret = ecdh(host_ephemeral_key, card_public_key)?;
+    let mut static_secret = ecdh(auth_key, device_public_key)?;
+    let keys = SessionKeys::derive(&ephemeral_secret, &static_secret);
+    ephemeral_secret.zeroize();
+    static_secret.zeroize();
+
+    let host_public_key = host_ephemeral_key.public_key_point();
+    let receipt = keys.receipt(card_public_key, &host_public_key);
+
+    if receipt[..].ct_eq(card_receipt).unwrap_u8() != 1 {
+        fail!(AuthFail, "(session: {}) receipt mismatch!", id.to_u8());
+    }
+
+    Ok(SecureChannel::from_session_keys(id, &keys, receipt))
+}
+
+/// Perform the card side of the handshake (for simulating a card): derive
+/// session keys from the host's ephemeral public key and the public key of
+/// the auth key the host is authenticating as, returning the channel along
+/// with the card's ephemeral public key and receipt
+#[cfg(feature = "mockhsm")]
+pub(crate) fn accept_channel(
+    id: SessionId,
+    device_key: &AsymmetricAuthKey,
+    auth_public_key: &[u8],
+    host_public_key: &[u8],
+) -> Result<(SecureChannel, Vec<u8>, [u8; RECEIPT_SIZE]), SessionError> {
+    let card_ephemeral_key = AsymmetricAuthKey::generate();
+    let card_public_key = card_ephemeral_key.public_key_point();
+
+    let mut ephemeral_secret = ecdh(&card_ephemeral_key, host_public_key)?;
+    let mut static_secret = ecdh(device_key, auth_public_key)?;
+    let keys = SessionKeys::derive(&ephemeral_secret, &static_secret);
+    ephemeral_secret.zeroize();
+    static_secret.zeroize();
+
+    let receipt = keys.receipt(&card_public_key, host_public_key);
+    let channel = SecureChannel::from_session_keys(id, &keys, receipt);
+
+    Ok((channel, card_public_key, receipt))
+}
@@ -2,9 +2,13 @@
 //!
 //! <https://developers.yubico.com/YubiHSM2/Commands/Create_Session.html>
 
+#[cfg(feature = "asymmetric-auth")]
+use asymmetric_auth_key::UNCOMPRESSED_POINT_SIZE;
 use command::{Command, CommandCode};
 use object::ObjectId;
 use response::Response;
+#[cfg(feature = "asymmetric-auth")]
+use session::asymmetric::RECEIPT_SIZE;
 use session::securechannel::{Challenge, Cryptogram};
 
 /// Request parameters for `command::create_session`
@@ -34,3 +38,51 @@ pub(crate) struct CreateSessionResponse {
 impl Response for CreateSessionResponse {
     const COMMAND_CODE: CommandCode = CommandCode::CreateSession;
 }
+
+/// Request parameters for `command::create_asymmetric_session`
+#[cfg(feature = "asymmetric-auth")]
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct CreateAsymmetricSessionCommand {
+    /// Authentication key ID to use
+    pub auth_key_id: ObjectId,
+
+    /// Ephemeral public key of the host (uncompressed SEC1 point)
+    pub host_public_key: Vec<u8>,
+}
+
+#[cfg(feature = "asymmetric-auth")]
+impl Command for CreateAsymmetricSessionCommand {
+    type ResponseType = CreateAsymmetricSessionResponse;
+}
+
+/// Response from `command::create_asymmetric_session`: the card's ephemeral
+/// public key (uncompressed SEC1 point) followed by the session receipt
+#[cfg(feature = "asymmetric-auth")]
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct CreateAsymmetricSessionResponse(pub Vec<u8>);
+
+#[cfg(feature = "asymmetric-auth")]
+impl CreateAsymmetricSessionResponse {
+    /// Create a response from the card's ephemeral public key and receipt
+    #[cfg(feature = "mockhsm")]
+    pub fn new(card_public_key: &[u8], receipt: &[u8]) -> Self {
+        let mut bytes = Vec::with_capacity(card_public_key.len() + receipt.len());
+        bytes.extend_from_slice(card_public_key);
+        bytes.extend_from_slice(receipt);
+        CreateAsymmetricSessionResponse(bytes)
+    }
+
+    /// Split the response into the card's ephemeral public key and receipt
+    pub fn split(&self) -> Option<(&[u8], &[u8])> {
+        if self.0.len() == UNCOMPRESSED_POINT_SIZE + RECEIPT_SIZE {
+            Some(self.0.split_at(UNCOMPRESSED_POINT_SIZE))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "asymmetric-auth")]
+impl Response for CreateAsymmetricSessionResponse {
+    const COMMAND_CODE: CommandCode = CommandCode::CreateSession;
+}
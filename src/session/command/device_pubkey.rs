@@ -0,0 +1,30 @@
+//! Get the device public key, used to authenticate the HSM during asymmetric
+//! session establishment
+//!
+//! <https://developers.yubico.com/YubiHSM2/Commands/Get_Device_Public_Key.html>
+
+use algorithm::AsymmetricAlg;
+use command::{Command, CommandCode};
+use response::Response;
+
+/// Request parameters for `command::get_device_pubkey`
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct GetDevicePubKeyCommand {}
+
+impl Command for GetDevicePubKeyCommand {
+    type ResponseType = GetDevicePubKeyResponse;
+}
+
+/// Response from `command::get_device_pubkey`
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct GetDevicePubKeyResponse {
+    /// Algorithm of the device public key (always `EC_P256`)
+    pub algorithm: AsymmetricAlg,
+
+    /// X and Y coordinates of the device public key
+    pub public_key: Vec<u8>,
+}
+
+impl Response for GetDevicePubKeyResponse {
+    const COMMAND_CODE: CommandCode = CommandCode::GetDevicePubKey;
+}
@@ -2,10 +2,16 @@
 
 pub(crate) mod close;
 pub(crate) mod create;
+#[cfg(feature = "asymmetric-auth")]
+pub(crate) mod device_pubkey;
 
 use self::create::*;
+#[cfg(feature = "asymmetric-auth")]
+use self::device_pubkey::*;
 use super::{securechannel::Challenge, SessionError, SessionErrorKind::*, SessionId};
-use command::{CommandCode, CommandMessage};
+#[cfg(feature = "asymmetric-auth")]
+use algorithm::AsymmetricAlg;
+use command::{Command, CommandMessage};
 use connector::Connection;
 use object::ObjectId;
 use response::ResponseMessage;
@@ -13,14 +19,65 @@ use serialization::deserialize;
 
 /// Create a new encrypted session with the HSM over the given `Connection`
 pub(super) fn create_session(
-    connection: &Connection,
+    connection: &dyn Connection,
     auth_key_id: ObjectId,
     host_challenge: Challenge,
 ) -> Result<(SessionId, CreateSessionResponse), SessionError> {
-    let command_message: CommandMessage = CreateSessionCommand {
-        auth_key_id,
-        host_challenge,
-    }.into();
+    let (session_id, session_response) = send_unencrypted(
+        connection,
+        CreateSessionCommand {
+            auth_key_id,
+            host_challenge,
+        },
+    )?;
+
+    let session_id = session_id.ok_or_else(|| err!(CreateFailed, "no session ID in response"))?;
+    Ok((session_id, session_response))
+}
+
+/// Create a new session with the HSM using asymmetric authentication,
+/// sending the host's ephemeral public key
+#[cfg(feature = "asymmetric-auth")]
+pub(super) fn create_asymmetric_session(
+    connection: &dyn Connection,
+    auth_key_id: ObjectId,
+    host_public_key: Vec<u8>,
+) -> Result<(SessionId, CreateAsymmetricSessionResponse), SessionError> {
+    let (session_id, session_response) = send_unencrypted(
+        connection,
+        CreateAsymmetricSessionCommand {
+            auth_key_id,
+            host_public_key,
+        },
+    )?;
+
+    let session_id = session_id.ok_or_else(|| err!(CreateFailed, "no session ID in response"))?;
+    Ok((session_id, session_response))
+}
+
+/// Get the device public key (X and Y coordinates of a P-256 point), which
+/// the HSM authenticates itself with during asymmetric session establishment
+#[cfg(feature = "asymmetric-auth")]
+pub(super) fn get_device_pubkey(connection: &dyn Connection) -> Result<Vec<u8>, SessionError> {
+    let (_, response) = send_unencrypted(connection, GetDevicePubKeyCommand {})?;
+
+    ensure!(
+        response.algorithm == AsymmetricAlg::EC_P256,
+        ProtocolError,
+        "unexpected device public key algorithm: {:?}",
+        response.algorithm
+    );
+
+    Ok(response.public_key)
+}
+
+/// Send a command to the HSM outside of an encrypted session, returning
+/// the session ID in the response (if any) along with the parsed response
+fn send_unencrypted<C: Command>(
+    connection: &dyn Connection,
+    command: C,
+) -> Result<(Option<SessionId>, C::ResponseType), SessionError> {
+    let command_message: CommandMessage = command.into();
 
     let uuid = command_message.uuid;
     let response_body = connection.send_message(uuid, command_message.into())?;
@@ -30,20 +87,15 @@ pub(super) fn create_session(
         fail!(ResponseError, "HSM error: {:?}", response_message.code);
     }
 
-    if response_message.command().unwrap() != CommandCode::CreateSession {
+    if response_message.command().unwrap() != C::COMMAND_CODE {
         fail!(
             ProtocolError,
             "command type mismatch: expected {:?}, got {:?}",
-            CommandCode::CreateSession,
+            C::COMMAND_CODE,
             response_message.command().unwrap()
         );
     }
 
-    let session_id = response_message
-        .session_id
-        .ok_or_else(|| err!(CreateFailed, "no session ID in response"))?;
-
-    let session_response = deserialize(response_message.data.as_ref())?;
-
-    Ok((session_id, session_response))
+    let response = deserialize(response_message.data.as_ref())?;
+    Ok((response_message.session_id, response))
 }
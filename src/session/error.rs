@@ -1,6 +1,6 @@
 //! Session error types
 
-use connector::ConnectionError;
+use connector::{ConnectionError, ConnectionErrorKind};
 use error::{Error, HsmErrorKind};
 use serialization::SerializationError;
 
@@ -22,6 +22,13 @@ pub enum SessionErrorKind {
     #[fail(display = "max commands per session exceeded")]
     CommandLimitExceeded,
 
+    /// Errors with the connection to the HSM
+    #[fail(display = "connection error")]
+    ConnectionError {
+        /// Connection error kind
+        kind: ConnectionErrorKind,
+    },
+
     /// Couldn't create session
     #[fail(display = "couldn't create session")]
     CreateFailed,
@@ -52,7 +59,8 @@ pub enum SessionErrorKind {
 
 impl From<ConnectionError> for SessionError {
     fn from(err: ConnectionError) -> Self {
-        err!(SessionErrorKind::ProtocolError, err.to_string())
+        let kind = SessionErrorKind::ConnectionError { kind: err.kind() };
+        err!(kind, err.to_string())
     }
 }
 
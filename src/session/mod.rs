@@ -1,18 +1,27 @@
 //! Encrypted connection to the HSM through a particular connection
 
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use subtle::ConstantTimeEq;
 
+use algorithm::AuthAlg;
+#[cfg(feature = "asymmetric-auth")]
+use asymmetric_auth_key::AsymmetricAuthKey;
+use auth_key::AuthKey;
 use command::{Command, CommandCode, CommandMessage};
 use connector::{Connection, Connector};
-use credentials::Credentials;
+use credentials::{AuthMethod, Credentials};
 use error::HsmErrorKind;
-use response::ResponseMessage;
+use object::ObjectId;
+use observer::{CommandEvent, Observer};
+use response::{Response, ResponseCode, ResponseMessage};
 use serialization::deserialize;
 
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "asymmetric-auth")]
+pub(crate) mod asymmetric;
 pub(crate) mod command;
 mod error;
 mod id;
@@ -25,7 +34,7 @@ use self::securechannel::{Challenge, SecureChannel};
 use self::SessionErrorKind::*;
 pub use self::{
     error::{SessionError, SessionErrorKind},
-    timeout::SessionTimeout,
+    timeout::{SessionTimeout, SESSION_INACTIVITY_TIMEOUT},
 };
 
 /// Timeout fuzz factor: to avoid races/skew with the YubiHSM's clock,
@@ -44,8 +53,14 @@ pub struct Session {
     /// ID for this session
     id: SessionId,
 
+    /// ID of the auth key this session was authenticated with
+    auth_key_id: ObjectId,
+
+    /// Algorithm of the auth key this session was authenticated with
+    auth_alg: AuthAlg,
+
     /// Connection which communicates with the HSM (HTTP or USB)
-    connection: Box<Connection>,
+    connection: Box<dyn Connection>,
 
     /// Encrypted channel (SCP03) to the HSM
     secure_channel: Option<SecureChannel>,
@@ -58,12 +73,21 @@ pub struct Session {
 
     /// Inactivity timeout for this session
     timeout: SessionTimeout,
+
+    /// Total bytes sent to the HSM during this session
+    bytes_sent: usize,
+
+    /// Total bytes received from the HSM during this session
+    bytes_received: usize,
+
+    /// Observer to notify about commands sent over this session
+    observer: Option<Arc<dyn Observer>>,
 }
 
 impl Session {
     /// Connect to the HSM using the given configuration and credentials
     pub(super) fn open(
-        connector: &Connector,
+        connector: &dyn Connector,
         credentials: &Credentials,
         timeout: SessionTimeout,
     ) -> Result<Self, SessionError> {
@@ -74,48 +98,54 @@ impl Session {
             TIMEOUT_FUZZ_FACTOR
         );
 
-        // Ensure `Connector` is healthy before attempting to open `Session`
-        connector.healthcheck()?;
-
         let connection = connector.connect()?;
-        let host_challenge = Challenge::random();
-
-        let (session_id, session_response) =
-            command::create_session(&*connection, credentials.auth_key_id, host_challenge)?;
 
-        let channel = SecureChannel::new(
-            session_id,
-            &credentials.auth_key,
-            host_challenge,
-            session_response.card_challenge,
-        );
+        let auth_key_id = credentials.auth_key_id;
 
-        if channel
-            .card_cryptogram()
-            .ct_eq(&session_response.card_cryptogram)
-            .unwrap_u8()
-            != 1
-        {
-            fail!(
-                AuthFail,
-                "(session: {}) card cryptogram mismatch!",
-                channel.id().to_u8()
-            );
-        }
+        let (channel, authenticated) = match credentials.auth_method {
+            AuthMethod::Symmetric(ref auth_key) => {
+                let channel =
+                    open_symmetric_channel(connector, &*connection, auth_key_id, auth_key)?;
+                (channel, false)
+            }
+            #[cfg(feature = "asymmetric-auth")]
+            AuthMethod::Asymmetric {
+                ref auth_key,
+                ref device_public_key,
+            } => (
+                open_asymmetric_channel(
+                    &*connection,
+                    auth_key_id,
+                    auth_key,
+                    device_public_key.as_ref().map(Vec::as_slice),
+                )?,
+                true,
+            ),
+        };
 
         let id = channel.id();
         let now = Instant::now();
 
         let mut session = Session {
             id,
+            auth_key_id,
+            auth_alg: credentials.auth_alg(),
             connection,
             secure_channel: Some(channel),
             created_at: now,
             last_active: now,
             timeout,
+            bytes_sent: 0,
+            bytes_received: 0,
+            observer: None,
         };
 
-        session.authenticate(credentials)?;
+        // Asymmetric authentication already authenticates the session as
+        // part of the handshake
+        if !authenticated {
+            session.authenticate(credentials)?;
+        }
+
         Ok(session)
     }
 
@@ -129,6 +159,16 @@ impl Session {
         self.id
     }
 
+    /// ID of the auth key this session was authenticated with
+    pub fn auth_key_id(&self) -> ObjectId {
+        self.auth_key_id
+    }
+
+    /// Algorithm of the auth key this session was authenticated with
+    pub fn auth_alg(&self) -> AuthAlg {
+        self.auth_alg
+    }
+
     /// How long has this session been open?
     pub fn duration(&self) -> Duration {
         Instant::now().duration_since(self.created_at)
@@ -142,6 +182,21 @@ impl Session {
             .map(SecureChannel::counter)
     }
 
+    /// Total number of bytes sent to the HSM during this session
+    pub fn bytes_sent(&self) -> usize {
+        self.bytes_sent
+    }
+
+    /// Total number of bytes received from the HSM during this session
+    pub fn bytes_received(&self) -> usize {
+        self.bytes_received
+    }
+
+    /// Set the `Observer` to notify about commands sent over this session
+    pub(crate) fn set_observer(&mut self, observer: Option<Arc<dyn Observer>>) {
+        self.observer = observer;
+    }
+
     /// Has this session timed out?
     pub fn is_timed_out(&self) -> bool {
         let idle_time = Instant::now().duration_since(self.last_active);
@@ -154,12 +209,50 @@ impl Session {
         &mut self,
         command: C,
     ) -> Result<C::ResponseType, SessionError> {
-        let plaintext_cmd: CommandMessage = command.into();
+        self.send_command_message(command.into())
+    }
+
+    /// Encrypt an already serialized command message, send it to the HSM,
+    /// then read and decrypt the response
+    pub(crate) fn send_command_message<R: Response>(
+        &mut self,
+        plaintext_cmd: CommandMessage,
+    ) -> Result<R, SessionError> {
+        let observer = match self.observer {
+            Some(ref observer) => Arc::clone(observer),
+            None => return self.exchange_command(plaintext_cmd, &mut None),
+        };
+
+        let started_at = Instant::now();
+        let (bytes_sent, bytes_received) = (self.bytes_sent, self.bytes_received);
+        let mut response_code = None;
+        let result = self.exchange_command(plaintext_cmd, &mut response_code);
+
+        observer.on_command(&CommandEvent {
+            command: R::COMMAND_CODE,
+            session_id: self.id,
+            latency: started_at.elapsed(),
+            response_code,
+            bytes_sent: self.bytes_sent - bytes_sent,
+            bytes_received: self.bytes_received - bytes_received,
+            error: result.as_ref().err().map(SessionError::kind),
+        });
+
+        result
+    }
+
+    /// Exchange an encrypted command with the HSM, storing the response code
+    /// of the decrypted response in `response_code`
+    fn exchange_command<R: Response>(
+        &mut self,
+        plaintext_cmd: CommandMessage,
+        response_code: &mut Option<ResponseCode>,
+    ) -> Result<R, SessionError> {
         let cmd_type = plaintext_cmd.command_type;
         let encrypted_cmd = self.secure_channel()?.encrypt_command(plaintext_cmd)?;
         let uuid = encrypted_cmd.uuid;
 
-        session_debug!(self, "uuid={} cmd={:?}", uuid, C::COMMAND_CODE);
+        session_debug!(self, "uuid={} cmd={:?}", uuid, R::COMMAND_CODE);
 
         let encrypted_response = self.send_message(encrypted_cmd)?;
 
@@ -171,6 +264,8 @@ impl Session {
                 e
             })?;
 
+        *response_code = Some(response.code);
+
         if response.is_err() {
             if let Some(kind) = HsmErrorKind::from_response_message(&response) {
                 session_debug!(self, "uuid={} failed={:?} error={:?}", uuid, cmd_type, kind);
@@ -181,12 +276,12 @@ impl Session {
             }
         }
 
-        if response.command() != Some(C::COMMAND_CODE) {
+        if response.command() != Some(R::COMMAND_CODE) {
             fail!(
                 ResponseError,
                 "bad command type in response: {:?} (expected {:?})",
                 response.command(),
-                C::COMMAND_CODE,
+                R::COMMAND_CODE,
             );
         }
 
@@ -201,8 +296,14 @@ impl Session {
 
         session_debug!(self, "uuid={} command={:?}", &uuid, cmd_type);
 
-        let response = match self.connection.send_message(uuid, cmd.into()) {
-            Ok(response_bytes) => ResponseMessage::parse(response_bytes)?,
+        let cmd_bytes: Vec<u8> = cmd.into();
+        self.bytes_sent += cmd_bytes.len();
+
+        let response = match self.connection.send_message(uuid, cmd_bytes) {
+            Ok(response_bytes) => {
+                self.bytes_received += response_bytes.len();
+                ResponseMessage::parse(response_bytes)?
+            }
             Err(e) => {
                 self.secure_channel = None;
                 return Err(e.into());
@@ -256,6 +357,106 @@ impl Session {
     }
 }
 
+/// Host challenge to open a session with: random, unless the connector
+/// requires a particular one (e.g. to replay a recorded session)
+#[cfg(feature = "testing")]
+fn host_challenge(connector: &dyn Connector) -> Challenge {
+    connector
+        .host_challenge()
+        .map(Challenge::from_bytes)
+        .unwrap_or_else(Challenge::random)
+}
+
+/// Host challenge to open a session with
+#[cfg(not(feature = "testing"))]
+fn host_challenge(_connector: &dyn Connector) -> Challenge {
+    Challenge::random()
+}
+
+/// Create a session and establish its channel using a symmetric `AuthKey`.
+/// The resulting channel still needs to be authenticated.
+fn open_symmetric_channel(
+    connector: &dyn Connector,
+    connection: &dyn Connection,
+    auth_key_id: ObjectId,
+    auth_key: &AuthKey,
+) -> Result<SecureChannel, SessionError> {
+    let host_challenge = host_challenge(connector);
+
+    let (session_id, session_response) =
+        command::create_session(connection, auth_key_id, host_challenge)?;
+
+    let channel = SecureChannel::new(
+        session_id,
+        auth_key,
+        host_challenge,
+        session_response.card_challenge,
+    );
+
+    if channel
+        .card_cryptogram()
+        .ct_eq(&session_response.card_cryptogram)
+        .unwrap_u8()
+        != 1
+    {
+        fail!(
+            AuthFail,
+            "(session: {}) card cryptogram mismatch!",
+            channel.id().to_u8()
+        );
+    }
+
+    Ok(channel)
+}
+
+/// Get the public key (`x || y`) of the HSM's device key, which it
+/// authenticates itself with when opening asymmetric sessions
+#[cfg(feature = "asymmetric-auth")]
+pub(crate) fn device_public_key(connector: &dyn Connector) -> Result<Vec<u8>, SessionError> {
+    command::get_device_pubkey(&*connector.connect()?)
+}
+
+/// Create a session and establish an (already authenticated) channel using
+/// an `AsymmetricAuthKey`, checking the device public key against the
+/// expected one (if given)
+#[cfg(feature = "asymmetric-auth")]
+fn open_asymmetric_channel(
+    connection: &dyn Connection,
+    auth_key_id: ObjectId,
+    auth_key: &AsymmetricAuthKey,
+    expected_device_public_key: Option<&[u8]>,
+) -> Result<SecureChannel, SessionError> {
+    let device_public_key = command::get_device_pubkey(connection)?;
+
+    if let Some(expected) = expected_device_public_key {
+        ensure!(
+            device_public_key.as_slice() == expected,
+            AuthFail,
+            "device public key doesn't match the expected key"
+        );
+    }
+    let host_ephemeral_key = AsymmetricAuthKey::generate();
+
+    let (session_id, session_response) = command::create_asymmetric_session(
+        connection,
+        auth_key_id,
+        host_ephemeral_key.public_key_point(),
+    )?;
+
+    let (card_public_key, receipt) = session_response
+        .split()
+        .ok_or_else(|| err!(ProtocolError, "malformed asymmetric session response"))?;
+
+    asymmetric::open_channel(
+        session_id,
+        auth_key,
+        &host_ephemeral_key,
+        &device_public_key,
+        card_public_key,
+        receipt,
+    )
+}
+
 /// Close session automatically on drop
 impl Drop for Session {
     /// Make a best effort to close the session
@@ -32,6 +32,12 @@ impl Challenge {
         Challenge(challenge)
     }
 
+    /// Create a challenge from the given bytes
+    #[cfg(any(feature = "asymmetric-auth", feature = "testing"))]
+    pub fn from_bytes(bytes: [u8; CHALLENGE_SIZE]) -> Self {
+        Challenge(bytes)
+    }
+
     /// Borrow the challenge value as a slice
     #[cfg_attr(clippy, allow(clippy::trivially_copy_pass_by_ref))]
     pub fn as_slice(&self) -> &[u8] {
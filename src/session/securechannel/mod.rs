@@ -36,6 +36,8 @@ pub use self::{
     cryptogram::{Cryptogram, CRYPTOGRAM_SIZE},
 };
 
+#[cfg(feature = "asymmetric-auth")]
+use super::asymmetric::SessionKeys;
 use super::{CommandMessage, ResponseMessage, SessionError, SessionErrorKind::*, SessionId};
 use aes::{
     block_cipher_trait::{
@@ -114,6 +116,32 @@ impl SecureChannel {
         }
     }
 
+    /// Create an already authenticated channel from session keys established
+    /// with asymmetric authentication. The receipt the card sent to confirm
+    /// the keys is used as the initial MAC chaining value.
+    #[cfg(feature = "asymmetric-auth")]
+    pub fn from_session_keys(
+        id: SessionId,
+        keys: &SessionKeys,
+        receipt: [u8; MAC_SIZE * 2],
+    ) -> Self {
+        let zero_challenge = Challenge::from_bytes([0u8; CHALLENGE_SIZE]);
+
+        Self {
+            id,
+            // "The encryption counter's start value shall be set to 1 for the
+            // first command following a successful EXTERNAL AUTHENTICATE
+            // command." -- GPC_SPE_014 section 6.2.6
+            counter: 1,
+            security_level: SecurityLevel::Authenticated,
+            context: Context::from_challenges(zero_challenge, zero_challenge),
+            enc_key: keys.enc_key,
+            mac_key: keys.mac_key,
+            rmac_key: keys.rmac_key,
+            mac_chaining_value: receipt,
+        }
+    }
+
     /// Get the channel (i.e. session) ID
     pub fn id(&self) -> SessionId {
         self.id
@@ -169,12 +197,7 @@ impl SecureChannel {
         let tag = mac.result().code();
         self.mac_chaining_value.copy_from_slice(tag.as_slice());
 
-        Ok(CommandMessage::new_with_mac(
-            command_type,
-            self.id,
-            command_data,
-            &tag,
-        )?)
+        CommandMessage::new_with_mac(command_type, self.id, command_data, &tag)
     }
 
     /// Compute a message for authenticating the host to the card
@@ -253,7 +276,8 @@ impl SecureChannel {
             .map_err(|e| {
                 self.terminate();
                 err!(ProtocolError, "error decrypting response: {:?}", e)
-            })?.len();
+            })?
+            .len();
 
         response_message.truncate(response_len);
         let mut decrypted_response = ResponseMessage::parse(response_message)?;
@@ -370,7 +394,8 @@ impl SecureChannel {
             .map_err(|e| {
                 self.terminate();
                 err!(ProtocolError, "error decrypting command: {:?}", e)
-            })?.len();
+            })?
+            .len();
 
         command_data.truncate(command_len);
         let mut decrypted_command = CommandMessage::parse(command_data)?;
@@ -595,7 +620,8 @@ mod tests {
             .encrypt_response(ResponseMessage::success(
                 decrypted_command.command_type,
                 decrypted_command.data,
-            )).unwrap();
+            ))
+            .unwrap();
 
         let decrypted_response = host_channel.decrypt_response(response_ciphertext).unwrap();
 
@@ -621,7 +647,8 @@ mod tests {
             .encrypt_response(ResponseMessage::success(
                 decrypted_command.command_type,
                 decrypted_command.data,
-            )).unwrap();
+            ))
+            .unwrap();
 
         // Tweak MAC in response
         let mut bad_mac = Vec::from(response_ciphertext.mac.as_ref().unwrap().as_slice());
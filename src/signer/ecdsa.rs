@@ -0,0 +1,167 @@
+//! ECDSA signer
+
+use ecdsa::Signature;
+use k256::Secp256k1;
+use p256::NistP256;
+use p384::NistP384;
+use sha2::{Digest, Sha256, Sha384};
+use signature::{self, Signer};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use super::{check_algorithm, lock, signature_error, SignerError};
+use client::get_pubkey::PublicKey;
+use client::sign_ecdsa::ECDSASignature;
+use {Algorithm, AsymmetricAlg, Client, ObjectId};
+
+/// Elliptic curves supported by `EcdsaSigner`
+pub trait CurveAlg {
+    /// Algorithm of keys on this curve
+    const ASYMMETRIC_ALG: AsymmetricAlg;
+}
+
+/// ECDSA signer for a key stored in the HSM, where `C` is the key's curve
+/// (e.g. `p256::NistP256`).
+///
+/// Messages are hashed with the digest conventionally paired with the
+/// curve (SHA-256 for P-256 and secp256k1, SHA-384 for P-384).
+pub struct EcdsaSigner<C> {
+    /// Client shared with other signers
+    client: Arc<Mutex<Client>>,
+
+    /// ID of the signing key
+    key_id: ObjectId,
+
+    /// Curve of the signing key
+    curve: PhantomData<C>,
+}
+
+impl<C: CurveAlg> EcdsaSigner<C> {
+    /// Create a new signer for the given key, ensuring it's a key on `C`
+    pub fn create(client: Arc<Mutex<Client>>, key_id: ObjectId) -> Result<Self, SignerError> {
+        check_algorithm(&client, key_id, Algorithm::Asymmetric(C::ASYMMETRIC_ALG))?;
+
+        Ok(Self {
+            client,
+            key_id,
+            curve: PhantomData,
+        })
+    }
+
+    /// Get the ID of the signing key
+    pub fn key_id(&self) -> ObjectId {
+        self.key_id
+    }
+
+    /// Get the public key for this signer
+    pub fn public_key(&self) -> Result<PublicKey, SignerError> {
+        Ok(lock(&self.client)?.get_pubkey(self.key_id)?)
+    }
+
+    /// Sign the given digest, returning an ASN.1 DER signature
+    fn sign_digest(&self, digest: &[u8]) -> Result<ECDSASignature, signature::Error> {
+        lock(&self.client)
+            .and_then(|mut client| Ok(client.sign_ecdsa(self.key_id, digest)?))
+            .map_err(signature_error)
+    }
+}
+
+impl<C> Clone for EcdsaSigner<C> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            key_id: self.key_id,
+            curve: PhantomData,
+        }
+    }
+}
+
+/// Implement `CurveAlg`, `Signer`, and conversion from `ECDSASignature` for
+/// the given curve
+macro_rules! impl_ecdsa_signer {
+    ($curve:ty, $alg:expr, $digest:ty) => {
+        impl CurveAlg for $curve {
+            const ASYMMETRIC_ALG: AsymmetricAlg = $alg;
+        }
+
+        impl TryFrom<ECDSASignature> for Signature<$curve> {
+            type Error = signature::Error;
+
+            /// Parse an ASN.1 DER signature returned by the HSM
+            fn try_from(signature: ECDSASignature) -> Result<Self, signature::Error> {
+                Signature::from_der(signature.as_slice())
+            }
+        }
+
+        impl Signer<Signature<$curve>> for EcdsaSigner<$curve> {
+            fn try_sign(&self, msg: &[u8]) -> Result<Signature<$curve>, signature::Error> {
+                let signature = self.sign_digest(<$digest>::digest(msg).as_slice())?;
+                Signature::try_from(signature)
+            }
+        }
+    };
+}
+
+impl_ecdsa_signer!(NistP256, AsymmetricAlg::EC_P256, Sha256);
+impl_ecdsa_signer!(NistP384, AsymmetricAlg::EC_P384, Sha384);
+impl_ecdsa_signer!(Secp256k1, AsymmetricAlg::EC_K256, Sha256);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a DER signature whose `r` has the high bit set (so it has a
+    /// leading zero) and whose `s` has two leading zero bytes stripped
+    fn der_signature(header: &[u8], field_size: usize, s_header: &[u8]) -> ECDSASignature {
+        let mut der = header.to_vec();
+        der.extend_from_slice(&vec![0xa5; field_size]);
+        der.extend_from_slice(s_header);
+        der.extend_from_slice(&vec![0x5a; field_size - 2]);
+        ECDSASignature(der)
+    }
+
+    /// `r || s` for the signatures built by `der_signature`
+    fn expected_bytes(field_size: usize) -> Vec<u8> {
+        let mut bytes = vec![0xa5; field_size];
+        bytes.extend_from_slice(&[0x00, 0x00]);
+        bytes.extend_from_slice(&vec![0x5a; field_size - 2]);
+        bytes
+    }
+
+    #[test]
+    fn p256_signature_from_der() {
+        let der = der_signature(&[0x30, 0x43, 0x02, 0x21, 0x00], 32, &[0x02, 0x1e]);
+        let signature = Signature::<NistP256>::try_from(der).unwrap();
+        assert_eq!(&signature.to_bytes()[..], &expected_bytes(32)[..]);
+    }
+
+    #[test]
+    fn p384_signature_from_der() {
+        let der = der_signature(&[0x30, 0x63, 0x02, 0x31, 0x00], 48, &[0x02, 0x2e]);
+        let signature = Signature::<NistP384>::try_from(der).unwrap();
+        assert_eq!(&signature.to_bytes()[..], &expected_bytes(48)[..]);
+    }
+
+    #[test]
+    fn k256_signature_from_der() {
+        let der = der_signature(&[0x30, 0x43, 0x02, 0x21, 0x00], 32, &[0x02, 0x1e]);
+        let signature = Signature::<Secp256k1>::try_from(der).unwrap();
+        assert_eq!(&signature.to_bytes()[..], &expected_bytes(32)[..]);
+    }
+
+    #[test]
+    fn invalid_signatures_rejected() {
+        // r = 0
+        let zero_r = ECDSASignature(vec![0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01]);
+        assert!(Signature::<NistP256>::try_from(zero_r).is_err());
+
+        // Trailing data after the sequence
+        let trailing = ECDSASignature(vec![0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00]);
+        assert!(Signature::<NistP256>::try_from(trailing).is_err());
+
+        // P-384 signature for a P-256 key
+        let p384 = der_signature(&[0x30, 0x63, 0x02, 0x31, 0x00], 48, &[0x02, 0x2e]);
+        assert!(Signature::<NistP256>::try_from(p384).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+//! Ed25519 signer
+
+use ed25519::Signature;
+use signature::{self, Signer};
+use std::sync::{Arc, Mutex};
+
+use super::{check_algorithm, lock, signature_error, SignerError};
+use client::get_pubkey::PublicKey;
+use client::sign_eddsa::Ed25519Signature;
+use {Algorithm, AsymmetricAlg, Client, ObjectId};
+
+/// Ed25519 signer for a key stored in the HSM
+#[derive(Clone)]
+pub struct Ed25519Signer {
+    /// Client shared with other signers
+    client: Arc<Mutex<Client>>,
+
+    /// ID of the signing key
+    key_id: ObjectId,
+}
+
+impl Ed25519Signer {
+    /// Create a new signer for the given key, ensuring it's an Ed25519 key
+    pub fn create(client: Arc<Mutex<Client>>, key_id: ObjectId) -> Result<Self, SignerError> {
+        check_algorithm(
+            &client,
+            key_id,
+            Algorithm::Asymmetric(AsymmetricAlg::Ed25519),
+        )?;
+
+        Ok(Self { client, key_id })
+    }
+
+    /// Get the ID of the signing key
+    pub fn key_id(&self) -> ObjectId {
+        self.key_id
+    }
+
+    /// Get the public key for this signer
+    pub fn public_key(&self) -> Result<PublicKey, SignerError> {
+        Ok(lock(&self.client)?.get_pubkey(self.key_id)?)
+    }
+}
+
+impl Signer<Signature> for Ed25519Signer {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let signature = lock(&self.client)
+            .and_then(|mut client| Ok(client.sign_ed25519(self.key_id, msg)?))
+            .map_err(signature_error)?;
+
+        Ok(signature.into())
+    }
+}
+
+impl From<Ed25519Signature> for Signature {
+    fn from(signature: Ed25519Signature) -> Signature {
+        Signature::from_bytes(&signature.0)
+    }
+}
@@ -0,0 +1,34 @@
+//! Errors that occur when creating signers
+
+use client::{ClientError, ClientErrorKind};
+use error::Error;
+
+/// Signer errors
+pub type SignerError = Error<SignerErrorKind>;
+
+/// Kinds of signer errors
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum SignerErrorKind {
+    /// The key's algorithm doesn't match the signer's
+    #[fail(display = "invalid key algorithm")]
+    AlgorithmInvalid,
+
+    /// Errors communicating with the HSM
+    #[fail(display = "client error: {}", kind)]
+    ClientError {
+        /// Client error kind
+        kind: ClientErrorKind,
+    },
+
+    /// The shared `Client`'s lock was poisoned
+    #[fail(display = "client lock poisoned")]
+    LockPoisoned,
+}
+
+// TODO: capture causes?
+impl From<ClientError> for SignerError {
+    fn from(err: ClientError) -> Self {
+        let kind = SignerErrorKind::ClientError { kind: err.kind() };
+        Error::new(kind, err.message().map(str::to_owned))
+    }
+}
@@ -0,0 +1,54 @@
+//! Signers for keys stored in the HSM which implement the `signature::Signer`
+//! trait, so HSM keys can be used with any library which is generic over
+//! signers.
+//!
+//! Signers share a `Client` (behind an `Arc<Mutex<_>>`), locking it for each
+//! signature. The key's algorithm is checked when the signer is created.
+//!
+//! DER signatures returned by `Client::sign_ecdsa` can also be converted into
+//! `ecdsa::Signature`s for the supported curves with `TryFrom`.
+
+mod ecdsa;
+mod ed25519;
+mod error;
+
+pub use self::ecdsa::{CurveAlg, EcdsaSigner};
+pub use self::ed25519::Ed25519Signer;
+pub use self::error::{SignerError, SignerErrorKind};
+
+use signature;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use {Algorithm, Client, ObjectId, ObjectType};
+
+/// Lock the shared client
+fn lock(client: &Arc<Mutex<Client>>) -> Result<MutexGuard<Client>, SignerError> {
+    client
+        .lock()
+        .map_err(|_| err!(SignerErrorKind::LockPoisoned, "error obtaining client lock"))
+}
+
+/// Ensure the given key exists and has the expected algorithm
+fn check_algorithm(
+    client: &Arc<Mutex<Client>>,
+    key_id: ObjectId,
+    expected: Algorithm,
+) -> Result<(), SignerError> {
+    let info = lock(client)?.get_object_info(key_id, ObjectType::AsymmetricKey)?;
+
+    ensure!(
+        info.algorithm == expected,
+        SignerErrorKind::AlgorithmInvalid,
+        "key 0x{:04x} is {:?} (expected {:?})",
+        key_id,
+        info.algorithm,
+        expected
+    );
+
+    Ok(())
+}
+
+/// Convert a `SignerError` into a `signature::Error`, keeping it as the source
+fn signature_error(err: SignerError) -> signature::Error {
+    signature::Error::from_source(err)
+}
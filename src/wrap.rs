@@ -17,16 +17,18 @@ pub struct WrapMessage {
 }
 
 impl WrapMessage {
-    /// Load a `WrapMessage` from a byte vector
+    /// Load a `WrapMessage` from a byte vector (the nonce followed by the
+    /// ciphertext, as produced by `into_vec`)
     pub fn from_vec(mut vec: Vec<u8>) -> Result<Self, Error> {
         if vec.len() < WRAP_NONCE_SIZE {
             bail!("message must be at least {}-bytes", WRAP_NONCE_SIZE);
         }
 
+        let ciphertext = vec.split_off(WRAP_NONCE_SIZE);
         let mut nonce = [0u8; WRAP_NONCE_SIZE];
-        nonce.copy_from_slice(vec.split_off(WRAP_NONCE_SIZE).as_ref());
+        nonce.copy_from_slice(&vec);
 
-        Ok(Self::new(nonce, vec))
+        Ok(Self::new(nonce, ciphertext))
     }
 
     /// Create a new `WrapMessage`
@@ -47,12 +49,12 @@ impl WrapMessage {
     }
 }
 
-impl Into<Vec<u8>> for WrapMessage {
-    fn into(self) -> Vec<u8> {
+impl From<WrapMessage> for Vec<u8> {
+    fn from(message: WrapMessage) -> Vec<u8> {
         let WrapMessage {
             nonce,
             mut ciphertext,
-        } = self;
+        } = message;
 
         let mut vec = Vec::with_capacity(WRAP_NONCE_SIZE + ciphertext.len());
         vec.extend_from_slice(nonce.as_ref());
@@ -90,3 +92,26 @@ impl From<[u8; WRAP_NONCE_SIZE]> for WrapNonce {
 }
 
 impl_array_serializers!(WrapNonce, WRAP_NONCE_SIZE);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_vec_round_trip() {
+        let nonce = [0x01u8; WRAP_NONCE_SIZE];
+        let ciphertext = vec![0x02u8; 40];
+
+        let bytes = WrapMessage::new(nonce, ciphertext.clone()).into_vec();
+        assert_eq!(&bytes[..WRAP_NONCE_SIZE], &nonce);
+
+        let message = WrapMessage::from_vec(bytes).unwrap();
+        assert_eq!(message.nonce.as_ref(), &nonce);
+        assert_eq!(message.ciphertext, ciphertext);
+    }
+
+    #[test]
+    fn from_vec_too_short() {
+        assert!(WrapMessage::from_vec(vec![0u8; WRAP_NONCE_SIZE - 1]).is_err());
+    }
+}
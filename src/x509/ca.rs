@@ -0,0 +1,291 @@
+//! Certificate authority backed by a key stored in the HSM
+
+use rand::{OsRng, RngCore};
+
+use super::extension::{extensions_der, key_identifier};
+use super::signing::{sign, signature_algorithm_der};
+use super::{
+    Certificate, CertificationRequest, DistinguishedName, Extension, GeneralName, KeyUsage,
+    Validity, X509Error, X509ErrorKind::*,
+};
+use der;
+use {AsymmetricAlg, Client, ObjectId};
+
+/// Size of randomly generated serial numbers (i.e. 127-bits of entropy)
+const SERIAL_NUMBER_SIZE: usize = 16;
+
+/// Maximum size of a DER-encoded serial number (RFC 5280 section 4.1.2.2)
+const MAX_SERIAL_NUMBER_SIZE: usize = 20;
+
+/// X.509 v3 (encoded as 2)
+const X509_VERSION_3: u8 = 2;
+
+/// Kinds of certificates a `CertificateAuthority` can issue, which determine
+/// the basic constraints and key usage extensions they include
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Profile {
+    /// Self-signed root CA certificate
+    Root,
+
+    /// Intermediate CA certificate, optionally limiting how many further
+    /// intermediate CAs may follow it in a certification path
+    Intermediate {
+        /// Maximum number of intermediate CAs below this one
+        path_len: Option<u8>,
+    },
+
+    /// End entity (i.e. non-CA) certificate
+    Leaf,
+}
+
+impl Profile {
+    /// Get the extensions implied by this profile
+    fn extensions(self) -> Vec<Extension> {
+        let ca_key_usage =
+            KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN;
+
+        match self {
+            Profile::Root => vec![
+                Extension::basic_constraints(true, None),
+                Extension::key_usage(ca_key_usage),
+            ],
+            Profile::Intermediate { path_len } => vec![
+                Extension::basic_constraints(true, path_len),
+                Extension::key_usage(ca_key_usage),
+            ],
+            Profile::Leaf => vec![
+                Extension::basic_constraints(false, None),
+                Extension::key_usage(KeyUsage::DIGITAL_SIGNATURE),
+            ],
+        }
+    }
+}
+
+/// Builder for certificates to be issued by a `CertificateAuthority`
+#[derive(Clone, Debug)]
+pub struct CertificateBuilder {
+    /// DER-encoded subject `Name`
+    subject: Vec<u8>,
+
+    /// DER-encoded `SubjectPublicKeyInfo`
+    spki: Vec<u8>,
+
+    /// Validity period
+    validity: Validity,
+
+    /// Kind of certificate
+    profile: Profile,
+
+    /// Serial number (random if unset)
+    serial_number: Option<Vec<u8>>,
+
+    /// Extensions besides those implied by the profile
+    extensions: Vec<Extension>,
+}
+
+impl CertificateBuilder {
+    /// Create a builder for a leaf certificate for the given subject and
+    /// DER-encoded `SubjectPublicKeyInfo` (e.g. from `PublicKey::to_spki_der`)
+    pub fn new<K: Into<Vec<u8>>>(
+        subject: &DistinguishedName,
+        public_key: K,
+        validity: Validity,
+    ) -> Self {
+        Self {
+            subject: subject.to_der(),
+            spki: public_key.into(),
+            validity,
+            profile: Profile::Leaf,
+            serial_number: None,
+            extensions: vec![],
+        }
+    }
+
+    /// Create a builder for a leaf certificate from a certificate signing
+    /// request, after verifying its signature.
+    ///
+    /// Only the requested subject alternative names are copied from the
+    /// request: all other extensions are determined by the issuer.
+    pub fn from_csr(request: &CertificationRequest, validity: Validity) -> Result<Self, X509Error> {
+        let request = request.verify()?;
+
+        Ok(Self {
+            subject: request.subject,
+            spki: request.spki,
+            validity,
+            profile: Profile::Leaf,
+            serial_number: None,
+            extensions: request.subject_alt_names.into_iter().collect(),
+        })
+    }
+
+    /// Set the kind of certificate to issue (default: `Profile::Leaf`)
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Set the serial number as an unsigned big endian integer (default: a
+    /// random 127-bit number). Serial numbers must be unique per issuer.
+    ///
+    /// RFC 5280 requires serial numbers to be positive and at most 20 bytes
+    /// long once DER-encoded, so zero (or empty) serial numbers and ones which
+    /// are too large are rejected.
+    pub fn serial_number<S: Into<Vec<u8>>>(mut self, serial_number: S) -> Result<Self, X509Error> {
+        let serial_number = serial_number.into();
+
+        // Leading zeros don't count towards the size, but DER adds one if
+        // the high bit is set
+        let start = serial_number
+            .iter()
+            .position(|&b| b != 0)
+            .ok_or_else(|| err!(SerialNumberInvalid, "serial number must be positive"))?;
+
+        let encoded_len = serial_number.len() - start + (serial_number[start] >> 7) as usize;
+
+        ensure!(
+            encoded_len <= MAX_SERIAL_NUMBER_SIZE,
+            SerialNumberInvalid,
+            "serial number is too large ({} bytes encoded, max {})",
+            encoded_len,
+            MAX_SERIAL_NUMBER_SIZE
+        );
+
+        self.serial_number = Some(serial_number[start..].to_vec());
+        Ok(self)
+    }
+
+    /// Add subject alternative names
+    pub fn subject_alt_names(self, names: &[GeneralName]) -> Self {
+        self.extension(Extension::subject_alt_names(names))
+    }
+
+    /// Add an extension, replacing any extension of the same type (including
+    /// those implied by the profile)
+    pub fn extension(mut self, extension: Extension) -> Self {
+        self.extensions.retain(|ext| ext.oid != extension.oid);
+        self.extensions.push(extension);
+        self
+    }
+}
+
+/// Certificate authority which signs certificates with a key in the HSM
+#[derive(Clone, Debug)]
+pub struct CertificateAuthority {
+    /// ID of the CA's signing key
+    key_id: ObjectId,
+
+    /// Algorithm of the CA's signing key
+    algorithm: AsymmetricAlg,
+
+    /// DER-encoded `Name` of the CA (i.e. the issuer of its certificates)
+    subject: Vec<u8>,
+
+    /// DER-encoded `SubjectPublicKeyInfo` of the CA's signing key
+    spki: Vec<u8>,
+
+    /// Key identifier of the CA's signing key
+    key_identifier: Vec<u8>,
+}
+
+impl CertificateAuthority {
+    /// Create a CA using the given key in the HSM, whose own certificate has
+    /// the given subject (i.e. the subject of the root or intermediate CA
+    /// certificate issued for this key)
+    pub fn new(
+        client: &mut Client,
+        key_id: ObjectId,
+        subject: &DistinguishedName,
+    ) -> Result<Self, X509Error> {
+        let public_key = client.get_pubkey(key_id)?;
+
+        // Ensure we can sign with this key before going any further
+        signature_algorithm_der(public_key.algorithm)?;
+
+        let spki = public_key.to_spki_der();
+        let key_identifier = key_identifier(&spki)
+            .ok_or_else(|| err!(DerInvalid, "malformed SubjectPublicKeyInfo"))?;
+
+        Ok(Self {
+            key_id,
+            algorithm: public_key.algorithm,
+            subject: subject.to_der(),
+            spki,
+            key_identifier,
+        })
+    }
+
+    /// Get the ID of the CA's signing key
+    pub fn key_id(&self) -> ObjectId {
+        self.key_id
+    }
+
+    /// Issue a self-signed root certificate for this CA
+    pub fn self_signed(
+        &self,
+        client: &mut Client,
+        validity: Validity,
+    ) -> Result<Certificate, X509Error> {
+        let builder = CertificateBuilder {
+            subject: self.subject.clone(),
+            spki: self.spki.clone(),
+            validity,
+            profile: Profile::Root,
+            serial_number: None,
+            extensions: vec![],
+        };
+
+        self.issue(client, &builder)
+    }
+
+    /// Issue a certificate
+    pub fn issue(
+        &self,
+        client: &mut Client,
+        builder: &CertificateBuilder,
+    ) -> Result<Certificate, X509Error> {
+        let subject_key_identifier = key_identifier(&builder.spki)
+            .ok_or_else(|| err!(DerInvalid, "malformed SubjectPublicKeyInfo"))?;
+
+        let mut extensions = builder.profile.extensions();
+        extensions.push(Extension::subject_key_identifier(&subject_key_identifier));
+        extensions.push(Extension::authority_key_identifier(&self.key_identifier));
+        extensions.retain(|ext| builder.extensions.iter().all(|other| other.oid != ext.oid));
+        extensions.extend_from_slice(&builder.extensions);
+
+        let serial_number = match builder.serial_number {
+            Some(ref serial_number) => serial_number.clone(),
+            None => random_serial_number(),
+        };
+
+        let signature_algorithm = signature_algorithm_der(self.algorithm)?;
+
+        let tbs_certificate = der::sequence(&[
+            &der::constructed(0, &der::unsigned_integer(&[X509_VERSION_3])),
+            &der::unsigned_integer(&serial_number),
+            &signature_algorithm,
+            &self.subject,
+            &builder.validity.to_der(),
+            &builder.subject,
+            &builder.spki,
+            &der::constructed(3, &extensions_der(&extensions)),
+        ]);
+
+        let signature = sign(client, self.key_id, self.algorithm, &tbs_certificate)?;
+
+        Ok(Certificate::from_der_unchecked(der::sequence(&[
+            &tbs_certificate,
+            &signature_algorithm,
+            &der::bit_string(&signature),
+        ])))
+    }
+}
+
+/// Generate a random (positive) serial number
+fn random_serial_number() -> Vec<u8> {
+    let mut rng = OsRng::new().expect("RNG failure!");
+    let mut serial_number = vec![0u8; SERIAL_NUMBER_SIZE];
+    rng.fill_bytes(&mut serial_number);
+    serial_number[0] &= 0x7f;
+    serial_number
+}
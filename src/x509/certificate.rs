@@ -0,0 +1,92 @@
+//! X.509 certificates
+
+use super::{X509Error, X509ErrorKind::DerInvalid};
+use der;
+use pem;
+
+/// PEM label for certificates
+const PEM_LABEL: &str = "CERTIFICATE";
+
+/// DER-encoded X.509 certificate
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Certificate(Vec<u8>);
+
+impl Certificate {
+    /// Parse a DER-encoded certificate (e.g. one issued by another CA),
+    /// checking it's well-formed
+    pub fn from_der<B: Into<Vec<u8>>>(der: B) -> Result<Self, X509Error> {
+        let certificate = Certificate(der.into());
+
+        ensure!(
+            certificate.subject_public_key_info().is_some(),
+            DerInvalid,
+            "malformed certificate"
+        );
+
+        Ok(certificate)
+    }
+
+    /// Wrap DER which is known to be a well-formed certificate
+    pub(crate) fn from_der_unchecked(der: Vec<u8>) -> Self {
+        Certificate(der)
+    }
+
+    /// Borrow the DER encoding of this certificate
+    pub fn as_der(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Unwrap the DER encoding of this certificate
+    pub fn into_der(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Encode this certificate as PEM (`-----BEGIN CERTIFICATE-----`)
+    pub fn to_pem(&self) -> String {
+        pem::encode(PEM_LABEL, &self.0)
+    }
+
+    /// Find the certificate's DER-encoded `SubjectPublicKeyInfo`, returning
+    /// `None` if the certificate is malformed
+    pub(crate) fn subject_public_key_info(&self) -> Option<&[u8]> {
+        let (certificate, rest) = der::parse(&self.0)?;
+        let fields = der::elements(certificate.value)?;
+
+        if !rest.is_empty()
+            || certificate.tag != der::SEQUENCE
+            || fields.len() != 3
+            || fields[0].tag != der::SEQUENCE
+            || fields[2].tag != der::BIT_STRING
+        {
+            return None;
+        }
+
+        let mut tbs_fields = der::elements(fields[0].value)?;
+
+        // The version is optional (and omitted from v1 certificates)
+        if tbs_fields.first()?.tag == der::CONTEXT_SPECIFIC | der::CONSTRUCTED {
+            tbs_fields.remove(0);
+        }
+
+        // serialNumber, signature, issuer, validity, subject, subjectPublicKeyInfo
+        let spki = tbs_fields.get(5)?;
+
+        if tbs_fields[0].tag != der::INTEGER || spki.tag != der::SEQUENCE {
+            return None;
+        }
+
+        Some(spki.encoded)
+    }
+}
+
+impl AsRef<[u8]> for Certificate {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Certificate> for Vec<u8> {
+    fn from(certificate: Certificate) -> Vec<u8> {
+        certificate.0
+    }
+}
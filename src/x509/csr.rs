@@ -0,0 +1,257 @@
+//! PKCS#10 certificate signing requests (RFC 2986)
+
+use super::extension::{extensions_der, OID_SUBJECT_ALT_NAME};
+use super::signing::{sign, signature_algorithm_der, verify};
+use super::{DistinguishedName, Extension, GeneralName, X509Error, X509ErrorKind::*};
+use der;
+use pem;
+use {Client, ObjectId};
+
+/// extensionRequest (PKCS#9)
+const OID_EXTENSION_REQUEST: &[u64] = &[1, 2, 840, 113_549, 1, 9, 14];
+
+/// PEM label for certificate signing requests
+const PEM_LABEL: &str = "CERTIFICATE REQUEST";
+
+/// Builder for certificate signing requests for keys stored in the HSM
+#[derive(Clone, Debug)]
+pub struct CsrBuilder {
+    /// Subject of the requested certificate
+    subject: DistinguishedName,
+
+    /// Subject alternative names to request
+    subject_alt_names: Vec<GeneralName>,
+
+    /// Other extensions to request
+    extensions: Vec<Extension>,
+}
+
+impl CsrBuilder {
+    /// Create a new `CsrBuilder` for the given subject
+    pub fn new(subject: DistinguishedName) -> Self {
+        Self {
+            subject,
+            subject_alt_names: vec![],
+            extensions: vec![],
+        }
+    }
+
+    /// Request a subject alternative name
+    pub fn subject_alt_name(mut self, name: GeneralName) -> Self {
+        self.subject_alt_names.push(name);
+        self
+    }
+
+    /// Request an extension
+    pub fn extension(mut self, extension: Extension) -> Self {
+        self.extensions.push(extension);
+        self
+    }
+
+    /// Build the request for the given key, obtaining its public key from the
+    /// HSM and signing the request with it
+    pub fn sign(
+        &self,
+        client: &mut Client,
+        key_id: ObjectId,
+    ) -> Result<CertificationRequest, X509Error> {
+        let public_key = client.get_pubkey(key_id)?;
+        let signature_algorithm = signature_algorithm_der(public_key.algorithm)?;
+        let info = self.info_der(&public_key.to_spki_der());
+        let signature = sign(client, key_id, public_key.algorithm, &info)?;
+
+        Ok(CertificationRequest(der::sequence(&[
+            &info,
+            &signature_algorithm,
+            &der::bit_string(&signature),
+        ])))
+    }
+
+    /// Serialize the `CertificationRequestInfo` for the given public key
+    fn info_der(&self, spki: &[u8]) -> Vec<u8> {
+        let mut extensions = self.extensions.clone();
+
+        if !self.subject_alt_names.is_empty() {
+            extensions.push(Extension::subject_alt_names(&self.subject_alt_names));
+        }
+
+        let attributes = if extensions.is_empty() {
+            vec![]
+        } else {
+            der::sequence(&[
+                &der::oid(OID_EXTENSION_REQUEST),
+                &der::set(&[&extensions_der(&extensions)]),
+            ])
+        };
+
+        der::sequence(&[
+            &der::unsigned_integer(&[0]),
+            &self.subject.to_der(),
+            spki,
+            &der::constructed(0, &attributes),
+        ])
+    }
+}
+
+/// DER-encoded PKCS#10 certificate signing request
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificationRequest(Vec<u8>);
+
+/// Fields of a certificate signing request whose signature has been verified
+pub(crate) struct VerifiedRequest {
+    /// DER-encoded subject `Name`
+    pub subject: Vec<u8>,
+
+    /// DER-encoded `SubjectPublicKeyInfo`
+    pub spki: Vec<u8>,
+
+    /// Requested subject alternative names extension (if any)
+    pub subject_alt_names: Option<Extension>,
+}
+
+impl CertificationRequest {
+    /// Parse a DER-encoded request (e.g. one generated by another tool),
+    /// checking it's well-formed
+    pub fn from_der<B: Into<Vec<u8>>>(der: B) -> Result<Self, X509Error> {
+        let request = CertificationRequest(der.into());
+
+        ensure!(
+            request.parse().is_some(),
+            DerInvalid,
+            "malformed certification request"
+        );
+
+        Ok(request)
+    }
+
+    /// Verify the request's signature (i.e. that the requester has the
+    /// private key), returning the fields needed to issue a certificate.
+    ///
+    /// Of the requested extensions, only the subject alternative names are
+    /// returned: it's up to the issuer to decide on the others.
+    pub(crate) fn verify(&self) -> Result<VerifiedRequest, X509Error> {
+        let request = self
+            .parse()
+            .ok_or_else(|| err!(DerInvalid, "malformed certification request"))?;
+
+        verify(
+            request.spki,
+            request.signature_algorithm,
+            request.info,
+            request.signature,
+        )?;
+
+        Ok(VerifiedRequest {
+            subject: request.subject.to_vec(),
+            spki: request.spki.to_vec(),
+            subject_alt_names: request.subject_alt_names,
+        })
+    }
+
+    /// Split the request into its fields
+    fn parse(&self) -> Option<ParsedRequest> {
+        let (request, rest) = der::parse(&self.0)?;
+        let fields = der::elements(request.value)?;
+
+        if !rest.is_empty()
+            || request.tag != der::SEQUENCE
+            || fields.len() != 3
+            || fields[2].tag != der::BIT_STRING
+            || fields[2].value.first() != Some(&0)
+        {
+            return None;
+        }
+
+        let info = der::elements(fields[0].value)?;
+
+        if info.len() != 4 || info[3].tag != der::CONTEXT_SPECIFIC | der::CONSTRUCTED {
+            return None;
+        }
+
+        Some(ParsedRequest {
+            info: fields[0].encoded,
+            subject: info[1].encoded,
+            spki: info[2].encoded,
+            subject_alt_names: parse_subject_alt_names(info[3].value)?,
+            signature_algorithm: fields[1].encoded,
+            signature: &fields[2].value[1..],
+        })
+    }
+    /// Borrow the DER encoding of this request
+    pub fn as_der(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Unwrap the DER encoding of this request
+    pub fn into_der(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Encode this request as PEM (`-----BEGIN CERTIFICATE REQUEST-----`)
+    pub fn to_pem(&self) -> String {
+        pem::encode(PEM_LABEL, &self.0)
+    }
+}
+
+impl AsRef<[u8]> for CertificationRequest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Fields of a certificate signing request
+struct ParsedRequest<'a> {
+    /// DER-encoded `CertificationRequestInfo` (i.e. the signed data)
+    info: &'a [u8],
+
+    /// DER-encoded subject `Name`
+    subject: &'a [u8],
+
+    /// DER-encoded `SubjectPublicKeyInfo`
+    spki: &'a [u8],
+
+    /// Requested subject alternative names extension (if any)
+    subject_alt_names: Option<Extension>,
+
+    /// DER-encoded signature `AlgorithmIdentifier`
+    signature_algorithm: &'a [u8],
+
+    /// Signature over `info`
+    signature: &'a [u8],
+}
+
+/// Find the subject alternative names extension in a CSR's attributes
+fn parse_subject_alt_names(attributes: &[u8]) -> Option<Option<Extension>> {
+    let extension_request = der::oid(OID_EXTENSION_REQUEST);
+    let subject_alt_name = der::oid(OID_SUBJECT_ALT_NAME);
+
+    for attribute in der::elements(attributes)? {
+        let fields = der::elements(attribute.value)?;
+
+        if fields.len() != 2 || fields[0].encoded != &extension_request[..] {
+            continue;
+        }
+
+        // The attribute value is a SET containing a single `Extensions`
+        let values = der::elements(fields[1].value)?;
+
+        if values.len() != 1 {
+            return None;
+        }
+
+        for extension in der::elements(values[0].value)? {
+            let fields = der::elements(extension.value)?;
+
+            if fields.is_empty() || fields[0].encoded != &subject_alt_name[..] {
+                continue;
+            }
+
+            let critical = fields.len() == 3 && fields[1].value == [0xff];
+            let value = fields.last()?.value;
+
+            return Some(Some(Extension::new(OID_SUBJECT_ALT_NAME, critical, value)));
+        }
+    }
+
+    Some(None)
+}
@@ -0,0 +1,46 @@
+//! Errors that occur when building X.509 structures
+
+use client::{ClientError, ClientErrorKind};
+use error::Error;
+
+/// X.509 errors
+pub type X509Error = Error<X509ErrorKind>;
+
+/// Kinds of X.509 errors
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum X509ErrorKind {
+    /// The key's algorithm can't be used to sign X.509 structures
+    #[fail(display = "unsupported algorithm")]
+    AlgorithmUnsupported,
+
+    /// Errors communicating with the HSM
+    #[fail(display = "client error: {}", kind)]
+    ClientError {
+        /// Client error kind
+        kind: ClientErrorKind,
+    },
+
+    /// Malformed DER encoding
+    #[fail(display = "invalid DER")]
+    DerInvalid,
+
+    /// A certificate's public key doesn't match the HSM key it's stored with
+    #[fail(display = "public key mismatch")]
+    PublicKeyMismatch,
+
+    /// A serial number isn't a positive integer of at most 20 bytes
+    #[fail(display = "invalid serial number")]
+    SerialNumberInvalid,
+
+    /// A signature (e.g. a CSR's proof of possession) didn't verify
+    #[fail(display = "invalid signature")]
+    SignatureInvalid,
+}
+
+// TODO: capture causes?
+impl From<ClientError> for X509Error {
+    fn from(err: ClientError) -> Self {
+        let kind = X509ErrorKind::ClientError { kind: err.kind() };
+        Error::new(kind, err.message().map(str::to_owned))
+    }
+}
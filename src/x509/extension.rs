@@ -0,0 +1,185 @@
+//! X.509 v3 extensions
+
+// Apparently bitflags isn't clippy-safe
+#![cfg_attr(
+    clippy,
+    allow(clippy::redundant_field_names, clippy::suspicious_arithmetic_impl)
+)]
+
+use sha2::{Digest, Sha256};
+
+use der;
+use x509::GeneralName;
+
+/// subjectKeyIdentifier (RFC 5280 4.2.1.2)
+const OID_SUBJECT_KEY_IDENTIFIER: &[u64] = &[2, 5, 29, 14];
+
+/// keyUsage (RFC 5280 4.2.1.3)
+const OID_KEY_USAGE: &[u64] = &[2, 5, 29, 15];
+
+/// subjectAltName (RFC 5280 4.2.1.6)
+pub(crate) const OID_SUBJECT_ALT_NAME: &[u64] = &[2, 5, 29, 17];
+
+/// basicConstraints (RFC 5280 4.2.1.9)
+const OID_BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+
+/// authorityKeyIdentifier (RFC 5280 4.2.1.1)
+const OID_AUTHORITY_KEY_IDENTIFIER: &[u64] = &[2, 5, 29, 35];
+
+/// Size of key identifiers (160-bits, as with the SHA-1 based method)
+const KEY_IDENTIFIER_SIZE: usize = 20;
+
+bitflags! {
+    /// Purposes a certificate's key may be used for (RFC 5280 4.2.1.3)
+    pub struct KeyUsage: u16 {
+        /// digitalSignature
+        const DIGITAL_SIGNATURE = 1 << 0;
+
+        /// nonRepudiation (a.k.a. contentCommitment)
+        const NON_REPUDIATION = 1 << 1;
+
+        /// keyEncipherment
+        const KEY_ENCIPHERMENT = 1 << 2;
+
+        /// dataEncipherment
+        const DATA_ENCIPHERMENT = 1 << 3;
+
+        /// keyAgreement
+        const KEY_AGREEMENT = 1 << 4;
+
+        /// keyCertSign
+        const KEY_CERT_SIGN = 1 << 5;
+
+        /// cRLSign
+        const CRL_SIGN = 1 << 6;
+
+        /// encipherOnly
+        const ENCIPHER_ONLY = 1 << 7;
+
+        /// decipherOnly
+        const DECIPHER_ONLY = 1 << 8;
+    }
+}
+
+/// X.509 v3 extensions (as included in certificates, or requested in CSRs)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Extension {
+    /// Object identifier for the extension's type (as arcs)
+    pub oid: Vec<u64>,
+
+    /// Must relying parties which don't understand the extension reject
+    /// the certificate?
+    pub critical: bool,
+
+    /// DER-encoded value of the extension
+    pub value: Vec<u8>,
+}
+
+impl Extension {
+    /// Create a new extension from its OID and DER-encoded value
+    pub fn new<O, V>(oid: O, critical: bool, value: V) -> Self
+    where
+        O: Into<Vec<u64>>,
+        V: Into<Vec<u8>>,
+    {
+        Self {
+            oid: oid.into(),
+            critical,
+            value: value.into(),
+        }
+    }
+
+    /// Create a (non-critical) subject alternative name extension
+    pub fn subject_alt_names(names: &[GeneralName]) -> Self {
+        let names: Vec<Vec<u8>> = names.iter().map(|name| name.to_der()).collect();
+        let names: Vec<&[u8]> = names.iter().map(|name| name.as_slice()).collect();
+        Self::new(OID_SUBJECT_ALT_NAME, false, der::sequence(&names))
+    }
+
+    /// Create a (critical) basic constraints extension, indicating whether
+    /// the subject is a CA and, if so, how many intermediate CAs may follow
+    /// it in a certification path
+    pub fn basic_constraints(ca: bool, path_len: Option<u8>) -> Self {
+        let mut fields = vec![];
+
+        if ca {
+            fields.push(der::boolean(true));
+
+            if let Some(len) = path_len {
+                fields.push(der::unsigned_integer(&[len]));
+            }
+        }
+
+        let fields: Vec<&[u8]> = fields.iter().map(|field| field.as_slice()).collect();
+        Self::new(OID_BASIC_CONSTRAINTS, true, der::sequence(&fields))
+    }
+
+    /// Create a (critical) key usage extension
+    pub fn key_usage(usage: KeyUsage) -> Self {
+        // Named bits are numbered from the most significant bit of the first
+        // byte, and DER requires trailing zero bits to be removed
+        let mut bytes: Vec<u8> = vec![];
+
+        for bit in 0..16 {
+            if usage.bits() & (1 << bit) != 0 {
+                bytes.resize(bit / 8 + 1, 0);
+                bytes[bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+
+        let unused_bits = bytes.last().map(|b| b.trailing_zeros() as u8).unwrap_or(0);
+        bytes.insert(0, unused_bits);
+
+        Self::new(OID_KEY_USAGE, true, der::tlv(der::BIT_STRING, &bytes))
+    }
+
+    /// Create a (non-critical) subject key identifier extension
+    pub fn subject_key_identifier(key_id: &[u8]) -> Self {
+        Self::new(OID_SUBJECT_KEY_IDENTIFIER, false, der::octet_string(key_id))
+    }
+
+    /// Create a (non-critical) authority key identifier extension from the
+    /// issuer's key identifier
+    pub fn authority_key_identifier(key_id: &[u8]) -> Self {
+        Self::new(
+            OID_AUTHORITY_KEY_IDENTIFIER,
+            false,
+            der::sequence(&[&der::implicit(0, key_id)]),
+        )
+    }
+
+    /// Serialize as a DER-encoded `Extension`
+    pub(crate) fn to_der(&self) -> Vec<u8> {
+        let oid = der::oid(&self.oid);
+        let value = der::octet_string(&self.value);
+
+        // DER requires omitting `critical` when it's the default (FALSE)
+        if self.critical {
+            der::sequence(&[&oid, &der::boolean(true), &value])
+        } else {
+            der::sequence(&[&oid, &value])
+        }
+    }
+}
+
+/// Compute the key identifier for the given DER-encoded
+/// `SubjectPublicKeyInfo`: the SHA-256 hash of its subjectPublicKey BIT
+/// STRING, truncated to 160-bits (RFC 7093 section 2, method 1)
+pub(crate) fn key_identifier(spki: &[u8]) -> Option<Vec<u8>> {
+    let (spki, _) = der::parse(spki)?;
+    let fields = der::elements(spki.value)?;
+
+    if fields.len() != 2 || fields[1].tag != der::BIT_STRING || fields[1].value.is_empty() {
+        return None;
+    }
+
+    let hash = Sha256::digest(&fields[1].value[1..]);
+    Some(hash[..KEY_IDENTIFIER_SIZE].to_vec())
+}
+
+/// Serialize extensions as a DER-encoded `Extensions` SEQUENCE
+pub(crate) fn extensions_der(extensions: &[Extension]) -> Vec<u8> {
+    let extensions: Vec<Vec<u8>> = extensions.iter().map(|ext| ext.to_der()).collect();
+    let extensions: Vec<&[u8]> = extensions.iter().map(|ext| ext.as_slice()).collect();
+    der::sequence(&extensions)
+}
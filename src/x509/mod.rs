@@ -0,0 +1,31 @@
+//! X.509 structures signed by keys stored in the HSM: certificate signing
+//! requests (PKCS#10) for enrolling HSM keys with a certificate authority,
+//! and a small certificate authority which issues certificates (e.g. for an
+//! mTLS PKI rooted in the HSM).
+//!
+//! Certificates can be stored in the HSM alongside their keys, as opaque
+//! objects with the same object ID (the convention used by yubihsm-shell).
+//!
+//! Signatures are computed by the HSM: ECDSA keys sign a digest of the
+//! structure (SHA-256, SHA-384 or SHA-512 depending on the curve), Ed25519
+//! keys sign it directly, and RSA keys use PKCS#1v1.5 with SHA-256 (which
+//! requires the `rsa` cargo feature).
+
+mod ca;
+mod certificate;
+mod csr;
+mod error;
+mod extension;
+mod name;
+mod signing;
+mod storage;
+mod validity;
+
+pub use self::ca::{CertificateAuthority, CertificateBuilder, Profile};
+pub use self::certificate::Certificate;
+pub use self::csr::{CertificationRequest, CsrBuilder};
+pub use self::error::{X509Error, X509ErrorKind};
+pub use self::extension::{Extension, KeyUsage};
+pub use self::name::{DistinguishedName, GeneralName};
+pub use self::storage::{get_certificate, put_certificate};
+pub use self::validity::Validity;
@@ -0,0 +1,122 @@
+//! Distinguished names and subject alternative names
+
+use std::net::IpAddr;
+
+use der;
+
+/// commonName (X.520)
+const OID_COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+
+/// countryName (X.520)
+const OID_COUNTRY: &[u64] = &[2, 5, 4, 6];
+
+/// localityName (X.520)
+const OID_LOCALITY: &[u64] = &[2, 5, 4, 7];
+
+/// stateOrProvinceName (X.520)
+const OID_STATE_OR_PROVINCE: &[u64] = &[2, 5, 4, 8];
+
+/// organizationName (X.520)
+const OID_ORGANIZATION: &[u64] = &[2, 5, 4, 10];
+
+/// organizationalUnitName (X.520)
+const OID_ORGANIZATIONAL_UNIT: &[u64] = &[2, 5, 4, 11];
+
+/// X.501 distinguished names (e.g. the subject or issuer of a certificate),
+/// encoded with one attribute per relative distinguished name, in the
+/// order they're added
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DistinguishedName(Vec<(&'static [u64], String)>);
+
+impl DistinguishedName {
+    /// Create a new, empty `DistinguishedName`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a common name (CN)
+    pub fn common_name<S: Into<String>>(self, value: S) -> Self {
+        self.attribute(OID_COMMON_NAME, value)
+    }
+
+    /// Add a two-letter country code (C)
+    pub fn country<S: Into<String>>(self, value: S) -> Self {
+        self.attribute(OID_COUNTRY, value)
+    }
+
+    /// Add a locality (L)
+    pub fn locality<S: Into<String>>(self, value: S) -> Self {
+        self.attribute(OID_LOCALITY, value)
+    }
+
+    /// Add a state or province (ST)
+    pub fn state_or_province<S: Into<String>>(self, value: S) -> Self {
+        self.attribute(OID_STATE_OR_PROVINCE, value)
+    }
+
+    /// Add an organization (O)
+    pub fn organization<S: Into<String>>(self, value: S) -> Self {
+        self.attribute(OID_ORGANIZATION, value)
+    }
+
+    /// Add an organizational unit (OU)
+    pub fn organizational_unit<S: Into<String>>(self, value: S) -> Self {
+        self.attribute(OID_ORGANIZATIONAL_UNIT, value)
+    }
+
+    /// Add an attribute
+    fn attribute<S: Into<String>>(mut self, oid: &'static [u64], value: S) -> Self {
+        self.0.push((oid, value.into()));
+        self
+    }
+
+    /// Serialize as a DER-encoded `Name`
+    pub fn to_der(&self) -> Vec<u8> {
+        let rdns: Vec<Vec<u8>> = self
+            .0
+            .iter()
+            .map(|&(oid, ref value)| {
+                // Country codes must be PrintableStrings (RFC 5280 Appendix A)
+                let value = if oid == OID_COUNTRY {
+                    der::printable_string(value)
+                } else {
+                    der::utf8_string(value)
+                };
+
+                der::set(&[&der::sequence(&[&der::oid(oid), &value])])
+            })
+            .collect();
+
+        let rdns: Vec<&[u8]> = rdns.iter().map(|rdn| rdn.as_slice()).collect();
+        der::sequence(&rdns)
+    }
+}
+
+/// Names which can appear in the subject alternative name extension
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GeneralName {
+    /// DNS name (e.g. `example.com`)
+    DnsName(String),
+
+    /// Email address
+    Email(String),
+
+    /// URI
+    Uri(String),
+
+    /// IPv4 or IPv6 address
+    IpAddress(IpAddr),
+}
+
+impl GeneralName {
+    /// Serialize as a DER-encoded `GeneralName`
+    pub(crate) fn to_der(&self) -> Vec<u8> {
+        match *self {
+            GeneralName::Email(ref email) => der::implicit(1, email.as_bytes()),
+            GeneralName::DnsName(ref name) => der::implicit(2, name.as_bytes()),
+            GeneralName::Uri(ref uri) => der::implicit(6, uri.as_bytes()),
+            GeneralName::IpAddress(IpAddr::V4(ref addr)) => der::implicit(7, &addr.octets()),
+            GeneralName::IpAddress(IpAddr::V6(ref addr)) => der::implicit(7, &addr.octets()),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Signing X.509 structures with HSM keys, and verifying signatures made
+//! by other keys (e.g. on CSRs)
+
+use ring::signature::{self as ring_signature, VerificationAlgorithm};
+use untrusted;
+
+use super::{X509Error, X509ErrorKind::*};
+use client::get_pubkey::{curve_oid, OID_EC_PUBLIC_KEY};
+use der;
+use {AsymmetricAlg, Client, EcdsaAlg, ObjectId};
+
+/// id-Ed25519 (RFC 8410)
+const OID_ED25519: &[u64] = &[1, 3, 101, 112];
+
+/// ecdsa-with-SHA256 (RFC 5758)
+const OID_ECDSA_WITH_SHA256: &[u64] = &[1, 2, 840, 10_045, 4, 3, 2];
+
+/// ecdsa-with-SHA384 (RFC 5758)
+const OID_ECDSA_WITH_SHA384: &[u64] = &[1, 2, 840, 10_045, 4, 3, 3];
+
+/// ecdsa-with-SHA512 (RFC 5758)
+const OID_ECDSA_WITH_SHA512: &[u64] = &[1, 2, 840, 10_045, 4, 3, 4];
+
+/// sha256WithRSAEncryption (RFC 4055)
+const OID_SHA256_WITH_RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113_549, 1, 1, 11];
+
+/// sha384WithRSAEncryption (RFC 4055)
+const OID_SHA384_WITH_RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113_549, 1, 1, 12];
+
+/// sha512WithRSAEncryption (RFC 4055)
+const OID_SHA512_WITH_RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113_549, 1, 1, 13];
+
+/// Get the DER-encoded `AlgorithmIdentifier` for signatures made with keys
+/// of the given algorithm
+pub(crate) fn signature_algorithm_der(algorithm: AsymmetricAlg) -> Result<Vec<u8>, X509Error> {
+    Ok(match algorithm {
+        AsymmetricAlg::Ed25519 => der::sequence(&[&der::oid(OID_ED25519)]),
+        #[cfg(feature = "rsa")]
+        AsymmetricAlg::RSA_2048 | AsymmetricAlg::RSA_3072 | AsymmetricAlg::RSA_4096 => {
+            der::sequence(&[&der::oid(OID_SHA256_WITH_RSA_ENCRYPTION), &der::null()])
+        }
+        // ECDSA keys use the digest picked by `AsymmetricAlg::ecdsa_alg`, as
+        // `sign` does
+        other => match other.ecdsa_alg() {
+            Some(EcdsaAlg::SHA256) => der::sequence(&[&der::oid(OID_ECDSA_WITH_SHA256)]),
+            Some(EcdsaAlg::SHA384) => der::sequence(&[&der::oid(OID_ECDSA_WITH_SHA384)]),
+            Some(EcdsaAlg::SHA512) => der::sequence(&[&der::oid(OID_ECDSA_WITH_SHA512)]),
+            _ => fail!(
+                AlgorithmUnsupported,
+                "can't sign X.509 structures with {:?} keys",
+                other
+            ),
+        },
+    })
+}
+
+/// Sign the given DER-encoded "to be signed" structure with the given key,
+/// returning the signature as it appears in the signed structure's BIT
+/// STRING (i.e. ASN.1 DER for ECDSA, raw bytes for Ed25519 and RSA)
+pub(crate) fn sign(
+    client: &mut Client,
+    key_id: ObjectId,
+    algorithm: AsymmetricAlg,
+    tbs: &[u8],
+) -> Result<Vec<u8>, X509Error> {
+    let digest = match algorithm {
+        AsymmetricAlg::Ed25519 => return Ok(client.sign_ed25519(key_id, tbs)?.as_ref().to_vec()),
+        #[cfg(feature = "rsa")]
+        AsymmetricAlg::RSA_2048 | AsymmetricAlg::RSA_3072 | AsymmetricAlg::RSA_4096 => {
+            return Ok(client.sign_rsa_pkcs1v15_sha256(key_id, tbs)?.into_vec())
+        }
+        other => other
+            .ecdsa_alg()
+            .and_then(|ecdsa_alg| ecdsa_alg.digest(tbs))
+            .ok_or_else(|| {
+                err!(
+                    AlgorithmUnsupported,
+                    "can't sign X.509 structures with {:?} keys",
+                    other
+                )
+            })?,
+    };
+
+    Ok(client.sign_ecdsa(key_id, digest)?.into_vec())
+}
+
+/// Verify a signature made by the key with the given DER-encoded
+/// `SubjectPublicKeyInfo`, using the signature algorithm identified by the
+/// given DER-encoded `AlgorithmIdentifier`
+pub(crate) fn verify(
+    spki: &[u8],
+    algorithm_id: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), X509Error> {
+    let (key_algorithm_id, public_key) =
+        parse_spki(spki).ok_or_else(|| err!(DerInvalid, "malformed SubjectPublicKeyInfo"))?;
+
+    let algorithm = verification_algorithm(key_algorithm_id, algorithm_id)
+        .ok_or_else(|| err!(AlgorithmUnsupported, "unsupported signature algorithm"))?;
+
+    ring_signature::verify(
+        algorithm,
+        untrusted::Input::from(public_key),
+        untrusted::Input::from(message),
+        untrusted::Input::from(signature),
+    )
+    .map_err(|_| err!(SignatureInvalid, "signature verification failed"))
+}
+
+/// Split a DER-encoded `SubjectPublicKeyInfo` into its `AlgorithmIdentifier`
+/// and the public key (i.e. the contents of the subjectPublicKey BIT STRING)
+pub(crate) fn parse_spki(spki: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (spki, rest) = der::parse(spki)?;
+    let fields = der::elements(spki.value)?;
+
+    if !rest.is_empty()
+        || spki.tag != der::SEQUENCE
+        || fields.len() != 2
+        || fields[1].tag != der::BIT_STRING
+        || fields[1].value.first() != Some(&0)
+    {
+        return None;
+    }
+
+    Some((fields[0].encoded, &fields[1].value[1..]))
+}
+
+/// Find the *ring* algorithm for verifying signatures with the given
+/// signature algorithm by keys with the given key algorithm
+fn verification_algorithm(
+    key_algorithm_id: &[u8],
+    algorithm_id: &[u8],
+) -> Option<&'static dyn VerificationAlgorithm> {
+    let is_ec_key = |curve: AsymmetricAlg| {
+        curve_oid(curve).map_or(false, |oid| {
+            key_algorithm_id == &der::sequence(&[&der::oid(OID_EC_PUBLIC_KEY), &der::oid(oid)])[..]
+        })
+    };
+    let algorithm = |oid: &[u64]| der::sequence(&[&der::oid(oid)]);
+    let rsa_algorithm = |oid: &[u64]| der::sequence(&[&der::oid(oid), &der::null()]);
+
+    if algorithm_id == &algorithm(OID_ED25519)[..] {
+        Some(&ring_signature::ED25519)
+    } else if algorithm_id == &rsa_algorithm(OID_SHA256_WITH_RSA_ENCRYPTION)[..] {
+        Some(&ring_signature::RSA_PKCS1_2048_8192_SHA256)
+    } else if algorithm_id == &rsa_algorithm(OID_SHA384_WITH_RSA_ENCRYPTION)[..] {
+        Some(&ring_signature::RSA_PKCS1_2048_8192_SHA384)
+    } else if algorithm_id == &rsa_algorithm(OID_SHA512_WITH_RSA_ENCRYPTION)[..] {
+        Some(&ring_signature::RSA_PKCS1_2048_8192_SHA512)
+    } else if is_ec_key(AsymmetricAlg::EC_P256) {
+        if algorithm_id == &algorithm(OID_ECDSA_WITH_SHA256)[..] {
+            Some(&ring_signature::ECDSA_P256_SHA256_ASN1)
+        } else if algorithm_id == &algorithm(OID_ECDSA_WITH_SHA384)[..] {
+            Some(&ring_signature::ECDSA_P256_SHA384_ASN1)
+        } else {
+            None
+        }
+    } else if is_ec_key(AsymmetricAlg::EC_P384) {
+        if algorithm_id == &algorithm(OID_ECDSA_WITH_SHA256)[..] {
+            Some(&ring_signature::ECDSA_P384_SHA256_ASN1)
+        } else if algorithm_id == &algorithm(OID_ECDSA_WITH_SHA384)[..] {
+            Some(&ring_signature::ECDSA_P384_SHA384_ASN1)
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
@@ -0,0 +1,144 @@
+//! Storing certificates in the HSM as opaque objects.
+//!
+//! Certificates are stored under the same object ID as the asymmetric key
+//! they certify, using the `X509_CERT` opaque algorithm.
+
+use super::signing::parse_spki;
+use super::{Certificate, X509Error, X509ErrorKind::*};
+use der;
+use {Capability, Client, Domain, ObjectId, ObjectLabel, OpaqueAlg};
+
+/// Store a certificate for the asymmetric key with the given ID, as an
+/// opaque object with the same ID.
+///
+/// The certificate's public key must match the key's.
+pub fn put_certificate(
+    client: &mut Client,
+    key_id: ObjectId,
+    label: ObjectLabel,
+    domains: Domain,
+    certificate: &Certificate,
+) -> Result<ObjectId, X509Error> {
+    check_public_key(client, key_id, certificate)?;
+
+    Ok(client.put_opaque(
+        key_id,
+        label,
+        domains,
+        Capability::default(),
+        OpaqueAlg::X509_CERT,
+        certificate.as_der(),
+    )?)
+}
+
+/// Get the certificate stored for the asymmetric key with the given ID,
+/// checking it's well-formed and its public key matches the key's.
+pub fn get_certificate(client: &mut Client, key_id: ObjectId) -> Result<Certificate, X509Error> {
+    let certificate = Certificate::from_der(client.get_opaque(key_id)?)?;
+    check_public_key(client, key_id, &certificate)?;
+    Ok(certificate)
+}
+
+/// Ensure the certificate's public key (i.e. its algorithm and public key
+/// bits) matches the public key of the given asymmetric key
+fn check_public_key(
+    client: &mut Client,
+    key_id: ObjectId,
+    certificate: &Certificate,
+) -> Result<(), X509Error> {
+    let certificate_key = certificate
+        .subject_public_key_info()
+        .and_then(parse_public_key)
+        .ok_or_else(|| err!(DerInvalid, "malformed certificate"))?;
+
+    let spki = client.get_pubkey(key_id)?.to_spki_der();
+    let key = parse_public_key(&spki)
+        .ok_or_else(|| err!(DerInvalid, "malformed public key for key 0x{:04x}", key_id))?;
+
+    ensure!(
+        certificate_key == key,
+        PublicKeyMismatch,
+        "certificate doesn't match public key for key 0x{:04x}",
+        key_id
+    );
+
+    Ok(())
+}
+
+/// Parse a DER-encoded `SubjectPublicKeyInfo` into the elements of its
+/// `AlgorithmIdentifier` (the algorithm OID and, for EC keys, the curve OID)
+/// and the public key bits.
+///
+/// NULL parameters are skipped, since they may be omitted for RSA keys.
+fn parse_public_key(spki: &[u8]) -> Option<(Vec<&[u8]>, &[u8])> {
+    let (algorithm_id, public_key) = parse_spki(spki)?;
+    let (algorithm_id, _) = der::parse(algorithm_id)?;
+
+    if algorithm_id.tag != der::SEQUENCE {
+        return None;
+    }
+
+    let fields = der::elements(algorithm_id.value)?;
+
+    if fields.first().map(|field| field.tag) != Some(der::OBJECT_IDENTIFIER) {
+        return None;
+    }
+
+    let algorithm = fields
+        .iter()
+        .filter(|field| field.tag != der::NULL)
+        .map(|field| field.encoded)
+        .collect();
+
+    Some((algorithm, public_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::get_pubkey::PublicKey;
+    use AsymmetricAlg;
+
+    #[test]
+    fn rsa_null_parameters_ignored() {
+        let spki = PublicKey {
+            algorithm: AsymmetricAlg::RSA_2048,
+            bytes: vec![0xc5; 256],
+        }
+        .to_spki_der();
+
+        let (_, public_key) = parse_spki(&spki).unwrap();
+
+        // rsaEncryption without the NULL parameters
+        let rsa_encryption = der::oid(&[1, 2, 840, 113_549, 1, 1, 1]);
+        let without_null = der::sequence(&[
+            &der::sequence(&[&rsa_encryption]),
+            &der::bit_string(public_key),
+        ]);
+
+        assert_eq!(
+            parse_public_key(&spki).unwrap(),
+            parse_public_key(&without_null).unwrap()
+        );
+    }
+
+    #[test]
+    fn ec_curves_compared() {
+        let p256 = PublicKey {
+            algorithm: AsymmetricAlg::EC_P256,
+            bytes: vec![0x42; 64],
+        }
+        .to_spki_der();
+
+        let k256 = PublicKey {
+            algorithm: AsymmetricAlg::EC_K256,
+            bytes: vec![0x42; 64],
+        }
+        .to_spki_der();
+
+        assert_ne!(
+            parse_public_key(&p256).unwrap(),
+            parse_public_key(&k256).unwrap()
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Certificate validity periods
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use der;
+
+/// Number of seconds in a day
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Period during which a certificate is valid
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Validity {
+    /// Start of the validity period
+    pub not_before: SystemTime,
+
+    /// End of the validity period
+    pub not_after: SystemTime,
+}
+
+impl Validity {
+    /// Create a validity period between the given times
+    pub fn new(not_before: SystemTime, not_after: SystemTime) -> Self {
+        Self {
+            not_before,
+            not_after,
+        }
+    }
+
+    /// Create a validity period starting now and lasting for the given
+    /// duration
+    pub fn from_now(duration: Duration) -> Self {
+        let now = SystemTime::now();
+        Self::new(now, now + duration)
+    }
+
+    /// Serialize as a DER-encoded `Validity`
+    pub(crate) fn to_der(self) -> Vec<u8> {
+        der::sequence(&[&time_der(self.not_before), &time_der(self.not_after)])
+    }
+}
+
+/// Serialize a time as a UTCTime for years 1950 through 2049 and as a
+/// GeneralizedTime otherwise, as required by RFC 5280 (times before the Unix
+/// epoch are clamped to it)
+fn time_der(time: SystemTime) -> Vec<u8> {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    let (year, month, day) = civil_from_days(secs / SECONDS_PER_DAY);
+    let secs_of_day = secs % SECONDS_PER_DAY;
+
+    let time_of_day = format!(
+        "{:02}{:02}{:02}{:02}{:02}Z",
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    );
+
+    if year < 2050 {
+        let utc_time = format!("{:02}{}", year % 100, time_of_day);
+        der::tlv(der::UTC_TIME, utc_time.as_bytes())
+    } else {
+        let generalized_time = format!("{:04}{}", year, time_of_day);
+        der::tlv(der::GENERALIZED_TIME, generalized_time.as_bytes())
+    }
+}
+
+/// Convert days since the Unix epoch into a (year, month, day) date in the
+/// proleptic Gregorian calendar (using Howard Hinnant's algorithm)
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_time_test() {
+        // 2019-03-01 12:34:56 UTC
+        let time = UNIX_EPOCH + Duration::from_secs(1_551_443_696);
+        assert_eq!(time_der(time), b"\x17\x0d190301123456Z".to_vec());
+    }
+
+    #[test]
+    fn generalized_time_test() {
+        // 2050-01-01 00:00:00 UTC
+        let time = UNIX_EPOCH + Duration::from_secs(2_524_608_000);
+        assert_eq!(time_der(time), b"\x18\x0f20500101000000Z".to_vec());
+    }
+}
@@ -0,0 +1,151 @@
+use yubihsm::client::ClientErrorKind;
+use yubihsm::{
+    AsymmetricAuthKey, AuthAlg, AuthKey, Capability, Client, Credentials, Domain, MockHsm,
+    ObjectId, ObjectType,
+};
+
+/// ID of the asymmetric auth key to authenticate with
+const ASYMMETRIC_AUTH_KEY_ID: ObjectId = 2;
+
+/// Put an asymmetric auth key for the given secret key into the HSM
+fn put_asymmetric_auth_key(hsm: &MockHsm, auth_key: &AsymmetricAuthKey) {
+    let mut client = Client::open(hsm.clone(), Credentials::default(), true).unwrap();
+
+    client
+        .put_asymmetric_auth_key(
+            ASYMMETRIC_AUTH_KEY_ID,
+            "asymmetric auth key".into(),
+            Domain::all(),
+            Capability::all(),
+            Capability::all(),
+            &auth_key.public_key()[..],
+        )
+        .unwrap();
+}
+
+#[test]
+fn asymmetric_auth_test() {
+    let hsm = MockHsm::new();
+    let auth_key = AsymmetricAuthKey::generate();
+    put_asymmetric_auth_key(&hsm, &auth_key);
+
+    let mut client = Client::open(
+        hsm,
+        Credentials::asymmetric_unpinned(ASYMMETRIC_AUTH_KEY_ID, auth_key),
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+
+    let auth_key_info = client
+        .get_object_info(ASYMMETRIC_AUTH_KEY_ID, ObjectType::AuthKey)
+        .unwrap();
+
+    assert_eq!(auth_key_info.algorithm, AuthAlg::EC_P256_YUBICO.into());
+}
+
+#[test]
+fn asymmetric_auth_wrong_key_test() {
+    let hsm = MockHsm::new();
+    put_asymmetric_auth_key(&hsm, &AsymmetricAuthKey::generate());
+
+    let result = Client::open(
+        hsm,
+        Credentials::asymmetric_unpinned(ASYMMETRIC_AUTH_KEY_ID, AsymmetricAuthKey::generate()),
+        true,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn rotate_to_asymmetric_auth_key_test() {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+
+    client
+        .rotate_auth_key(
+            1,
+            "asymmetric auth key".into(),
+            Credentials::asymmetric_unpinned(ASYMMETRIC_AUTH_KEY_ID, AsymmetricAuthKey::generate()),
+        )
+        .unwrap();
+
+    client.ping().unwrap();
+    assert!(!client.has_default_auth_key().unwrap());
+}
+
+#[test]
+fn asymmetric_auth_pinned_device_key_test() {
+    let hsm = MockHsm::new();
+    let auth_key = AsymmetricAuthKey::generate();
+    put_asymmetric_auth_key(&hsm, &auth_key);
+
+    let device_public_key = Client::new(hsm.clone(), Credentials::default())
+        .unwrap()
+        .device_public_key()
+        .unwrap();
+
+    let mut client = Client::open(
+        hsm,
+        Credentials::asymmetric(ASYMMETRIC_AUTH_KEY_ID, auth_key, &device_public_key),
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+}
+
+#[test]
+fn asymmetric_auth_wrong_device_key_test() {
+    let hsm = MockHsm::new();
+    let auth_key = AsymmetricAuthKey::generate();
+    put_asymmetric_auth_key(&hsm, &auth_key);
+
+    // Pin the device key of a different HSM
+    let other_device_public_key = Client::new(MockHsm::new(), Credentials::default())
+        .unwrap()
+        .device_public_key()
+        .unwrap();
+
+    let credentials =
+        Credentials::asymmetric(ASYMMETRIC_AUTH_KEY_ID, auth_key, &other_device_public_key);
+
+    match Client::open(hsm, credentials, true) {
+        Ok(_) => panic!("opened session with unexpected device key"),
+        Err(e) => assert_eq!(e.kind(), ClientErrorKind::AuthFail),
+    }
+}
+
+#[test]
+fn asymmetric_auth_with_symmetric_key_test() {
+    // The default auth key in slot 1 is symmetric
+    let result = Client::open(
+        MockHsm::new(),
+        Credentials::asymmetric_unpinned(1, AsymmetricAuthKey::generate()),
+        true,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn asymmetric_auth_change_auth_key_test() {
+    let hsm = MockHsm::new();
+    let auth_key = AsymmetricAuthKey::generate();
+    put_asymmetric_auth_key(&hsm, &auth_key);
+
+    let mut client = Client::open(
+        hsm,
+        Credentials::asymmetric_unpinned(ASYMMETRIC_AUTH_KEY_ID, auth_key),
+        true,
+    )
+    .unwrap();
+
+    match client.change_auth_key(AuthKey::random()) {
+        Ok(_) => panic!("changed asymmetric auth key to a symmetric one"),
+        Err(e) => assert_eq!(e.kind(), ClientErrorKind::InvalidArgument),
+    }
+
+    assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+}
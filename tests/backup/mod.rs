@@ -0,0 +1,169 @@
+use yubihsm::backup::{self, Archive};
+use yubihsm::{
+    AsymmetricAlg, Capability, Client, Credentials, Domain, MockHsm, ObjectId, ObjectType, WrapAlg,
+};
+
+/// ID of the wrap key used for backups
+const TEST_WRAP_KEY_ID: ObjectId = 200;
+
+/// ID of a key without the `EXPORT_UNDER_WRAP` capability
+const TEST_NONEXPORTABLE_KEY_ID: ObjectId = 201;
+
+/// Create a MockHsm client with a wrap key, an exportable key, and a key
+/// which can't be exported
+fn create_test_client() -> Client {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+
+    client
+        .put_wrap_key(
+            TEST_WRAP_KEY_ID,
+            "backup wrap key".into(),
+            Domain::all(),
+            Capability::EXPORT_WRAPPED | Capability::IMPORT_WRAPPED,
+            Capability::all(),
+            WrapAlg::AES256_CCM,
+            vec![0x42u8; 32],
+        )
+        .unwrap();
+
+    client
+        .generate_asymmetric_key(
+            ::TEST_KEY_ID,
+            ::TEST_KEY_LABEL.into(),
+            ::TEST_DOMAINS,
+            Capability::ASYMMETRIC_SIGN_EDDSA | Capability::EXPORT_UNDER_WRAP,
+            AsymmetricAlg::Ed25519,
+        )
+        .unwrap();
+
+    client
+        .generate_asymmetric_key(
+            TEST_NONEXPORTABLE_KEY_ID,
+            ::TEST_KEY_LABEL.into(),
+            ::TEST_DOMAINS,
+            Capability::ASYMMETRIC_SIGN_EDDSA,
+            AsymmetricAlg::Ed25519,
+        )
+        .unwrap();
+
+    client
+}
+
+#[test]
+fn backup_export_all_test() {
+    let mut client = create_test_client();
+    let report = backup::export_all(&mut client, TEST_WRAP_KEY_ID).unwrap();
+
+    assert!(report.archive.entries.iter().any(|entry| {
+        entry.object_type == ObjectType::AsymmetricKey && entry.object_id == ::TEST_KEY_ID
+    }));
+
+    for &(object_type, object_id) in &[
+        (ObjectType::WrapKey, TEST_WRAP_KEY_ID),
+        (ObjectType::AsymmetricKey, TEST_NONEXPORTABLE_KEY_ID),
+    ] {
+        assert!(report
+            .skipped
+            .iter()
+            .any(|skipped| skipped.object_type == object_type && skipped.object_id == object_id));
+    }
+
+    assert!(report
+        .archive
+        .entries
+        .iter()
+        .all(|entry| entry.verify_digest()));
+
+    let archive: Archive = report.archive.to_string().parse().unwrap();
+    assert_eq!(archive.to_string(), report.archive.to_string());
+}
+
+#[test]
+fn backup_restore_test() {
+    let mut client = create_test_client();
+    let mut archive = backup::export_all(&mut client, TEST_WRAP_KEY_ID)
+        .unwrap()
+        .archive;
+
+    // Only restore the key we're about to delete
+    archive
+        .entries
+        .retain(|entry| entry.object_type == ObjectType::AsymmetricKey);
+
+    let pubkey = client.get_pubkey(::TEST_KEY_ID).unwrap().bytes;
+    client
+        .delete_object(::TEST_KEY_ID, ObjectType::AsymmetricKey)
+        .unwrap();
+
+    let report = backup::restore(&mut client, &archive).unwrap();
+    assert!(report.is_complete());
+    assert_eq!(
+        report.restored,
+        vec![(ObjectType::AsymmetricKey, ::TEST_KEY_ID)]
+    );
+
+    assert_eq!(client.get_pubkey(::TEST_KEY_ID).unwrap().bytes, pubkey);
+}
+
+#[test]
+fn backup_restore_corrupted_test() {
+    let mut client = create_test_client();
+    let mut archive = backup::export_all(&mut client, TEST_WRAP_KEY_ID)
+        .unwrap()
+        .archive;
+
+    archive
+        .entries
+        .retain(|entry| entry.object_type == ObjectType::AsymmetricKey);
+
+    archive.entries[0].wrapped[0] ^= 0xff;
+
+    let report = backup::restore(&mut client, &archive).unwrap();
+    assert!(report.restored.is_empty());
+    assert_eq!(report.failed.len(), 1);
+}
+
+#[test]
+fn backup_archive_version_test() {
+    let archive = "# yubihsm.rs backup archive v2\nwrap_key=0x00c8\n";
+    assert!(archive.parse::<Archive>().is_err());
+}
+
+#[test]
+fn backup_restore_manifest_mismatch_test() {
+    let mut client = create_test_client();
+    let mut archive = backup::export_all(&mut client, TEST_WRAP_KEY_ID)
+        .unwrap()
+        .archive;
+
+    archive
+        .entries
+        .retain(|entry| entry.object_type == ObjectType::AsymmetricKey);
+
+    let entry = archive.entries[0].clone();
+    assert_eq!(entry.algorithm, AsymmetricAlg::Ed25519.into());
+    assert!(entry.public_key.is_some());
+
+    let mut wrong_capabilities = entry.clone();
+    wrong_capabilities.capabilities = Capability::ASYMMETRIC_SIGN_EDDSA;
+
+    let mut wrong_domains = entry.clone();
+    wrong_domains.domains = Domain::all();
+
+    let mut wrong_public_key = entry;
+    wrong_public_key.public_key.as_mut().unwrap()[0] ^= 0xff;
+
+    for tampered in vec![wrong_capabilities, wrong_domains, wrong_public_key] {
+        // The previous iteration's import is left in place when it fails
+        // verification, so make room for the next one
+        client
+            .delete_object(::TEST_KEY_ID, ObjectType::AsymmetricKey)
+            .unwrap();
+
+        archive.entries = vec![tampered];
+
+        let report = backup::restore(&mut client, &archive).unwrap();
+        assert!(report.restored.is_empty());
+        assert!(report.failed[0].reason.starts_with("imported object has"));
+    }
+}
@@ -0,0 +1,61 @@
+use yubihsm::{AuthAlg, AuthKey, Capability, Client, Credentials, MockHsm, ObjectType};
+
+use {TEST_DOMAINS, TEST_KEY_ID, TEST_KEY_LABEL, TEST_MESSAGE};
+
+/// Password for the auth key after it's been changed
+const NEW_PASSWORD: &[u8] = b"yubihsm.rs changed auth key";
+
+/// Change the secret of an auth key in place
+#[test]
+fn change_auth_key_test() {
+    let hsm = MockHsm::new();
+    let mut client = Client::open(hsm.clone(), Credentials::default(), true).unwrap();
+    let capabilities = Capability::CHANGE_AUTHKEY | Capability::GET_RANDOMNESS;
+
+    client
+        .put_auth_key(
+            TEST_KEY_ID,
+            TEST_KEY_LABEL.into(),
+            TEST_DOMAINS,
+            capabilities,
+            Capability::empty(),
+            AuthAlg::YUBICO_AES,
+            AuthKey::derive_from_password(TEST_MESSAGE),
+        )
+        .unwrap_or_else(|err| panic!("error putting auth key: {}", err));
+
+    let mut test_key_client = Client::open(
+        hsm.clone(),
+        Credentials::from_password(TEST_KEY_ID, TEST_MESSAGE),
+        true,
+    )
+    .unwrap();
+
+    test_key_client
+        .change_auth_key(AuthKey::derive_from_password(NEW_PASSWORD))
+        .unwrap_or_else(|err| panic!("error changing auth key: {}", err));
+
+    assert!(Client::open(
+        hsm.clone(),
+        Credentials::from_password(TEST_KEY_ID, TEST_MESSAGE),
+        true
+    )
+    .is_err());
+
+    let mut new_key_client = Client::open(
+        hsm,
+        Credentials::from_password(TEST_KEY_ID, NEW_PASSWORD),
+        true,
+    )
+    .unwrap();
+
+    new_key_client.get_pseudo_random(32).unwrap();
+
+    let object_info = client
+        .get_object_info(TEST_KEY_ID, ObjectType::AuthKey)
+        .unwrap_or_else(|err| panic!("error getting object info: {}", err));
+
+    assert_eq!(object_info.capabilities, capabilities);
+    assert_eq!(object_info.domains, TEST_DOMAINS);
+    assert_eq!(&object_info.label.to_string().unwrap(), TEST_KEY_LABEL);
+}
@@ -13,16 +13,12 @@ fn delete_object_test() {
     );
 
     // The first request to delete should succeed because the object exists
-    assert!(
-        client
-            .delete_object(TEST_KEY_ID, ObjectType::AsymmetricKey)
-            .is_ok()
-    );
+    assert!(client
+        .delete_object(TEST_KEY_ID, ObjectType::AsymmetricKey)
+        .is_ok());
 
     // The second request to delete should fail because it's already deleted
-    assert!(
-        client
-            .delete_object(TEST_KEY_ID, ObjectType::AsymmetricKey)
-            .is_err()
-    );
+    assert!(client
+        .delete_object(TEST_KEY_ID, ObjectType::AsymmetricKey)
+        .is_err());
 }
@@ -19,7 +19,8 @@ fn hmac_key_test() {
             TEST_DOMAINS,
             capabilities,
             algorithm,
-        ).unwrap_or_else(|err| panic!("error generating wrap key: {}", err));
+        )
+        .unwrap_or_else(|err| panic!("error generating wrap key: {}", err));
 
     assert_eq!(key_id, TEST_KEY_ID);
 
@@ -24,7 +24,8 @@ fn wrap_key_test() {
             capabilities,
             delegated_capabilities,
             algorithm,
-        ).unwrap_or_else(|err| panic!("error generating wrap key: {}", err));
+        )
+        .unwrap_or_else(|err| panic!("error generating wrap key: {}", err));
 
     assert_eq!(key_id, TEST_KEY_ID);
 
@@ -0,0 +1,30 @@
+use yubihsm::{AsymmetricAlg, Capability};
+
+use {generate_asymmetric_key, TEST_KEY_ID};
+
+/// DER prefix of an Ed25519 `SubjectPublicKeyInfo` (RFC 8410)
+const ED25519_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Get an Ed25519 public key and serialize it as a `SubjectPublicKeyInfo`
+#[test]
+fn ed25519_pubkey_spki_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let public_key = client
+        .get_pubkey(TEST_KEY_ID)
+        .unwrap_or_else(|err| panic!("error getting public key: {}", err));
+
+    assert_eq!(public_key.algorithm, AsymmetricAlg::Ed25519);
+
+    let spki = public_key.to_spki_der();
+    assert_eq!(&spki[..ED25519_SPKI_PREFIX.len()], ED25519_SPKI_PREFIX);
+    assert_eq!(&spki[ED25519_SPKI_PREFIX.len()..], public_key.as_slice());
+}
@@ -18,10 +18,7 @@ fn list_objects_test() {
         .unwrap_or_else(|err| panic!("error listing objects: {}", err));
 
     // Look for the asymmetric key we just generated
-    assert!(
-        objects
-            .iter()
-            .find(|i| i.object_id == TEST_KEY_ID && i.object_type == ObjectType::AsymmetricKey)
-            .is_some()
-    );
+    assert!(objects
+        .iter()
+        .any(|i| i.object_id == TEST_KEY_ID && i.object_type == ObjectType::AsymmetricKey));
 }
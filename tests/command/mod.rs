@@ -3,6 +3,8 @@
 #[cfg(not(feature = "mockhsm"))]
 pub mod attest_asymmetric;
 pub mod blink;
+#[cfg(feature = "mockhsm")]
+pub mod change_auth_key;
 pub mod delete_object;
 pub mod device_info;
 pub mod export_wrapped;
@@ -13,6 +15,7 @@ pub mod get_logs;
 pub mod get_object_info;
 pub mod get_option;
 pub mod get_pseudo_random;
+pub mod get_pubkey;
 pub mod list_objects;
 pub mod put_asymmetric_key;
 pub mod put_auth_key;
@@ -24,5 +27,10 @@ pub mod reset;
 #[cfg(not(feature = "mockhsm"))]
 pub mod sign_ecdsa;
 pub mod sign_eddsa;
+// TODO: MockHsm RSA support
+#[cfg(all(feature = "rsa", not(feature = "mockhsm")))]
+pub mod sign_rsa_pss;
+#[cfg(feature = "secp256k1")]
+pub mod sign_secp256k1;
 pub mod storage_status;
 pub mod verify_hmac;
@@ -23,7 +23,8 @@ fn put_auth_key() {
             delegated_capabilities,
             algorithm,
             new_auth_key,
-        ).unwrap_or_else(|err| panic!("error putting auth key: {}", err));
+        )
+        .unwrap_or_else(|err| panic!("error putting auth key: {}", err));
 
     assert_eq!(key_id, TEST_KEY_ID);
 
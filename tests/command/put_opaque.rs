@@ -17,7 +17,8 @@ fn opaque_object_test() {
             Capability::default(),
             OpaqueAlg::DATA,
             TEST_MESSAGE,
-        ).unwrap_or_else(|err| panic!("error putting opaque object: {}", err));
+        )
+        .unwrap_or_else(|err| panic!("error putting opaque object: {}", err));
 
     assert_eq!(object_id, TEST_KEY_ID);
 
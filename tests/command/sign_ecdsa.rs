@@ -38,5 +38,6 @@ fn generated_nistp256_key_test() {
         untrusted::Input::from(&pubkey),
         untrusted::Input::from(TEST_MESSAGE),
         untrusted::Input::from(signature.as_ref()),
-    ).unwrap();
+    )
+    .unwrap();
 }
@@ -59,5 +59,6 @@ fn generated_key_test() {
         untrusted::Input::from(pubkey.bytes.as_ref()),
         untrusted::Input::from(TEST_MESSAGE),
         untrusted::Input::from(signature.as_ref()),
-    ).unwrap();
+    )
+    .unwrap();
 }
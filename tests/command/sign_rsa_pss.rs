@@ -0,0 +1,42 @@
+use ring;
+use untrusted;
+use yubihsm::{AsymmetricAlg, Capability};
+
+use {generate_asymmetric_key, TEST_KEY_ID, TEST_MESSAGE};
+
+/// Public exponent of RSA keys generated by the HSM (65537)
+const RSA_PUBLIC_EXPONENT: [u8; 3] = [0x01, 0x00, 0x01];
+
+/// Test RSASSA-PSS signatures (using RSA-2048 and SHA-256)
+#[test]
+fn generated_rsa2048_key_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::RSA_2048,
+        Capability::ASYMMETRIC_SIGN_PSS,
+    );
+
+    let pubkey_response = client
+        .get_pubkey(TEST_KEY_ID)
+        .unwrap_or_else(|err| panic!("error getting public key: {}", err));
+
+    assert_eq!(pubkey_response.algorithm, AsymmetricAlg::RSA_2048);
+    assert_eq!(pubkey_response.bytes.len(), 256);
+
+    let signature = client
+        .sign_rsa_pss_sha256(TEST_KEY_ID, TEST_MESSAGE)
+        .unwrap_or_else(|err| panic!("error performing RSASSA-PSS signature: {}", err));
+
+    ring::signature::primitive::verify_rsa(
+        &ring::signature::RSA_PSS_2048_8192_SHA256,
+        (
+            untrusted::Input::from(pubkey_response.bytes.as_ref()),
+            untrusted::Input::from(&RSA_PUBLIC_EXPONENT),
+        ),
+        untrusted::Input::from(TEST_MESSAGE),
+        untrusted::Input::from(signature.as_ref()),
+    )
+    .unwrap();
+}
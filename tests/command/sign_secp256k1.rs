@@ -0,0 +1,26 @@
+use yubihsm::client::ClientErrorKind;
+use yubihsm::{AsymmetricAlg, Capability};
+
+use {generate_asymmetric_key, TEST_KEY_ID};
+
+/// Keys which aren't secp256k1 keys are rejected before signing
+#[test]
+fn wrong_key_type_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let digest = [0x42u8; 32];
+
+    let err = client.sign_secp256k1(TEST_KEY_ID, &digest).unwrap_err();
+    assert_eq!(err.kind(), ClientErrorKind::InvalidArgument);
+
+    let err = client
+        .sign_secp256k1_recoverable(TEST_KEY_ID, &digest)
+        .unwrap_err();
+    assert_eq!(err.kind(), ClientErrorKind::InvalidArgument);
+}
@@ -21,7 +21,8 @@ fn hmac_test_vectors() {
                 capabilities,
                 algorithm,
                 vector.key,
-            ).unwrap_or_else(|err| panic!("error putting HMAC key: {}", err));
+            )
+            .unwrap_or_else(|err| panic!("error putting HMAC key: {}", err));
 
         assert_eq!(key_id, TEST_KEY_ID);
 
@@ -31,19 +32,15 @@ fn hmac_test_vectors() {
 
         assert_eq!(tag.as_ref(), vector.tag);
 
-        assert!(
-            client
-                .verify_hmac(TEST_KEY_ID, vector.msg, vector.tag)
-                .is_ok()
-        );
+        assert!(client
+            .verify_hmac(TEST_KEY_ID, vector.msg, vector.tag)
+            .is_ok());
 
         let mut bad_tag = Vec::from(vector.tag);
         bad_tag[0] ^= 1;
 
-        assert!(
-            client
-                .verify_hmac(TEST_KEY_ID, vector.msg, bad_tag)
-                .is_err()
-        );
+        assert!(client
+            .verify_hmac(TEST_KEY_ID, vector.msg, bad_tag)
+            .is_err());
     }
 }
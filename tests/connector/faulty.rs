@@ -0,0 +1,87 @@
+use yubihsm::client::ClientErrorKind;
+use yubihsm::connector::{ConnectionErrorKind, Fault, FaultPlan, FaultyConnector};
+use yubihsm::{Client, ClientBuilder, Credentials, MockHsm, RetryPolicy};
+
+/// Create a client which reaches the MockHsm through a `FaultyConnector`
+fn create_client(plan: FaultPlan, retry_policy: RetryPolicy) -> Client {
+    ClientBuilder::new()
+        .connector(FaultyConnector::new(MockHsm::new(), plan))
+        .credentials(Credentials::default())
+        .retry_policy(retry_policy)
+        .build()
+        .unwrap()
+}
+
+/// Idempotent commands are retried on a new session after connection errors
+#[test]
+fn retry_after_connection_error_test() {
+    // Let the session handshake through, then fail the first command
+    let connector = FaultyConnector::new(
+        MockHsm::new(),
+        FaultPlan::scripted(vec![
+            None,
+            None,
+            Some(Fault::Error(ConnectionErrorKind::IoError)),
+        ]),
+    );
+
+    let handle = connector.handle();
+
+    let mut client = ClientBuilder::new()
+        .connector(connector)
+        .credentials(Credentials::default())
+        .retry_policy(RetryPolicy {
+            backoff_ms: 1,
+            ..RetryPolicy::new(2)
+        })
+        .open()
+        .unwrap();
+
+    assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+    assert_eq!(handle.injected(), 1);
+}
+
+/// Connection errors are surfaced when retrying is disabled
+#[test]
+fn dropped_response_test() {
+    let plan = FaultPlan::scripted(vec![None, None, Some(Fault::DropResponse)]);
+    let mut client = create_client(plan, RetryPolicy::default());
+
+    let err = client.echo(::TEST_MESSAGE).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ClientErrorKind::ConnectionError {
+            kind: ConnectionErrorKind::IoError
+        }
+    );
+}
+
+/// Corrupted responses fail MAC verification
+#[test]
+fn bit_flip_test() {
+    // Flip a bit in the first byte of the encrypted response to the command
+    let plan = FaultPlan::scripted(vec![None, None, Some(Fault::BitFlip(32))]);
+    let mut client = create_client(plan, RetryPolicy::default());
+
+    // Response MAC failures surface as protocol errors
+    let err = client.echo(::TEST_MESSAGE).unwrap_err();
+    assert_eq!(err.kind(), ClientErrorKind::ProtocolError);
+    assert_eq!(err.to_string(), "protocol error: R-MAC mismatch!");
+}
+
+/// Sessions aren't opened if the connector's health check fails
+#[test]
+fn healthcheck_failure_test() {
+    let plan = FaultPlan::new().fail_healthchecks(1);
+    let mut client = create_client(plan, RetryPolicy::default());
+
+    let err = client.connect().unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ClientErrorKind::ConnectionError {
+            kind: ConnectionErrorKind::ConnectionFailed
+        }
+    );
+
+    client.connect().unwrap();
+}
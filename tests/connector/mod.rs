@@ -0,0 +1,4 @@
+#[cfg(feature = "testing")]
+mod faulty;
+#[cfg(feature = "testing")]
+mod replay;
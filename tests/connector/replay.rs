@@ -0,0 +1,44 @@
+use std::env;
+use std::fs;
+use yubihsm::connector::{RecordingConnector, ReplayConnector};
+use yubihsm::{Client, Credentials, MockHsm};
+
+/// Record a session with the MockHsm, then replay it
+#[test]
+fn record_and_replay_test() {
+    let transcript_path = env::temp_dir().join("yubihsm-rs-replay-test.transcript");
+
+    {
+        let connector = RecordingConnector::create(MockHsm::new(), &transcript_path).unwrap();
+        let mut client = Client::open(connector, Credentials::default(), true).unwrap();
+        assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+    }
+
+    let connector = ReplayConnector::open(&transcript_path).unwrap();
+    fs::remove_file(&transcript_path).unwrap();
+
+    {
+        let mut client = Client::open(connector.clone(), Credentials::default(), true).unwrap();
+        assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+    }
+
+    assert_eq!(connector.remaining(), 0);
+}
+
+/// Requests which differ from the transcript are rejected
+#[test]
+fn replay_mismatch_test() {
+    let transcript_path = env::temp_dir().join("yubihsm-rs-replay-mismatch-test.transcript");
+
+    {
+        let connector = RecordingConnector::create(MockHsm::new(), &transcript_path).unwrap();
+        let mut client = Client::open(connector, Credentials::default(), true).unwrap();
+        client.echo(&b"recorded message"[..]).unwrap();
+    }
+
+    let connector = ReplayConnector::open(&transcript_path).unwrap();
+    fs::remove_file(&transcript_path).unwrap();
+
+    let mut client = Client::open(connector, Credentials::default(), true).unwrap();
+    assert!(client.echo(&b"different message"[..]).is_err());
+}
@@ -0,0 +1,148 @@
+use ring;
+use untrusted;
+use yubihsm::cose::{CoseKey, Mac0Builder, Sign1Builder};
+use yubihsm::{AsymmetricAlg, Capability, HmacAlg, ObjectType};
+
+use {
+    clear_test_key_slot, generate_asymmetric_key, TEST_DOMAINS, TEST_KEY_ID, TEST_KEY_LABEL,
+    TEST_MESSAGE,
+};
+
+/// Key ID to include in unprotected headers
+const TEST_KID: &[u8] = b"key-1";
+
+/// External data to authenticate along with the payload
+const TEST_EXTERNAL_AAD: &[u8] = b"aad";
+
+/// Encode a CBOR byte string (shorter than 256 bytes)
+fn bstr(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = if bytes.len() < 24 {
+        vec![0x40 | bytes.len() as u8]
+    } else {
+        vec![0x58, bytes.len() as u8]
+    };
+
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
+/// Sign a `COSE_Sign1` with an Ed25519 key and verify it
+#[test]
+fn ed25519_sign1_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let sign1 = Sign1Builder::new(TEST_MESSAGE)
+        .kid(TEST_KID)
+        .external_aad(TEST_EXTERNAL_AAD)
+        .sign(&mut client, TEST_KEY_ID)
+        .unwrap();
+
+    // Protected header: { 1 (alg): -8 (EdDSA) }
+    let protected = [0xa1, 0x01, 0x27];
+
+    // 18([protected, { 4 (kid): TEST_KID }, payload, signature])
+    let mut expected = vec![0xd2, 0x84];
+    expected.extend(bstr(&protected));
+    expected.extend([0xa1, 0x04]);
+    expected.extend(bstr(TEST_KID));
+    expected.extend(bstr(TEST_MESSAGE));
+    expected.extend([0x58, 0x40]);
+
+    let bytes = sign1.as_bytes();
+    assert_eq!(&bytes[..expected.len()], expected.as_slice());
+    assert_eq!(bytes.len(), expected.len() + 64);
+
+    // ["Signature1", protected, external_aad, payload]
+    let mut sig_structure = vec![0x84];
+    sig_structure.extend(b"\x6aSignature1");
+    sig_structure.extend(bstr(&protected));
+    sig_structure.extend(bstr(TEST_EXTERNAL_AAD));
+    sig_structure.extend(bstr(TEST_MESSAGE));
+
+    let pubkey = client.get_pubkey(TEST_KEY_ID).unwrap();
+
+    ring::signature::verify(
+        &ring::signature::ED25519,
+        untrusted::Input::from(pubkey.bytes.as_ref()),
+        untrusted::Input::from(&sig_structure[..]),
+        untrusted::Input::from(&bytes[expected.len()..]),
+    )
+    .unwrap();
+}
+
+/// Compute a `COSE_Mac0` with an HMAC key and check it against a locally
+/// computed tag
+#[test]
+fn hmac_mac0_test() {
+    let mut client = ::get_hsm_client();
+    let hmac_key = [0x0b; 32];
+
+    clear_test_key_slot(&mut client, ObjectType::HMACKey);
+
+    client
+        .put_hmac_key(
+            TEST_KEY_ID,
+            TEST_KEY_LABEL.into(),
+            TEST_DOMAINS,
+            Capability::HMAC_DATA,
+            HmacAlg::SHA256,
+            &hmac_key[..],
+        )
+        .unwrap();
+
+    let mac0 = Mac0Builder::new(TEST_MESSAGE)
+        .mac(&mut client, TEST_KEY_ID)
+        .unwrap();
+
+    // Protected header: { 1 (alg): 5 (HMAC 256/256) }
+    let protected = [0xa1, 0x01, 0x05];
+
+    // 17([protected, {}, payload, tag])
+    let mut expected = vec![0xd1, 0x84];
+    expected.extend(bstr(&protected));
+    expected.push(0xa0);
+    expected.extend(bstr(TEST_MESSAGE));
+    expected.extend([0x58, 0x20]);
+
+    // ["MAC0", protected, external_aad, payload]
+    let mut mac_structure = vec![0x84];
+    mac_structure.extend(b"\x64MAC0");
+    mac_structure.extend(bstr(&protected));
+    mac_structure.push(0x40);
+    mac_structure.extend(bstr(TEST_MESSAGE));
+
+    let key = ring::hmac::SigningKey::new(&ring::digest::SHA256, &hmac_key);
+    expected.extend(ring::hmac::sign(&key, &mac_structure).as_ref());
+
+    assert_eq!(mac0.as_bytes(), expected.as_slice());
+}
+
+/// Encode the public key of an Ed25519 key as a `COSE_Key`
+#[test]
+fn ed25519_cose_key_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let pubkey = client.get_pubkey(TEST_KEY_ID).unwrap();
+    let cose_key = CoseKey::from_public_key(&pubkey).unwrap().kid(TEST_KID);
+
+    // { 1 (kty): 1 (OKP), 2 (kid): TEST_KID, 3 (alg): -8 (EdDSA),
+    //   -1 (crv): 6 (Ed25519), -2 (x): public key }
+    let mut expected = vec![0xa5, 0x01, 0x01, 0x02];
+    expected.extend(bstr(TEST_KID));
+    expected.extend([0x03, 0x27, 0x20, 0x06, 0x21]);
+    expected.extend(bstr(&pubkey.bytes));
+
+    assert_eq!(cose_key.to_cbor(), expected);
+}
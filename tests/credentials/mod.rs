@@ -0,0 +1,168 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use yubihsm::client::ClientErrorKind;
+#[cfg(feature = "testing")]
+use yubihsm::connector::{Fault, FaultPlan, FaultyConnector};
+use yubihsm::credentials::{
+    CommandSource, CredentialsError, CredentialsErrorKind, EnvSource, FileSource,
+};
+#[cfg(feature = "testing")]
+use yubihsm::RetryPolicy;
+use yubihsm::{ClientBuilder, CredentialSource, Credentials, MockHsm};
+
+/// Password for the MockHsm's default auth key
+const DEFAULT_PASSWORD: &str = "password";
+
+/// Open a session with the MockHsm using the given source and echo a message
+fn echo_with_source<S: CredentialSource + 'static>(source: S) {
+    let mut client = ClientBuilder::new()
+        .connector(MockHsm::new())
+        .credential_source(source)
+        .open()
+        .unwrap();
+
+    assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+}
+
+/// Load the password from an environment variable
+#[test]
+fn env_source_test() {
+    env::set_var("YUBIHSM_TEST_PASSWORD", DEFAULT_PASSWORD);
+    echo_with_source(EnvSource::new(1, "YUBIHSM_TEST_PASSWORD"));
+}
+
+/// A missing environment variable is a load failure
+#[test]
+fn env_source_missing_var_test() {
+    let err = EnvSource::new(1, "YUBIHSM_TEST_MISSING_PASSWORD")
+        .credentials()
+        .err()
+        .unwrap();
+
+    assert_eq!(err.kind(), CredentialsErrorKind::LoadFailed);
+}
+
+/// Load the password from a file readable only by its owner
+#[test]
+fn file_source_test() {
+    let path = env::temp_dir().join("yubihsm-file-source-test");
+
+    {
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "{}", DEFAULT_PASSWORD).unwrap();
+    }
+
+    #[cfg(unix)]
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+    echo_with_source(FileSource::new(1, &path));
+    fs::remove_file(&path).unwrap();
+}
+
+/// Files readable by other users are rejected
+#[cfg(unix)]
+#[test]
+fn file_source_permissions_test() {
+    let path = env::temp_dir().join("yubihsm-file-source-permissions-test");
+
+    {
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "{}", DEFAULT_PASSWORD).unwrap();
+    }
+
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    let err = FileSource::new(1, &path).credentials().err().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(err.kind(), CredentialsErrorKind::PermissionsInvalid);
+}
+
+/// The permissions of a symlink's target (i.e. the file actually read) are
+/// checked
+#[cfg(unix)]
+#[test]
+fn file_source_symlink_test() {
+    let path = env::temp_dir().join("yubihsm-file-source-symlink-target");
+    let link = env::temp_dir().join("yubihsm-file-source-symlink-test");
+
+    {
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "{}", DEFAULT_PASSWORD).unwrap();
+    }
+
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+    let _ = fs::remove_file(&link);
+    symlink(&path, &link).unwrap();
+
+    let err = FileSource::new(1, &link).credentials().err().unwrap();
+    fs::remove_file(&link).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(err.kind(), CredentialsErrorKind::PermissionsInvalid);
+}
+
+/// Load the password printed by a helper command
+#[cfg(unix)]
+#[test]
+fn command_source_test() {
+    echo_with_source(CommandSource::new(1, "echo").arg(DEFAULT_PASSWORD));
+}
+
+/// Source which counts how many times credentials were requested
+struct CountingSource(Arc<AtomicUsize>);
+
+impl CredentialSource for CountingSource {
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(Credentials::default())
+    }
+}
+
+/// The source is asked for credentials again when reconnecting
+#[test]
+#[cfg(feature = "testing")]
+fn source_called_on_reconnect_test() {
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let mut client = ClientBuilder::new()
+        .connector(FaultyConnector::new(
+            MockHsm::new(),
+            FaultPlan::scripted(vec![None, None, Some(Fault::DropResponse)]),
+        ))
+        .credential_source(CountingSource(count.clone()))
+        .retry_policy(RetryPolicy {
+            backoff_ms: 1,
+            ..RetryPolicy::new(2)
+        })
+        .open()
+        .unwrap();
+
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+    assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+    assert_eq!(count.load(Ordering::SeqCst), 2);
+}
+
+/// Errors from the source are surfaced when opening a session
+#[test]
+fn source_error_test() {
+    let err = ClientBuilder::new()
+        .connector(MockHsm::new())
+        .credential_source(EnvSource::new(1, "YUBIHSM_TEST_MISSING_PASSWORD"))
+        .open()
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err.kind(),
+        ClientErrorKind::CredentialsError {
+            kind: CredentialsErrorKind::LoadFailed
+        }
+    );
+}
@@ -0,0 +1,52 @@
+use yubihsm::{Client, Credentials, HsmFleet, MockHsm, SerialNumber};
+
+/// Create a fleet of two MockHsms under made-up serial numbers
+fn create_fleet() -> (HsmFleet, SerialNumber, SerialNumber) {
+    let serial_a: SerialNumber = "0000000001".parse().unwrap();
+    let serial_b: SerialNumber = "0000000002".parse().unwrap();
+
+    let mut fleet = HsmFleet::new();
+
+    for serial in &[serial_a, serial_b] {
+        let client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+        fleet.insert(*serial, client).unwrap();
+    }
+
+    (fleet, serial_a, serial_b)
+}
+
+#[test]
+fn fleet_routing_test() {
+    let (mut fleet, _, serial_b) = create_fleet();
+
+    assert!(fleet.client_for_key(::TEST_KEY_ID).is_err());
+    fleet.route_key(::TEST_KEY_ID, serial_b).unwrap();
+
+    let client = fleet.client_for_key(::TEST_KEY_ID).unwrap();
+    assert_eq!(client.echo(::TEST_MESSAGE).unwrap(), ::TEST_MESSAGE);
+
+    assert!(fleet.remove(serial_b).is_some());
+    assert!(fleet.client_for_key(::TEST_KEY_ID).is_err());
+}
+
+#[test]
+fn fleet_fan_out_test() {
+    let (mut fleet, serial_a, serial_b) = create_fleet();
+
+    let results = fleet.storage_status_all();
+    assert_eq!(
+        results.keys().cloned().collect::<Vec<_>>(),
+        vec![serial_a, serial_b]
+    );
+
+    for result in results.values() {
+        assert_eq!(result.as_ref().unwrap().total_records, 256);
+    }
+}
+
+#[test]
+fn fleet_duplicate_serial_test() {
+    let (mut fleet, serial_a, _) = create_fleet();
+    let client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+    assert!(fleet.insert(serial_a, client).is_err());
+}
@@ -1,9 +1,15 @@
 //! Integration tests (using live YubiHSM2 or MockHsm)
 
+#[cfg(feature = "jose")]
+extern crate base64;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "signer")]
+extern crate p256;
 extern crate ring;
 extern crate sha2;
+#[cfg(feature = "signer")]
+extern crate signature;
 extern crate untrusted;
 extern crate yubihsm;
 
@@ -17,9 +23,61 @@ use yubihsm::MockHsm;
 use yubihsm::UsbConnector;
 use yubihsm::{AsymmetricAlg, Capability, Client, Connector, Domain, ObjectId, ObjectType};
 
+/// Integration tests for asymmetric (EC P-256) authentication
+#[cfg(all(feature = "mockhsm", feature = "asymmetric-auth"))]
+mod asymmetric_auth;
+
+/// Integration tests for whole-device backup and restore
+#[cfg(all(feature = "mockhsm", feature = "backup"))]
+mod backup;
+
 /// Integration tests for individual YubiHSM2 commands
 pub mod command;
 
+/// Integration tests for connectors which wrap other connectors
+#[cfg(feature = "mockhsm")]
+mod connector;
+
+/// Integration tests for COSE signatures and MACs
+#[cfg(all(feature = "mockhsm", feature = "cose"))]
+mod cose;
+
+/// Integration tests for loading credentials from sources
+#[cfg(feature = "mockhsm")]
+mod credentials;
+
+/// Integration tests for managing several HSMs at once
+#[cfg(feature = "mockhsm")]
+mod fleet;
+
+/// Integration tests for JWS/JWT signing
+#[cfg(all(feature = "mockhsm", feature = "jose"))]
+mod jose;
+
+/// Integration tests for observing commands
+#[cfg(feature = "mockhsm")]
+mod observer;
+
+/// Integration tests for provisioning HSMs from a manifest
+#[cfg(feature = "mockhsm")]
+mod provision;
+
+/// Integration tests for replicating keys between HSMs
+#[cfg(feature = "mockhsm")]
+mod replication;
+
+/// Integration tests for rotating auth keys
+#[cfg(feature = "mockhsm")]
+mod rotate_auth_key;
+
+/// Integration tests for signers implementing the `signature` traits
+#[cfg(all(feature = "mockhsm", feature = "signer"))]
+mod signer;
+
+/// Integration tests for X.509 certificate signing requests
+#[cfg(feature = "x509")]
+mod x509;
+
 /// Cryptographic test vectors taken from standards documents
 mod test_vectors;
 
@@ -49,7 +107,7 @@ pub const EC_P256_PUBLIC_KEY_SIZE: usize = 64;
 
 lazy_static! {
     static ref HSM_CLIENT: Mutex<Client> =
-        { Mutex::new(Client::open(create_hsm_connector(), Default::default(), true).unwrap()) };
+        Mutex::new(Client::open(create_hsm_connector(), Default::default(), true).unwrap());
 }
 
 //
@@ -72,7 +130,7 @@ pub fn get_hsm_client() -> MutexGuard<'static, Client> {
 ///
 /// Panics if none of the above features are enabled
 #[allow(unreachable_code)]
-pub fn create_hsm_connector() -> Box<Connector> {
+pub fn create_hsm_connector() -> Box<dyn Connector> {
     // MockHSM has highest priority when testing
     #[cfg(feature = "mockhsm")]
     return create_mockhsm_connector();
@@ -93,20 +151,20 @@ pub fn create_hsm_connector() -> Box<Connector> {
 
 /// Connect to the HSM via HTTP using `yubihsm-connector`
 #[cfg(feature = "http")]
-pub fn create_http_connector() -> Box<Connector> {
+pub fn create_http_connector() -> Box<dyn Connector> {
     HttpConnector::new(&Default::default()).unwrap().into()
 }
 
 /// Connect to the HSM via USB
 #[cfg(feature = "usb")]
-pub fn create_usb_connector() -> Box<Connector> {
+pub fn create_usb_connector() -> Box<dyn Connector> {
     UsbConnector::new(&Default::default()).unwrap().into()
 }
 
 /// Create a mock HSM for testing in situations where a hardware device is
 /// unavailable/impractical (e.g. CI)
 #[cfg(feature = "mockhsm")]
-pub fn create_mockhsm_connector() -> Box<Connector> {
+pub fn create_mockhsm_connector() -> Box<dyn Connector> {
     MockHsm::default().into()
 }
 
@@ -137,7 +195,8 @@ pub fn generate_asymmetric_key(
             TEST_DOMAINS,
             capabilities,
             algorithm,
-        ).unwrap_or_else(|err| panic!("error generating asymmetric key: {}", err));
+        )
+        .unwrap_or_else(|err| panic!("error generating asymmetric key: {}", err));
 
     assert_eq!(key_id, TEST_KEY_ID);
 }
@@ -159,7 +218,8 @@ pub fn put_asymmetric_key<T: Into<Vec<u8>>>(
             capabilities,
             algorithm,
             data,
-        ).unwrap_or_else(|err| panic!("error putting asymmetric key: {}", err));
+        )
+        .unwrap_or_else(|err| panic!("error putting asymmetric key: {}", err));
 
     assert_eq!(key_id, TEST_KEY_ID);
 }
@@ -0,0 +1,135 @@
+use base64;
+use ring;
+use std::collections::BTreeMap;
+use untrusted;
+use yubihsm::jose::{JoseErrorKind, JwsAlg, JwsSigner};
+use yubihsm::{AsymmetricAlg, Capability, HmacAlg, ObjectType};
+
+use {
+    clear_test_key_slot, generate_asymmetric_key, TEST_DOMAINS, TEST_KEY_ID, TEST_KEY_LABEL,
+    TEST_MESSAGE,
+};
+
+/// Key ID to include in headers
+const TEST_KID: &str = "test-key";
+
+/// Split a compact JWS into its decoded header and payload, the signing
+/// input, and the decoded signature
+fn split_jws(jws: &str) -> (Vec<u8>, Vec<u8>, &str, Vec<u8>) {
+    let parts: Vec<&str> = jws.split('.').collect();
+    assert_eq!(parts.len(), 3);
+
+    let decode = |part: &str| base64::decode_config(part, base64::URL_SAFE_NO_PAD).unwrap();
+    let signing_input = &jws[..parts[0].len() + 1 + parts[1].len()];
+
+    (
+        decode(parts[0]),
+        decode(parts[1]),
+        signing_input,
+        decode(parts[2]),
+    )
+}
+
+/// Sign a JWT with an Ed25519 key and verify it
+#[test]
+fn eddsa_jwt_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let signer = JwsSigner::new(&mut client, TEST_KEY_ID, JwsAlg::EdDSA)
+        .unwrap()
+        .kid(TEST_KID);
+
+    let mut claims = BTreeMap::new();
+    claims.insert("iss", "yubihsm.rs");
+    claims.insert("sub", "test");
+
+    let jwt = signer.sign_jwt(&mut client, &claims).unwrap();
+    let (header, payload, signing_input, signature) = split_jws(&jwt);
+
+    assert_eq!(
+        header.as_slice(),
+        &br#"{"alg":"EdDSA","typ":"JWT","kid":"test-key"}"#[..]
+    );
+    assert_eq!(
+        payload.as_slice(),
+        &br#"{"iss":"yubihsm.rs","sub":"test"}"#[..]
+    );
+
+    let pubkey = client.get_pubkey(TEST_KEY_ID).unwrap();
+
+    ring::signature::verify(
+        &ring::signature::ED25519,
+        untrusted::Input::from(pubkey.bytes.as_ref()),
+        untrusted::Input::from(signing_input.as_bytes()),
+        untrusted::Input::from(&signature[..]),
+    )
+    .unwrap();
+
+    let jwk = signer.jwk(&mut client).unwrap();
+
+    assert_eq!(
+        jwk.to_json(),
+        format!(
+            r#"{{"kty":"OKP","crv":"Ed25519","x":"{}","alg":"EdDSA","kid":"test-key"}}"#,
+            base64::encode_config(&pubkey.bytes, base64::URL_SAFE_NO_PAD)
+        )
+    );
+}
+
+/// Sign a JWS with an HMAC key and check it against a locally computed tag
+#[test]
+fn hs256_jws_test() {
+    let mut client = ::get_hsm_client();
+    let hmac_key = [0x0b; 32];
+
+    clear_test_key_slot(&mut client, ObjectType::HMACKey);
+
+    client
+        .put_hmac_key(
+            TEST_KEY_ID,
+            TEST_KEY_LABEL.into(),
+            TEST_DOMAINS,
+            Capability::HMAC_DATA,
+            HmacAlg::SHA256,
+            &hmac_key[..],
+        )
+        .unwrap();
+
+    let signer = JwsSigner::new(&mut client, TEST_KEY_ID, JwsAlg::HS256).unwrap();
+    let jws = signer.sign(&mut client, TEST_MESSAGE).unwrap();
+    let (header, payload, signing_input, signature) = split_jws(&jws);
+
+    assert_eq!(header.as_slice(), &br#"{"alg":"HS256"}"#[..]);
+    assert_eq!(payload.as_slice(), TEST_MESSAGE);
+
+    let key = ring::hmac::SigningKey::new(&ring::digest::SHA256, &hmac_key);
+    let expected = ring::hmac::sign(&key, signing_input.as_bytes());
+    assert_eq!(signature.as_slice(), expected.as_ref());
+
+    // HMAC keys are secret, so they don't have JWKs
+    assert_eq!(
+        signer.jwk(&mut client).unwrap_err().kind(),
+        JoseErrorKind::AlgorithmUnsupported
+    );
+}
+
+/// Keys can't be used with algorithms they don't support
+#[test]
+fn algorithm_mismatch_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let err = JwsSigner::new(&mut client, TEST_KEY_ID, JwsAlg::ES256).unwrap_err();
+    assert_eq!(err.kind(), JoseErrorKind::AlgorithmInvalid);
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use yubihsm::observer::MetricsObserver;
+use yubihsm::{ClientBuilder, CommandCode, Credentials, MockHsm};
+
+/// Collect metrics about commands sent to the MockHsm
+#[test]
+fn metrics_observer_test() {
+    let observer = Arc::new(MetricsObserver::new());
+
+    let mut client = ClientBuilder::new()
+        .connector(MockHsm::new())
+        .credentials(Credentials::default())
+        .observer(observer.clone())
+        .open()
+        .unwrap();
+
+    for _ in 0..2 {
+        client.echo(::TEST_MESSAGE).unwrap();
+    }
+
+    let command_metrics = observer.command_metrics();
+    let echo_metrics = &command_metrics[&CommandCode::Echo];
+    assert_eq!(echo_metrics.count, 2);
+    assert_eq!(echo_metrics.errors, 0);
+    assert!(echo_metrics.bytes_sent > 2 * ::TEST_MESSAGE.len() as u64);
+    assert!(echo_metrics.bytes_received > 2 * ::TEST_MESSAGE.len() as u64);
+
+    let connect_metrics = observer.connect_metrics();
+    assert_eq!(connect_metrics.sessions_opened, 1);
+    assert_eq!(connect_metrics.reconnects, 0);
+
+    #[cfg(feature = "prometheus")]
+    assert!(observer
+        .to_prometheus()
+        .contains("yubihsm_commands_total{command=\"Echo\"} 2\n"));
+}
@@ -0,0 +1,166 @@
+use yubihsm::provision::{Change, Manifest, ManifestEntry, Plan};
+use yubihsm::{Client, Credentials, MockHsm, ObjectId, ObjectType};
+
+/// ID of the wrap key in the test manifest
+const TEST_WRAP_KEY_ID: ObjectId = 200;
+
+/// Manifest with a generated Ed25519 key and an imported wrap key
+fn test_manifest() -> Manifest {
+    Manifest {
+        asymmetric_keys: vec![ManifestEntry {
+            id: ::TEST_KEY_ID,
+            label: ::TEST_KEY_LABEL.to_owned(),
+            domains: "1".to_owned(),
+            capabilities: "sign-eddsa,exportable-under-wrap".to_owned(),
+            algorithm: Some("ed25519".to_owned()),
+            ..ManifestEntry::default()
+        }],
+        wrap_keys: vec![ManifestEntry {
+            id: TEST_WRAP_KEY_ID,
+            label: "provisioned wrap key".to_owned(),
+            domains: "all".to_owned(),
+            capabilities: "export-wrapped,import-wrapped".to_owned(),
+            delegated_capabilities: Some("sign-eddsa,exportable-under-wrap".to_owned()),
+            key: Some(
+                "000102030405060708090a0b0c0d0e0f000102030405060708090a0b0c0d0e0f".to_owned(),
+            ),
+            ..ManifestEntry::default()
+        }],
+        ..Manifest::default()
+    }
+}
+
+#[test]
+fn provision_apply_test() {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+    let manifest = test_manifest();
+
+    let plan = Plan::new(&mut client, &manifest).unwrap();
+    assert_eq!(plan.changes().len(), 2);
+    assert!(plan
+        .changes()
+        .iter()
+        .all(|change| matches!(*change, Change::Create(_))));
+
+    plan.apply(&mut client).unwrap();
+
+    let plan = Plan::new(&mut client, &manifest).unwrap();
+    assert!(plan.is_empty());
+    assert_eq!(plan.unchanged().len(), 2);
+}
+
+#[test]
+fn provision_drift_and_prune_test() {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+    let mut manifest = test_manifest();
+
+    Plan::new(&mut client, &manifest)
+        .unwrap()
+        .apply(&mut client)
+        .unwrap();
+
+    manifest.asymmetric_keys[0].label = "renamed key".to_owned();
+    manifest.wrap_keys.clear();
+    manifest.prune = true;
+
+    // Label-only differences are reported as drift, even with allow_replace
+    manifest.allow_replace = true;
+
+    let plan = Plan::new(&mut client, &manifest).unwrap();
+    assert_eq!(plan.changes().len(), 1);
+    assert!(!plan.has_replacements());
+
+    match plan.changes()[0] {
+        Change::Delete { object_type, .. } => assert_eq!(object_type, ObjectType::WrapKey),
+        ref other => panic!("unexpected change: {:?}", other),
+    }
+
+    assert_eq!(
+        plan.drifted(),
+        &[(ObjectType::AsymmetricKey, ::TEST_KEY_ID, vec!["label"])]
+    );
+
+    plan.apply(&mut client).unwrap();
+
+    let plan = Plan::new(&mut client, &manifest).unwrap();
+    assert!(plan.is_empty());
+    assert_eq!(plan.drifted().len(), 1);
+}
+
+#[test]
+fn provision_replace_test() {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+    let mut manifest = test_manifest();
+
+    Plan::new(&mut client, &manifest)
+        .unwrap()
+        .apply(&mut client)
+        .unwrap();
+
+    manifest.asymmetric_keys[0].capabilities = "sign-eddsa".to_owned();
+
+    // Keys are never replaced without allow_replace
+    let plan = Plan::new(&mut client, &manifest).unwrap();
+    assert!(plan.is_empty());
+    assert_eq!(
+        plan.drifted(),
+        &[(
+            ObjectType::AsymmetricKey,
+            ::TEST_KEY_ID,
+            vec!["capabilities"]
+        )]
+    );
+
+    manifest.allow_replace = true;
+
+    let plan = Plan::new(&mut client, &manifest).unwrap();
+    assert_eq!(plan.changes().len(), 1);
+
+    match plan.changes()[0] {
+        Change::Replace {
+            ref spec,
+            ref differences,
+        } => {
+            assert_eq!(spec.object_type, ObjectType::AsymmetricKey);
+            assert_eq!(differences, &["capabilities"]);
+        }
+        ref other => panic!("unexpected change: {:?}", other),
+    }
+
+    plan.apply(&mut client).unwrap();
+    assert!(Plan::new(&mut client, &manifest).unwrap().is_empty());
+}
+
+#[test]
+fn provision_never_replaces_session_auth_key_test() {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+
+    let manifest = Manifest {
+        allow_replace: true,
+        auth_keys: vec![ManifestEntry {
+            id: client.session().unwrap().auth_key_id(),
+            label: ::DEFAULT_AUTH_KEY_LABEL.to_owned(),
+            domains: "1".to_owned(),
+            capabilities: "get-pseudo-random".to_owned(),
+            password: Some("password".to_owned()),
+            ..ManifestEntry::default()
+        }],
+        ..Manifest::default()
+    };
+
+    let plan = Plan::new(&mut client, &manifest).unwrap();
+    assert!(plan.is_empty());
+    assert_eq!(plan.drifted().len(), 1);
+}
+
+#[test]
+fn provision_invalid_manifest_test() {
+    let mut manifest = test_manifest();
+    manifest.asymmetric_keys[0].capabilities = "not_a_capability".to_owned();
+    assert!(manifest.objects().is_err());
+
+    let mut manifest = test_manifest();
+    let duplicate = manifest.asymmetric_keys[0].clone();
+    manifest.asymmetric_keys.push(duplicate);
+    assert!(manifest.objects().is_err());
+}
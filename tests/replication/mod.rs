@@ -0,0 +1,108 @@
+use yubihsm::replication::replicate;
+use yubihsm::{
+    AsymmetricAlg, Capability, Client, Credentials, Domain, MockHsm, ObjectId, ObjectOrigin,
+    ObjectType, WrapAlg,
+};
+
+/// ID of the wrap key shared by both HSMs
+const TEST_WRAP_KEY_ID: ObjectId = 200;
+
+/// Create a MockHsm client with the shared wrap key
+fn create_test_client() -> Client {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+
+    client
+        .put_wrap_key(
+            TEST_WRAP_KEY_ID,
+            "replication wrap key".into(),
+            Domain::all(),
+            Capability::EXPORT_WRAPPED | Capability::IMPORT_WRAPPED,
+            Capability::all(),
+            WrapAlg::AES256_CCM,
+            vec![0x42u8; 32],
+        )
+        .unwrap();
+
+    client
+}
+
+#[test]
+fn replicate_test() {
+    let mut source = create_test_client();
+    let mut destination = create_test_client();
+    let objects = [(ObjectType::AsymmetricKey, ::TEST_KEY_ID)];
+
+    source
+        .generate_asymmetric_key(
+            ::TEST_KEY_ID,
+            ::TEST_KEY_LABEL.into(),
+            ::TEST_DOMAINS,
+            Capability::ASYMMETRIC_SIGN_EDDSA | Capability::EXPORT_UNDER_WRAP,
+            AsymmetricAlg::Ed25519,
+        )
+        .unwrap();
+
+    let report = replicate(&mut source, &mut destination, TEST_WRAP_KEY_ID, &objects).unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.replicated, objects.to_vec());
+
+    let info = destination
+        .get_object_info(::TEST_KEY_ID, ObjectType::AsymmetricKey)
+        .unwrap();
+
+    assert_eq!(info.origin, ObjectOrigin::WrappedGenerated);
+
+    // Replicating again should skip the existing copy
+    let report = replicate(&mut source, &mut destination, TEST_WRAP_KEY_ID, &objects).unwrap();
+    assert!(report.replicated.is_empty());
+    assert_eq!(report.skipped, objects.to_vec());
+}
+
+#[test]
+fn replicate_nonexportable_test() {
+    let mut source = create_test_client();
+    let mut destination = create_test_client();
+    let objects = [(ObjectType::AsymmetricKey, ::TEST_KEY_ID)];
+
+    source
+        .generate_asymmetric_key(
+            ::TEST_KEY_ID,
+            ::TEST_KEY_LABEL.into(),
+            ::TEST_DOMAINS,
+            Capability::ASYMMETRIC_SIGN_EDDSA,
+            AsymmetricAlg::Ed25519,
+        )
+        .unwrap();
+
+    let report = replicate(&mut source, &mut destination, TEST_WRAP_KEY_ID, &objects).unwrap();
+    assert!(report.replicated.is_empty());
+    assert_eq!(report.failed.len(), 1);
+    assert!(destination
+        .get_object_info(::TEST_KEY_ID, ObjectType::AsymmetricKey)
+        .is_err());
+}
+
+#[test]
+fn replicate_mismatched_existing_object_test() {
+    let mut source = create_test_client();
+    let mut destination = create_test_client();
+    let objects = [(ObjectType::AsymmetricKey, ::TEST_KEY_ID)];
+
+    // Generate unrelated keys with the same ID (and sequence) on both HSMs
+    for client in &mut [&mut source, &mut destination] {
+        client
+            .generate_asymmetric_key(
+                ::TEST_KEY_ID,
+                ::TEST_KEY_LABEL.into(),
+                ::TEST_DOMAINS,
+                Capability::ASYMMETRIC_SIGN_EDDSA | Capability::EXPORT_UNDER_WRAP,
+                AsymmetricAlg::Ed25519,
+            )
+            .unwrap();
+    }
+
+    // The existing key isn't a copy, so it must not be reported as skipped
+    let report = replicate(&mut source, &mut destination, TEST_WRAP_KEY_ID, &objects).unwrap();
+    assert!(report.skipped.is_empty());
+    assert_eq!(report.failed.len(), 1);
+}
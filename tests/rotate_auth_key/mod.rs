@@ -0,0 +1,118 @@
+#[cfg(feature = "testing")]
+use yubihsm::client::ClientErrorKind;
+#[cfg(feature = "testing")]
+use yubihsm::connector::{ConnectionErrorKind, Fault, FaultPlan, FaultyConnector};
+use yubihsm::{AuthKey, Client, Credentials, MockHsm, ObjectId, ObjectType};
+
+/// ID of the auth key to rotate to
+const NEW_AUTH_KEY_ID: ObjectId = 2;
+
+/// Password for the new auth key
+const NEW_AUTH_KEY_PASSWORD: &[u8] = b"yubihsm.rs rotated auth key";
+
+/// List the IDs of the auth keys in the HSM
+fn auth_key_ids(client: &mut Client) -> Vec<ObjectId> {
+    client
+        .list_objects()
+        .unwrap()
+        .iter()
+        .filter(|entry| entry.object_type == ObjectType::AuthKey)
+        .map(|entry| entry.object_id)
+        .collect()
+}
+
+/// Rotate the default auth key, failing the message with the given index
+/// (counting from the `Get Object Info` for the old key)
+#[cfg(feature = "testing")]
+fn rotate_with_fault(failed_message: usize) -> Client {
+    let connector = FaultyConnector::new(MockHsm::new(), FaultPlan::new());
+    let handle = connector.handle();
+    let mut client = Client::open(connector, Credentials::default(), true).unwrap();
+
+    let mut script = vec![None; failed_message];
+    script.push(Some(Fault::Error(ConnectionErrorKind::ConnectionFailed)));
+    handle.set_plan(FaultPlan::scripted(script));
+
+    let err = client
+        .rotate_auth_key(
+            1,
+            "rotated auth key".into(),
+            Credentials::from_password(NEW_AUTH_KEY_ID, NEW_AUTH_KEY_PASSWORD),
+        )
+        .unwrap_err();
+
+    assert_eq!(
+        err.kind(),
+        ClientErrorKind::ConnectionError {
+            kind: ConnectionErrorKind::ConnectionFailed
+        }
+    );
+    assert_eq!(handle.injected(), 1);
+
+    client
+}
+
+#[test]
+fn rotate_default_auth_key_test() {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+    assert!(client.has_default_auth_key().unwrap());
+
+    client
+        .rotate_auth_key(
+            1,
+            "rotated auth key".into(),
+            Credentials::new(
+                NEW_AUTH_KEY_ID,
+                AuthKey::derive_from_password(NEW_AUTH_KEY_PASSWORD),
+            ),
+        )
+        .unwrap();
+
+    // The client should keep working on a session with the new key
+    client.ping().unwrap();
+    assert!(!client.has_default_auth_key().unwrap());
+
+    assert_eq!(auth_key_ids(&mut client), vec![NEW_AUTH_KEY_ID]);
+}
+
+#[test]
+fn rotate_auth_key_same_id_test() {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+
+    assert!(client
+        .rotate_auth_key(
+            1,
+            "rotated auth key".into(),
+            Credentials::from_password(1, NEW_AUTH_KEY_PASSWORD),
+        )
+        .is_err());
+
+    assert!(client.has_default_auth_key().unwrap());
+}
+
+/// The new key is deleted again if a session can't be opened with it
+#[test]
+#[cfg(feature = "testing")]
+fn rotate_auth_key_open_session_failure_test() {
+    // Get Object Info, Put Auth Key, then fail Create Session
+    let mut client = rotate_with_fault(2);
+
+    // The client should keep working on its session with the old key
+    client.ping().unwrap();
+    assert!(client.has_default_auth_key().unwrap());
+    assert_eq!(auth_key_ids(&mut client), vec![1]);
+}
+
+/// The new key is deleted again if the old key can't be deleted
+#[test]
+#[cfg(feature = "testing")]
+fn rotate_auth_key_delete_failure_test() {
+    // Get Object Info, Put Auth Key, Create Session, Authenticate Session,
+    // then fail Delete Object (which also breaks the old session, so the
+    // rollback happens on a new session with the old key)
+    let mut client = rotate_with_fault(4);
+
+    client.ping().unwrap();
+    assert!(client.has_default_auth_key().unwrap());
+    assert_eq!(auth_key_ids(&mut client), vec![1]);
+}
@@ -0,0 +1,48 @@
+use p256;
+use ring;
+use signature::Signer;
+use std::sync::{Arc, Mutex};
+use untrusted;
+use yubihsm::signer::{EcdsaSigner, Ed25519Signer, SignerErrorKind};
+use yubihsm::{AsymmetricAlg, Capability, Client, Credentials, MockHsm};
+
+use {generate_asymmetric_key, TEST_KEY_ID, TEST_MESSAGE};
+
+/// Create a client shared between signers with an Ed25519 test key
+fn create_shared_client() -> Arc<Mutex<Client>> {
+    let mut client = Client::open(MockHsm::new(), Credentials::default(), true).unwrap();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    Arc::new(Mutex::new(client))
+}
+
+/// Sign a message with `Ed25519Signer` and verify it
+#[test]
+fn ed25519_signer_test() {
+    let signer = Ed25519Signer::create(create_shared_client(), TEST_KEY_ID).unwrap();
+    let pubkey = signer.public_key().unwrap();
+    let signature = signer.sign(TEST_MESSAGE);
+
+    ring::signature::verify(
+        &ring::signature::ED25519,
+        untrusted::Input::from(pubkey.bytes.as_ref()),
+        untrusted::Input::from(TEST_MESSAGE),
+        untrusted::Input::from(&signature.to_bytes()),
+    )
+    .unwrap();
+}
+
+/// Signers reject keys with a different algorithm
+#[test]
+fn ecdsa_signer_algorithm_mismatch_test() {
+    let err = EcdsaSigner::<p256::NistP256>::create(create_shared_client(), TEST_KEY_ID)
+        .err()
+        .unwrap();
+
+    assert_eq!(err.kind(), SignerErrorKind::AlgorithmInvalid);
+}
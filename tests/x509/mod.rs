@@ -0,0 +1,314 @@
+use ring;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+use untrusted;
+use yubihsm::x509::{
+    self, Certificate, CertificateAuthority, CertificateBuilder, CertificationRequest, CsrBuilder,
+    DistinguishedName, GeneralName, Validity, X509ErrorKind,
+};
+use yubihsm::{AsymmetricAlg, Capability, Client, ObjectType};
+
+use {clear_test_key_slot, generate_asymmetric_key, TEST_DOMAINS, TEST_KEY_ID, TEST_KEY_LABEL};
+
+/// DER encoding of the Ed25519 `AlgorithmIdentifier`
+const ED25519_ALGORITHM_ID: &[u8] = &[0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// P-256 CSR generated with OpenSSL for `O=Example, CN=example.com`,
+/// requesting the subject alternative name `DNS:example.com`
+const OPENSSL_P256_CSR: &[u8] = &[
+    0x30, 0x82, 0x01, 0x0c, 0x30, 0x81, 0xb3, 0x02, 0x01, 0x00, 0x30, 0x28, 0x31, 0x10, 0x30, 0x0e,
+    0x06, 0x03, 0x55, 0x04, 0x0a, 0x0c, 0x07, 0x45, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x31, 0x14,
+    0x30, 0x12, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x0b, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65,
+    0x2e, 0x63, 0x6f, 0x6d, 0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02,
+    0x01, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04, 0xf1,
+    0x96, 0x55, 0x14, 0x56, 0x83, 0x1a, 0xa2, 0x46, 0x48, 0x18, 0xcd, 0x93, 0x6d, 0x7c, 0x2a, 0x1e,
+    0xcc, 0x40, 0x29, 0xff, 0x84, 0xa1, 0xcd, 0xf2, 0x09, 0x5c, 0x5a, 0xb4, 0xc6, 0x1d, 0xef, 0x1f,
+    0xe7, 0xe4, 0x0b, 0x5c, 0x7f, 0x8f, 0x3e, 0x41, 0x08, 0x49, 0xcd, 0x5e, 0x2a, 0x30, 0xfc, 0x2a,
+    0xe0, 0x65, 0x02, 0x20, 0x55, 0x5c, 0x38, 0xad, 0x7e, 0xab, 0xab, 0xc7, 0x3c, 0xf7, 0x87, 0xa0,
+    0x29, 0x30, 0x27, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x0e, 0x31, 0x1a,
+    0x30, 0x18, 0x30, 0x16, 0x06, 0x03, 0x55, 0x1d, 0x11, 0x04, 0x0f, 0x30, 0x0d, 0x82, 0x0b, 0x65,
+    0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x2e, 0x63, 0x6f, 0x6d, 0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86,
+    0x48, 0xce, 0x3d, 0x04, 0x03, 0x02, 0x03, 0x48, 0x00, 0x30, 0x45, 0x02, 0x21, 0x00, 0xd0, 0x6f,
+    0xad, 0x14, 0x99, 0xf9, 0xf9, 0x1b, 0xce, 0x7c, 0x46, 0x91, 0x30, 0xa8, 0xcd, 0xf4, 0xc8, 0xaa,
+    0x74, 0xa1, 0x6f, 0x15, 0x4d, 0xe4, 0x7a, 0x9e, 0x1d, 0xc7, 0xc7, 0x07, 0xf5, 0xc5, 0x02, 0x20,
+    0x77, 0x2c, 0x6b, 0xec, 0x2b, 0xef, 0xf1, 0x6a, 0xf4, 0x2f, 0xb8, 0xc9, 0x36, 0xe9, 0xc5, 0xaf,
+    0xcd, 0x69, 0x5c, 0x6d, 0xd8, 0xf9, 0xd0, 0x18, 0xda, 0x53, 0x02, 0x94, 0x98, 0x58, 0xc1, 0xe5,
+];
+
+/// Offset of the "E" of "Example" in `OPENSSL_P256_CSR`
+const OPENSSL_P256_CSR_SUBJECT_OFFSET: usize = 23;
+
+/// Split the first DER element off the given bytes, returning its tag, its
+/// value, the whole element, and the remaining bytes
+fn split_element(bytes: &[u8]) -> (u8, &[u8], &[u8], &[u8]) {
+    let (header_len, len) = if bytes[1] < 0x80 {
+        (2, bytes[1] as usize)
+    } else {
+        let len_bytes = (bytes[1] & 0x7f) as usize;
+        let len = bytes[2..2 + len_bytes]
+            .iter()
+            .fold(0, |len, &b| len << 8 | b as usize);
+        (2 + len_bytes, len)
+    };
+
+    let end = header_len + len;
+    (
+        bytes[0],
+        &bytes[header_len..end],
+        &bytes[..end],
+        &bytes[end..],
+    )
+}
+
+/// Split a signed structure (e.g. a CSR or certificate) into the signed
+/// data, signature algorithm, and signature
+fn split_signed(bytes: &[u8]) -> (&[u8], &[u8], &[u8]) {
+    let (tag, body, _, rest) = split_element(bytes);
+    assert_eq!(tag, 0x30);
+    assert!(rest.is_empty());
+
+    let (_, _, signed, body) = split_element(body);
+    let (_, _, algorithm_id, body) = split_element(body);
+    let (tag, signature, _, body) = split_element(body);
+
+    assert_eq!(tag, 0x03);
+    assert_eq!(signature[0], 0);
+    assert!(body.is_empty());
+
+    (signed, algorithm_id, &signature[1..])
+}
+
+/// Verify a signed structure was signed by the Ed25519 test key
+fn verify_ed25519(client: &mut Client, bytes: &[u8]) {
+    let pubkey = client.get_pubkey(TEST_KEY_ID).unwrap();
+    let (signed, algorithm_id, signature) = split_signed(bytes);
+
+    assert_eq!(algorithm_id, ED25519_ALGORITHM_ID);
+
+    ring::signature::verify(
+        &ring::signature::ED25519,
+        untrusted::Input::from(pubkey.bytes.as_ref()),
+        untrusted::Input::from(signed),
+        untrusted::Input::from(signature),
+    )
+    .unwrap();
+}
+
+/// Generate a CSR for the test key
+fn generate_csr(client: &mut Client) -> CertificationRequest {
+    CsrBuilder::new(DistinguishedName::new().common_name("leaf.example.com"))
+        .subject_alt_name(GeneralName::DnsName("leaf.example.com".to_owned()))
+        .sign(client, TEST_KEY_ID)
+        .unwrap()
+}
+
+/// Issue a self-signed certificate for the test key
+fn self_signed_certificate(client: &mut Client) -> Certificate {
+    let subject = DistinguishedName::new().common_name("hsm.example.com");
+    let ca = CertificateAuthority::new(client, TEST_KEY_ID, &subject).unwrap();
+
+    ca.self_signed(client, Validity::from_now(Duration::from_secs(86_400)))
+        .unwrap()
+}
+
+/// Generate a CSR for an Ed25519 key and verify its signature
+#[test]
+fn ed25519_csr_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let pubkey = client.get_pubkey(TEST_KEY_ID).unwrap();
+
+    let subject = DistinguishedName::new()
+        .country("US")
+        .organization("Example")
+        .common_name("hsm.example.com");
+
+    let csr = CsrBuilder::new(subject)
+        .subject_alt_name(GeneralName::DnsName("hsm.example.com".to_owned()))
+        .subject_alt_name(GeneralName::IpAddress(IpAddr::V4(Ipv4Addr::new(
+            10, 0, 0, 1,
+        ))))
+        .sign(&mut client, TEST_KEY_ID)
+        .unwrap();
+
+    verify_ed25519(&mut client, csr.as_der());
+
+    // The request includes the key's SubjectPublicKeyInfo
+    let (info, _, _) = split_signed(csr.as_der());
+    let spki = pubkey.to_spki_der();
+    assert!(info.windows(spki.len()).any(|window| window == &spki[..]));
+
+    let pem = csr.to_pem();
+    assert!(pem.starts_with("-----BEGIN CERTIFICATE REQUEST-----\n"));
+}
+
+/// Issue a self-signed root certificate and a leaf certificate from a CSR
+#[test]
+fn certificate_authority_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let ca_name = DistinguishedName::new()
+        .organization("Example")
+        .common_name("Example Root CA");
+
+    let ca = CertificateAuthority::new(&mut client, TEST_KEY_ID, &ca_name).unwrap();
+    let validity = Validity::from_now(Duration::from_secs(86_400));
+
+    let root = ca.self_signed(&mut client, validity).unwrap();
+    verify_ed25519(&mut client, root.as_der());
+    assert!(root.to_pem().starts_with("-----BEGIN CERTIFICATE-----\n"));
+
+    let csr = generate_csr(&mut client);
+    let builder = CertificateBuilder::from_csr(&csr, validity)
+        .unwrap()
+        .serial_number(vec![0x01, 0x02, 0x03])
+        .unwrap();
+
+    let leaf = ca.issue(&mut client, &builder).unwrap();
+    verify_ed25519(&mut client, leaf.as_der());
+}
+
+/// CSRs with invalid signatures are rejected
+#[test]
+fn tampered_csr_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let mut der = generate_csr(&mut client).into_der();
+    let last = der.len() - 1;
+    der[last] ^= 0xff;
+
+    let csr = CertificationRequest::from_der(der).unwrap();
+    let validity = Validity::from_now(Duration::from_secs(86_400));
+    let err = CertificateBuilder::from_csr(&csr, validity).unwrap_err();
+
+    assert_eq!(err.kind(), X509ErrorKind::SignatureInvalid);
+}
+
+/// CSRs generated by OpenSSL are accepted, and rejected once tampered with
+#[test]
+fn openssl_csr_test() {
+    let validity = Validity::from_now(Duration::from_secs(86_400));
+
+    let csr = CertificationRequest::from_der(OPENSSL_P256_CSR).unwrap();
+    assert!(CertificateBuilder::from_csr(&csr, validity).is_ok());
+
+    let mut der = OPENSSL_P256_CSR.to_vec();
+    der[OPENSSL_P256_CSR_SUBJECT_OFFSET] = b'e';
+
+    let csr = CertificationRequest::from_der(der).unwrap();
+    let err = CertificateBuilder::from_csr(&csr, validity).unwrap_err();
+    assert_eq!(err.kind(), X509ErrorKind::SignatureInvalid);
+}
+
+/// Serial numbers must be positive and at most 20 bytes (RFC 5280)
+#[test]
+fn serial_number_test() {
+    let name = DistinguishedName::new().common_name("example.com");
+    let validity = Validity::from_now(Duration::from_secs(86_400));
+    let builder = CertificateBuilder::new(&name, vec![], validity);
+
+    for serial_number in &[vec![0x01], vec![0x00, 0x01], vec![0x7f; 20]] {
+        assert!(builder.clone().serial_number(serial_number.clone()).is_ok());
+    }
+
+    for serial_number in &[vec![], vec![0x00, 0x00], vec![0x80; 20], vec![0x01; 21]] {
+        let err = builder
+            .clone()
+            .serial_number(serial_number.clone())
+            .unwrap_err();
+
+        assert_eq!(err.kind(), X509ErrorKind::SerialNumberInvalid);
+    }
+}
+
+/// Store a certificate alongside its key and read it back
+#[test]
+fn certificate_storage_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    clear_test_key_slot(&mut client, ObjectType::Opaque);
+
+    let certificate = self_signed_certificate(&mut client);
+
+    let object_id = x509::put_certificate(
+        &mut client,
+        TEST_KEY_ID,
+        TEST_KEY_LABEL.into(),
+        TEST_DOMAINS,
+        &certificate,
+    )
+    .unwrap();
+
+    assert_eq!(object_id, TEST_KEY_ID);
+    assert_eq!(
+        x509::get_certificate(&mut client, TEST_KEY_ID).unwrap(),
+        certificate
+    );
+}
+
+/// Certificates for a different public key are rejected
+#[test]
+fn certificate_storage_mismatch_test() {
+    let mut client = ::get_hsm_client();
+
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    clear_test_key_slot(&mut client, ObjectType::Opaque);
+
+    let certificate = self_signed_certificate(&mut client);
+
+    // Replace the key the certificate was issued for
+    generate_asymmetric_key(
+        &mut client,
+        AsymmetricAlg::Ed25519,
+        Capability::ASYMMETRIC_SIGN_EDDSA,
+    );
+
+    let err = x509::put_certificate(
+        &mut client,
+        TEST_KEY_ID,
+        TEST_KEY_LABEL.into(),
+        TEST_DOMAINS,
+        &certificate,
+    )
+    .unwrap_err();
+
+    assert_eq!(err.kind(), X509ErrorKind::PublicKeyMismatch);
+}
+
+/// Malformed certificates are rejected
+#[test]
+fn certificate_from_der_test() {
+    assert_eq!(
+        Certificate::from_der(vec![0x30, 0x00]).unwrap_err().kind(),
+        X509ErrorKind::DerInvalid
+    );
+}